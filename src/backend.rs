@@ -0,0 +1,293 @@
+pub mod raster;
+pub mod svg;
+
+use std::path::Path;
+
+use image::RgbaImage;
+
+use crate::{
+    error::Result,
+    font::{BitmapFont, Glyph, TextStyle},
+    geometry::{Point, Rectangle, Transform},
+    layout::{Layout, LegendEntry},
+    visual::Color,
+};
+
+pub use raster::RasterBackend;
+pub use svg::SvgBackend;
+
+/// Longer side of the data area when no size is requested
+pub const DEFAULT_IMAGE_SIZE: u32 = 800;
+/// Longest side of the data area, larger sizes are scaled down to it
+pub const MAX_IMAGE_DIMENSION: u32 = 5_000;
+/// Data area size (in pixels) that corresponds to one pixel of point size
+const POINT_SIZE_STEP: u32 = 150;
+
+#[derive(Debug, Clone)]
+pub enum LabelContent {
+    Text(String),
+    Bitmap(Glyph),
+}
+
+/// Text anchored to a point of the field, its size is set in output pixels.
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub anchor: Point,
+    pub content: LabelContent,
+    pub color: Color,
+    pub style: TextStyle,
+}
+
+/// Target of the plot drawing calls. Coordinates are given in field units.
+pub trait DrawingBackend {
+    /// Fails if the point was not drawn: it is out of the field or it overlaps
+    /// another one and `do_not_override` is set
+    fn draw_point(&mut self, point: Point, color: Color, do_not_override: bool) -> Result<()>;
+
+    /// Connected line through all of the points
+    fn draw_polyline(&mut self, points: &[Point], color: Color);
+
+    fn draw_label(&mut self, label: Label);
+
+    /// Filled rectangle under everything else on the data area. Regions do not
+    /// take the place of points, so they never cause overlaps.
+    fn fill_region(&mut self, rect: &Rectangle, color: Color);
+
+    /// Writes the picture with the layout decorations around the data area.
+    fn save(
+        &self,
+        path: &Path,
+        layout: &Layout,
+        legend: &[LegendEntry],
+        font: &BitmapFont,
+    ) -> std::io::Result<()>;
+
+    /// Picture as it would be saved now, `None` if the backend does not produce pixels.
+    fn snapshot(
+        &self,
+        layout: &Layout,
+        legend: &[LegendEntry],
+        font: &BitmapFont,
+    ) -> Option<RgbaImage>;
+
+    fn box_clone(&self) -> Box<dyn DrawingBackend>;
+}
+
+impl Clone for Box<dyn DrawingBackend> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+/// Size of the data area of the output picture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    /// Size in pixels; a missing side follows the aspect ratio of the field,
+    /// without both the longer side is [`DEFAULT_IMAGE_SIZE`]
+    Pixels {
+        width: Option<u32>,
+        height: Option<u32>,
+    },
+    /// Pixels per one field unit along both axes
+    PixelsPerUnit(f32),
+    /// Printed size in inches at `dpi` dots per inch
+    Inches { width: f32, height: f32, dpi: f32 },
+}
+
+impl Default for Resolution {
+    fn default() -> Self {
+        Resolution::Pixels {
+            width: None,
+            height: None,
+        }
+    }
+}
+
+impl Resolution {
+    /// Size of the data area in pixels (width; height) for the field, at least 1x1.
+    /// Sides beyond [`MAX_IMAGE_DIMENSION`] are scaled down with the aspect ratio kept.
+    pub fn size(&self, rect: &Rectangle) -> (u32, u32) {
+        let (width, height) = self.requested_size(rect);
+        let longest = width.max(height);
+        let scale = if longest > MAX_IMAGE_DIMENSION as f32 {
+            MAX_IMAGE_DIMENSION as f32 / longest
+        } else {
+            1.0
+        };
+        (
+            ((width * scale).round() as u32).clamp(1, MAX_IMAGE_DIMENSION),
+            ((height * scale).round() as u32).clamp(1, MAX_IMAGE_DIMENSION),
+        )
+    }
+
+    /// Whether the requested size is larger than [`MAX_IMAGE_DIMENSION`] allows
+    pub fn is_downscaled(&self, rect: &Rectangle) -> bool {
+        let (width, height) = self.requested_size(rect);
+        width.max(height) > MAX_IMAGE_DIMENSION as f32
+    }
+
+    /// Size as asked for, non-finite sides are taken as too large
+    fn requested_size(&self, rect: &Rectangle) -> (f32, f32) {
+        let aspect_ratio = if rect.height() > 0.0 && rect.width() > 0.0 {
+            rect.width() / rect.height()
+        } else {
+            1.0
+        };
+        let (width, height) = match *self {
+            Resolution::Pixels {
+                width: Some(width),
+                height: Some(height),
+            } => (width as f32, height as f32),
+            Resolution::Pixels {
+                width: Some(width),
+                height: None,
+            } => (width as f32, width as f32 / aspect_ratio),
+            Resolution::Pixels {
+                width: None,
+                height: Some(height),
+            } => (height as f32 * aspect_ratio, height as f32),
+            Resolution::Pixels {
+                width: None,
+                height: None,
+            } => {
+                let size = DEFAULT_IMAGE_SIZE as f32;
+                if aspect_ratio >= 1.0 {
+                    (size, size / aspect_ratio)
+                } else {
+                    (size * aspect_ratio, size)
+                }
+            }
+            Resolution::PixelsPerUnit(pixels) => (rect.width() * pixels, rect.height() * pixels),
+            Resolution::Inches { width, height, dpi } => (width * dpi, height * dpi),
+        };
+        let finite = |side: f32| {
+            if side.is_finite() {
+                side.max(0.0)
+            } else {
+                f32::MAX
+            }
+        };
+        (finite(width), finite(height))
+    }
+}
+
+/// Field shown on a `width`x`height` pixel data area
+#[derive(Debug, Clone, PartialEq)]
+pub struct Viewport {
+    pub rect: Rectangle,
+    pub width: u32,
+    pub height: u32,
+    transform: Transform,
+}
+
+impl Viewport {
+    /// Sizes beyond [`MAX_IMAGE_DIMENSION`] are scaled down without a word,
+    /// callers that care ask [`Resolution::is_downscaled`] beforehand
+    pub fn new(rect: Rectangle, resolution: &Resolution) -> Self {
+        let (width, height) = resolution.size(&rect);
+        let transform = Transform::to_pixels(&rect, width, height);
+        Self {
+            rect,
+            width,
+            height,
+            transform,
+        }
+    }
+
+    /// Field to pixels map, the Y axis of the pixels points down
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    /// Field units covered by one pixel (X; Y)
+    pub fn units_per_pixel(&self) -> (f32, f32) {
+        (
+            self.rect.width() / self.width as f32,
+            self.rect.height() / self.height as f32,
+        )
+    }
+
+    /// Position of the point in pixels, the Y axis points down
+    pub fn to_pixel(&self, point: Point) -> (f32, f32) {
+        let pixel = self.transform.apply(point);
+        (pixel.x, pixel.y)
+    }
+
+    /// Side of the square drawn for a point, in pixels
+    pub fn point_size(&self) -> u32 {
+        (self.width.min(self.height) / POINT_SIZE_STEP).max(1)
+    }
+
+    /// Width of the lines, in pixels
+    pub fn line_width(&self) -> u32 {
+        self.point_size().div_ceil(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        backend::{DEFAULT_IMAGE_SIZE, MAX_IMAGE_DIMENSION, Resolution, Viewport},
+        geometry::{Point, Rectangle},
+    };
+
+    fn rect(left: f32, bottom: f32, right: f32, top: f32) -> Rectangle {
+        Rectangle::new(Point::new(left, bottom), Point::new(right, top)).unwrap()
+    }
+
+    #[test]
+    fn missing_sides_follow_the_field() {
+        let field = rect(0.0, 0.0, 200.0, 100.0);
+        assert_eq!(
+            Resolution::default().size(&field),
+            (DEFAULT_IMAGE_SIZE, DEFAULT_IMAGE_SIZE / 2)
+        );
+        let height_only = Resolution::Pixels {
+            width: None,
+            height: Some(300),
+        };
+        assert_eq!(height_only.size(&field), (600, 300));
+        assert_eq!(Resolution::PixelsPerUnit(2.0).size(&field), (400, 200));
+        let inches = Resolution::Inches {
+            width: 4.0,
+            height: 3.0,
+            dpi: 100.0,
+        };
+        assert_eq!(inches.size(&field), (400, 300));
+    }
+
+    #[test]
+    fn large_sizes_are_scaled_down_with_the_aspect_ratio() {
+        let field = rect(0.0, 0.0, 200.0, 100.0);
+        let huge = Resolution::PixelsPerUnit(100.0);
+        assert!(huge.is_downscaled(&field));
+        assert_eq!(
+            huge.size(&field),
+            (MAX_IMAGE_DIMENSION, MAX_IMAGE_DIMENSION / 2)
+        );
+
+        let viewport = Viewport::new(field, &huge);
+        assert_eq!(viewport.width, MAX_IMAGE_DIMENSION);
+        assert_eq!(viewport.height, MAX_IMAGE_DIMENSION / 2);
+        assert!(!Resolution::default().is_downscaled(&viewport.rect));
+    }
+
+    #[test]
+    fn field_corners_map_to_the_picture_corners() {
+        let field = rect(-10.0, -5.0, 30.0, 15.0);
+        let resolution = Resolution::Pixels {
+            width: Some(400),
+            height: Some(200),
+        };
+        let viewport = Viewport::new(field, &resolution);
+
+        assert_eq!(viewport.to_pixel(Point::new(-10.0, 15.0)), (0.0, 0.0));
+        assert_eq!(viewport.to_pixel(Point::new(30.0, -5.0)), (400.0, 200.0));
+        assert_eq!(viewport.to_pixel(Point::new(10.0, 5.0)), (200.0, 100.0));
+        // The Y axis of the picture points down
+        assert!(
+            viewport.to_pixel(Point::new(0.0, 10.0)).1 < viewport.to_pixel(Point::new(0.0, 0.0)).1
+        );
+        assert_eq!(viewport.units_per_pixel(), (0.1, 0.1));
+    }
+}
@@ -0,0 +1,344 @@
+use std::path::Path;
+
+use image::{Pixel, RgbaImage, imageops::overlay};
+
+use crate::{
+    backend::{DrawingBackend, Label, LabelContent, Viewport},
+    error::{Error, Result},
+    font::{BitmapFont, Glyph},
+    geometry::{Point, Rectangle},
+    layout::{Arrangement, Layout, LegendEntry},
+    visual::Color,
+};
+
+/// Draws on an `RgbaImage` of the output size, points are squares of
+/// [`Viewport::point_size`] pixels centered on their position.
+#[derive(Clone)]
+pub struct RasterBackend {
+    inner: RgbaImage,
+    viewport: Viewport,
+    background: Color,
+    labels: Vec<Label>,
+    /// Regions shown where the data area still has the background color
+    regions: Option<RgbaImage>,
+}
+
+impl RasterBackend {
+    pub fn new(viewport: Viewport, background: Option<Color>) -> Self {
+        let background = background.unwrap_or_else(|| Color::rgba(0, 0, 0, 0));
+        Self {
+            inner: RgbaImage::from_pixel(viewport.width, viewport.height, background.inner()),
+            viewport,
+            background,
+            labels: Vec::new(),
+            regions: None,
+        }
+    }
+
+    /// Data area with the labels drawn on it
+    pub fn render(&self, font: &BitmapFont) -> RgbaImage {
+        let mut output = self.inner.clone();
+
+        if let Some(regions) = &self.regions {
+            for (pixel, region) in output.pixels_mut().zip(regions.pixels()) {
+                if region[3] > 0 && *pixel == self.background.inner() {
+                    pixel.blend(region);
+                }
+            }
+        }
+
+        for label in &self.labels {
+            let bitmap = match &label.content {
+                LabelContent::Text(text) => font.render(text, &label.style),
+                LabelContent::Bitmap(glyph) => glyph
+                    .scaled(label.style.scale.max(1) as usize)
+                    .rotated(label.style.rotation),
+            };
+            let (x, y) = self.viewport.to_pixel(label.anchor);
+            let (offset_x, offset_y) = label
+                .style
+                .top_left_offset(bitmap.width as u32, bitmap.height as u32);
+            draw_bitmap(
+                &mut output,
+                (x.round() as i32 + offset_x, y.round() as i32 + offset_y),
+                &bitmap,
+                label.color,
+            );
+        }
+
+        output
+    }
+
+    /// Fills a `size`x`size` square centered on the pixel position
+    fn stamp(&mut self, center: (f32, f32), size: u32, color: Color) {
+        let left = (center.0 - size as f32 / 2.0).round() as i64;
+        let top = (center.1 - size as f32 / 2.0).round() as i64;
+        for y in top..top + size as i64 {
+            for x in left..left + size as i64 {
+                if x < 0 || y < 0 {
+                    continue;
+                }
+                if let Some(pixel) = self.inner.get_pixel_mut_checked(x as u32, y as u32) {
+                    *pixel = color.inner();
+                }
+            }
+        }
+    }
+}
+
+impl DrawingBackend for RasterBackend {
+    fn draw_point(&mut self, point: Point, color: Color, do_not_override: bool) -> Result<()> {
+        let (x, y) = self.viewport.to_pixel(point);
+        let pixel = if x >= 0.0 && y >= 0.0 {
+            self.inner.get_pixel_checked(x as u32, y as u32)
+        } else {
+            None
+        };
+        let Some(pixel) = pixel else {
+            return Err(Error::PointOutOfField {
+                point,
+                rect: self.viewport.rect.clone(),
+            });
+        };
+        if do_not_override && *pixel != self.background.inner() {
+            return Err(Error::PointOverlap(point));
+        }
+        self.stamp((x, y), self.viewport.point_size(), color);
+        Ok(())
+    }
+
+    fn draw_polyline(&mut self, points: &[Point], color: Color) {
+        let line_width = self.viewport.line_width();
+        let pixels: Vec<(f32, f32)> = points
+            .iter()
+            .map(|point| self.viewport.to_pixel(*point))
+            .collect();
+
+        if let [pixel] = pixels.as_slice() {
+            self.stamp(*pixel, line_width, color);
+        }
+        for pair in pixels.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            if !start.0.is_finite()
+                || !start.1.is_finite()
+                || !end.0.is_finite()
+                || !end.1.is_finite()
+            {
+                continue;
+            }
+            // Clipped to the canvas, so that steep graphs do not step far outside of it
+            let Some((start, end)) = clip_segment(
+                start,
+                end,
+                self.inner.width() as f32,
+                self.inner.height() as f32,
+            ) else {
+                continue;
+            };
+            // Half of a pixel, so that no pixel of the line is skipped
+            let length = (end.0 - start.0).hypot(end.1 - start.1);
+            let steps = (length * 2.0).ceil().max(1.0) as usize;
+            for i in 0..=steps {
+                let t = i as f32 / steps as f32;
+                let pixel = (
+                    start.0 + (end.0 - start.0) * t,
+                    start.1 + (end.1 - start.1) * t,
+                );
+                self.stamp(pixel, line_width, color);
+            }
+        }
+    }
+
+    fn draw_label(&mut self, label: Label) {
+        self.labels.push(label);
+    }
+
+    fn fill_region(&mut self, rect: &Rectangle, color: Color) {
+        let (width, height) = (self.inner.width(), self.inner.height());
+        // Rounded edges, so that adjacent regions share them without gaps
+        let (left, top) = self
+            .viewport
+            .to_pixel(Point::new(rect.bottom_left.x, rect.top_right.y));
+        let (right, bottom) = self
+            .viewport
+            .to_pixel(Point::new(rect.top_right.x, rect.bottom_left.y));
+        let clamp = |value: f32, max: u32| (value.round().max(0.0) as u32).min(max);
+        let (left, right) = (clamp(left, width), clamp(right, width));
+        let (top, bottom) = (clamp(top, height), clamp(bottom, height));
+
+        let regions = self
+            .regions
+            .get_or_insert_with(|| RgbaImage::new(width, height));
+        fill_rect(
+            regions,
+            left,
+            top,
+            right.saturating_sub(left),
+            bottom.saturating_sub(top),
+            color,
+        );
+    }
+
+    fn save(
+        &self,
+        path: &Path,
+        layout: &Layout,
+        legend: &[LegendEntry],
+        font: &BitmapFont,
+    ) -> std::io::Result<()> {
+        let output = self.snapshot(layout, legend, font).unwrap_or_default();
+        output.save(path).map_err(std::io::Error::other)
+    }
+
+    fn snapshot(
+        &self,
+        layout: &Layout,
+        legend: &[LegendEntry],
+        font: &BitmapFont,
+    ) -> Option<RgbaImage> {
+        let output = self.render(font);
+        if layout.is_empty() {
+            return Some(output);
+        }
+
+        let arrangement = layout.arrange(
+            output.width(),
+            output.height(),
+            legend,
+            self.background,
+            font,
+        );
+        Some(compose(&output, &arrangement, self.background, font))
+    }
+
+    fn box_clone(&self) -> Box<dyn DrawingBackend> {
+        Box::new(self.clone())
+    }
+}
+
+/// Places the data area on a canvas of the arranged size and draws the decorations.
+pub fn compose(
+    data: &RgbaImage,
+    arrangement: &Arrangement,
+    background: Color,
+    font: &BitmapFont,
+) -> RgbaImage {
+    let mut canvas =
+        RgbaImage::from_pixel(arrangement.width, arrangement.height, background.inner());
+    overlay(
+        &mut canvas,
+        data,
+        arrangement.data_origin.0 as i64,
+        arrangement.data_origin.1 as i64,
+    );
+
+    for placed in &arrangement.boxes {
+        fill_rect(
+            &mut canvas,
+            placed.x,
+            placed.y,
+            placed.width,
+            placed.height,
+            placed.fill,
+        );
+        if let Some(frame) = placed.frame {
+            draw_frame(
+                &mut canvas,
+                placed.x,
+                placed.y,
+                placed.width,
+                placed.height,
+                frame,
+            );
+        }
+    }
+    for placed in &arrangement.texts {
+        let bitmap = font.render(&placed.text, &placed.style);
+        let (x, y) = placed
+            .style
+            .top_left_offset(bitmap.width as u32, bitmap.height as u32);
+        draw_bitmap(
+            &mut canvas,
+            (placed.anchor.0 + x, placed.anchor.1 + y),
+            &bitmap,
+            placed.color,
+        );
+    }
+
+    canvas
+}
+
+/// Draws set pixels of the bitmap with its top left corner at `top_left`, pixels
+/// outside of the canvas are skipped.
+pub fn draw_bitmap(canvas: &mut RgbaImage, top_left: (i32, i32), bitmap: &Glyph, color: Color) {
+    for row in 0..bitmap.height {
+        for column in 0..bitmap.width {
+            if !bitmap.is_set(column, row) {
+                continue;
+            }
+            let x = top_left.0 + column as i32;
+            let y = top_left.1 + row as i32;
+            if x < 0 || y < 0 {
+                continue;
+            }
+            if let Some(pixel) = canvas.get_pixel_mut_checked(x as u32, y as u32) {
+                *pixel = color.inner();
+            }
+        }
+    }
+}
+
+/// Part of the segment inside of the `[0; width]`x`[0; height]` box
+/// (Liang–Barsky), `None` if the segment misses it.
+fn clip_segment(
+    start: (f32, f32),
+    end: (f32, f32),
+    width: f32,
+    height: f32,
+) -> Option<((f32, f32), (f32, f32))> {
+    let delta = (end.0 - start.0, end.1 - start.1);
+    let (mut enter, mut exit) = (0.0_f32, 1.0_f32);
+    for (p, q) in [
+        (-delta.0, start.0),
+        (delta.0, width - start.0),
+        (-delta.1, start.1),
+        (delta.1, height - start.1),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                enter = enter.max(t);
+            } else {
+                exit = exit.min(t);
+            }
+        }
+    }
+    if enter > exit {
+        return None;
+    }
+    Some((
+        (start.0 + delta.0 * enter, start.1 + delta.1 * enter),
+        (start.0 + delta.0 * exit, start.1 + delta.1 * exit),
+    ))
+}
+
+pub fn fill_rect(canvas: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Color) {
+    for pixel_x in x..x + width {
+        for pixel_y in y..y + height {
+            if let Some(pixel) = canvas.get_pixel_mut_checked(pixel_x, pixel_y) {
+                *pixel = color.inner();
+            }
+        }
+    }
+}
+
+pub fn draw_frame(canvas: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Color) {
+    fill_rect(canvas, x, y, width, 1, color);
+    fill_rect(canvas, x, y + height - 1, width, 1, color);
+    fill_rect(canvas, x, y, 1, height, color);
+    fill_rect(canvas, x + width - 1, y, 1, height, color);
+}
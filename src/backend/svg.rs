@@ -0,0 +1,316 @@
+use std::{collections::HashSet, fmt::Write, fs, path::Path};
+
+use image::RgbaImage;
+
+use crate::{
+    backend::{DrawingBackend, Label, LabelContent, Viewport},
+    error::{Error, Result},
+    font::{BitmapFont, Glyph, Rotation, TextStyle},
+    geometry::{Point, Rectangle},
+    layout::{Layout, LegendEntry},
+    visual::Color,
+};
+
+/// Share of the em size taken by capital letters of the SVG font
+const CAP_HEIGHT: f32 = 0.72;
+const FONT_FAMILY: &str = "monospace";
+
+/// Writes the plot as an SVG document, every field unit is a square of output pixels.
+#[derive(Clone)]
+pub struct SvgBackend {
+    viewport: Viewport,
+    background: Option<Color>,
+    /// Data area elements, coordinates are in output pixels
+    elements: Vec<String>,
+    /// Elements under all of the others
+    regions: Vec<String>,
+    labels: Vec<Label>,
+    /// Field cells (floored coordinates) that already hold a point
+    occupied: HashSet<(i64, i64)>,
+}
+
+impl SvgBackend {
+    pub fn new(viewport: Viewport, background: Option<Color>) -> Self {
+        Self {
+            viewport,
+            background,
+            elements: Vec::new(),
+            regions: Vec::new(),
+            labels: Vec::new(),
+            occupied: HashSet::new(),
+        }
+    }
+}
+
+impl DrawingBackend for SvgBackend {
+    fn draw_point(&mut self, point: Point, color: Color, do_not_override: bool) -> Result<()> {
+        let (x, y) = self.viewport.to_pixel(point);
+        if !(0.0..self.viewport.width as f32).contains(&x)
+            || !(0.0..self.viewport.height as f32).contains(&y)
+        {
+            return Err(Error::PointOutOfField {
+                point,
+                rect: self.viewport.rect.clone(),
+            });
+        }
+
+        // Same rule as on the raster picture: a point overlaps when its center pixel is taken
+        let key = (x.floor() as i64, y.floor() as i64);
+        if do_not_override && self.occupied.contains(&key) {
+            return Err(Error::PointOverlap(point));
+        }
+        let size = self.viewport.point_size() as i64;
+        let (left, top) = (key.0 - size / 2, key.1 - size / 2);
+        for cell_y in top..top + size {
+            for cell_x in left..left + size {
+                self.occupied.insert((cell_x, cell_y));
+            }
+        }
+
+        self.elements.push(format!(
+            r#"<rect x="{:.2}" y="{:.2}" width="{}" height="{}" {}/>"#,
+            x - size as f32 / 2.0,
+            y - size as f32 / 2.0,
+            size,
+            size,
+            fill_attributes(color)
+        ));
+        Ok(())
+    }
+
+    fn draw_polyline(&mut self, points: &[Point], color: Color) {
+        if points.is_empty() {
+            return;
+        }
+        let mut coordinates = String::new();
+        for point in points {
+            let (x, y) = self.viewport.to_pixel(*point);
+            if x.is_finite() && y.is_finite() {
+                let _ = write!(coordinates, "{:.2},{:.2} ", x, y);
+            }
+        }
+        self.elements.push(format!(
+            r#"<polyline points="{}" fill="none" {} stroke-width="{}"/>"#,
+            coordinates.trim_end(),
+            stroke_attributes(color),
+            self.viewport.line_width()
+        ));
+    }
+
+    fn draw_label(&mut self, label: Label) {
+        self.labels.push(label);
+    }
+
+    fn fill_region(&mut self, rect: &Rectangle, color: Color) {
+        let (left, top) = self
+            .viewport
+            .to_pixel(Point::new(rect.bottom_left.x, rect.top_right.y));
+        let (right, bottom) = self
+            .viewport
+            .to_pixel(Point::new(rect.top_right.x, rect.bottom_left.y));
+        self.regions.push(format!(
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" {}/>"#,
+            left,
+            top,
+            right - left,
+            bottom - top,
+            fill_attributes(color)
+        ));
+    }
+
+    fn save(
+        &self,
+        path: &Path,
+        layout: &Layout,
+        legend: &[LegendEntry],
+        font: &BitmapFont,
+    ) -> std::io::Result<()> {
+        let background = self
+            .background
+            .unwrap_or_else(|| Color::rgba(255, 255, 255, 0));
+        let arrangement = layout.arrange(
+            self.viewport.width,
+            self.viewport.height,
+            legend,
+            background,
+            font,
+        );
+
+        let mut document = String::new();
+        let _ = writeln!(
+            document,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            arrangement.width, arrangement.height
+        );
+        if let Some(background) = self.background {
+            let _ = writeln!(
+                document,
+                r#"<rect width="100%" height="100%" {}/>"#,
+                fill_attributes(background)
+            );
+        }
+
+        // Everything outside of the data area is cut off, as on the raster picture
+        let _ = writeln!(
+            document,
+            r#"<clipPath id="data-area"><rect width="{}" height="{}"/></clipPath>"#,
+            self.viewport.width, self.viewport.height
+        );
+        let _ = writeln!(
+            document,
+            r#"<g transform="translate({} {})" clip-path="url(#data-area)">"#,
+            arrangement.data_origin.0, arrangement.data_origin.1
+        );
+        for element in self.regions.iter().chain(&self.elements) {
+            let _ = writeln!(document, "{}", element);
+        }
+        for label in &self.labels {
+            let (x, y) = self.viewport.to_pixel(label.anchor);
+            let element = match &label.content {
+                LabelContent::Text(text) => {
+                    text_element((x, y), text, label.color, &label.style, font)
+                }
+                LabelContent::Bitmap(glyph) => {
+                    bitmap_element((x, y), glyph, label.color, &label.style)
+                }
+            };
+            let _ = writeln!(document, "{}", element);
+        }
+        let _ = writeln!(document, "</g>");
+
+        for placed in &arrangement.boxes {
+            let frame = placed
+                .frame
+                .map(|frame| format!(r#" {} stroke-width="1""#, stroke_attributes(frame)))
+                .unwrap_or_default();
+            let _ = writeln!(
+                document,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" {}{}/>"#,
+                placed.x,
+                placed.y,
+                placed.width,
+                placed.height,
+                fill_attributes(placed.fill),
+                frame
+            );
+        }
+        for placed in &arrangement.texts {
+            let anchor = (placed.anchor.0 as f32, placed.anchor.1 as f32);
+            let element = text_element(anchor, &placed.text, placed.color, &placed.style, font);
+            let _ = writeln!(document, "{}", element);
+        }
+        let _ = writeln!(document, "</svg>");
+
+        fs::write(path, document)
+    }
+
+    fn snapshot(
+        &self,
+        _layout: &Layout,
+        _legend: &[LegendEntry],
+        _font: &BitmapFont,
+    ) -> Option<RgbaImage> {
+        None
+    }
+
+    fn box_clone(&self) -> Box<dyn DrawingBackend> {
+        Box::new(self.clone())
+    }
+}
+
+/// Text stretched over the same box the bitmap font would take.
+fn text_element(
+    anchor: (f32, f32),
+    text: &str,
+    color: Color,
+    style: &TextStyle,
+    font: &BitmapFont,
+) -> String {
+    let (width, height) = font.measure(text, style);
+    let (offset_x, offset_y) = style.top_left_offset(width, height);
+    let left = anchor.0 + offset_x as f32;
+    let top = anchor.1 + offset_y as f32;
+
+    // Length of the line and height of the letters before the rotation
+    let (length, letters_height, transform) = match style.rotation {
+        Rotation::None => (
+            width,
+            height,
+            format!("translate({:.2} {:.2})", left, top + height as f32),
+        ),
+        Rotation::Clockwise => (
+            height,
+            width,
+            format!("translate({:.2} {:.2}) rotate(90)", left, top),
+        ),
+        Rotation::CounterClockwise => (
+            height,
+            width,
+            format!(
+                "translate({:.2} {:.2}) rotate(-90)",
+                left + width as f32,
+                top + height as f32
+            ),
+        ),
+    };
+    let weight = if style.bold { "bold" } else { "normal" };
+
+    format!(
+        r#"<text transform="{}" font-family="{}" font-size="{:.2}" font-weight="{}" textLength="{}" lengthAdjust="spacingAndGlyphs" {}>{}</text>"#,
+        transform,
+        FONT_FAMILY,
+        letters_height as f32 / CAP_HEIGHT,
+        weight,
+        length,
+        fill_attributes(color),
+        escape(text)
+    )
+}
+
+/// Glyph drawn as a path of its pixels.
+fn bitmap_element(anchor: (f32, f32), glyph: &Glyph, color: Color, style: &TextStyle) -> String {
+    let bitmap = glyph
+        .scaled(style.scale.max(1) as usize)
+        .rotated(style.rotation);
+    let (offset_x, offset_y) = style.top_left_offset(bitmap.width as u32, bitmap.height as u32);
+    let left = anchor.0 + offset_x as f32;
+    let top = anchor.1 + offset_y as f32;
+
+    let mut path = String::new();
+    for row in 0..bitmap.height {
+        for column in 0..bitmap.width {
+            if bitmap.is_set(column, row) {
+                let _ = write!(
+                    path,
+                    "M{:.2} {:.2}h1v1h-1z",
+                    left + column as f32,
+                    top + row as f32
+                );
+            }
+        }
+    }
+    format!(r#"<path d="{}" {}/>"#, path, fill_attributes(color))
+}
+
+fn fill_attributes(color: Color) -> String {
+    format!(
+        r#"fill="{}" fill-opacity="{:.3}""#,
+        color.to_hex(),
+        color.alpha()
+    )
+}
+
+fn stroke_attributes(color: Color) -> String {
+    format!(
+        r#"stroke="{}" stroke-opacity="{:.3}""#,
+        color.to_hex(),
+        color.alpha()
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
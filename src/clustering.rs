@@ -0,0 +1,17 @@
+pub mod fuzzy;
+pub mod gmm;
+pub mod kmeans;
+pub mod medoids;
+pub mod minibatch;
+pub mod selection;
+
+pub use fuzzy::{FuzzyClustering, fuzzy_c_means};
+pub use gmm::{CovarianceType, GaussianMixture};
+pub use kmeans::{Clustering, k_means};
+pub use medoids::{DistanceMatrix, Medoids, clara, pam};
+pub use minibatch::{MiniBatchKMeans, mini_batch_k_means};
+pub use selection::ModelSelection;
+
+/// Gets the state of an iterative algorithm after every iteration, e.g. to
+/// record it with a [`FrameRecorder`](crate::recorder::FrameRecorder)
+pub type Observer<'a, T> = &'a mut dyn FnMut(&T);
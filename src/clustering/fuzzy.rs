@@ -0,0 +1,328 @@
+use std::{fmt::Display, path::Path};
+
+use crate::{
+    clustering::{Observer, kmeans::k_means_plus_plus},
+    distance::Metric,
+    error::{Error, Result},
+    geometry::Point,
+    messages::Message,
+    utils::RoundToDecimalPlaces,
+    vector::Features,
+    visual::Image,
+};
+
+const DEFAULT_FUZZIFIER: f32 = 2.0;
+const DEFAULT_MAX_ITERATIONS: usize = 100;
+/// Iterations stop once no membership changes more than this
+const DEFAULT_TOLERANCE: f32 = 1e-4;
+
+/// Soft clustering: every point belongs to every cluster to some degree.
+/// Clusters are numbered from 1, `memberships[i][j]` is the membership of
+/// `points[i]` in cluster `j + 1`, the memberships of a point sum up to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyClustering<P = Point> {
+    pub centroids: Vec<P>,
+    pub memberships: Vec<Vec<f32>>,
+    pub fuzzifier: f32,
+    /// Sum of the squared distances to the centroids weighted by the memberships
+    /// raised to the fuzzifier
+    pub objective: f32,
+    pub iterations: usize,
+}
+
+/// Fuzzy c-means (Bezdek) with k-means++ seeding. The fuzzifier `m` (2 by
+/// default) must be above 1: the closer it is to 1 the harder the memberships,
+/// large ones make every point belong to every cluster almost equally.
+/// `c` is limited by the number of points. `on_iteration` gets the clustering
+/// after every update of the centroids and the memberships.
+pub fn fuzzy_c_means<P: Features>(
+    points: &[P],
+    c: usize,
+    fuzzifier: Option<f32>,
+    metric: &dyn Metric,
+    max_iterations: Option<usize>,
+    tolerance: Option<f32>,
+    mut on_iteration: Option<Observer<FuzzyClustering<P>>>,
+) -> Result<FuzzyClustering<P>> {
+    let fuzzifier = fuzzifier.unwrap_or(DEFAULT_FUZZIFIER);
+    let max_iterations = max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS);
+    let tolerance = tolerance.unwrap_or(DEFAULT_TOLERANCE);
+    if !fuzzifier.is_finite() || fuzzifier <= 1.0 {
+        return Err(Error::InvalidFuzzifier(fuzzifier));
+    }
+    if points.is_empty() {
+        return Err(Error::EmptyPointSet);
+    }
+
+    let mut centroids = k_means_plus_plus(points, c.clamp(1, points.len()), metric);
+    let mut memberships: Vec<Vec<f32>> = points
+        .iter()
+        .map(|point| memberships_of(point, &centroids, fuzzifier, metric))
+        .collect();
+    let mut iterations = 0;
+
+    while iterations < max_iterations {
+        iterations += 1;
+        centroids = (0..centroids.len())
+            .map(|cluster| {
+                weighted_mean(points, &memberships, cluster, fuzzifier)
+                    .unwrap_or_else(|| centroids[cluster].clone())
+            })
+            .collect();
+
+        let mut largest_change: f32 = 0.0;
+        for (point, old) in points.iter().zip(memberships.iter_mut()) {
+            let new = memberships_of(point, &centroids, fuzzifier, metric);
+            for (old, new) in old.iter().zip(&new) {
+                largest_change = largest_change.max((old - new).abs());
+            }
+            *old = new;
+        }
+        if let Some(on_iteration) = on_iteration.as_mut() {
+            on_iteration(&FuzzyClustering {
+                centroids: centroids.clone(),
+                memberships: memberships.clone(),
+                fuzzifier,
+                objective: objective(points, &centroids, &memberships, fuzzifier, metric),
+                iterations,
+            });
+        }
+        if largest_change <= tolerance {
+            break;
+        }
+    }
+
+    let objective = objective(points, &centroids, &memberships, fuzzifier, metric);
+
+    Ok(FuzzyClustering {
+        centroids,
+        memberships,
+        fuzzifier,
+        objective,
+        iterations,
+    })
+}
+
+/// Sum of the squared distances to the centroids weighted by the memberships
+/// raised to the fuzzifier
+fn objective<P: Features>(
+    points: &[P],
+    centroids: &[P],
+    memberships: &[Vec<f32>],
+    fuzzifier: f32,
+    metric: &dyn Metric,
+) -> f32 {
+    points
+        .iter()
+        .zip(memberships)
+        .flat_map(|(point, memberships)| {
+            centroids
+                .iter()
+                .zip(memberships)
+                .map(move |(centroid, membership)| {
+                    membership.powf(fuzzifier) * metric.between(point, centroid).powi(2)
+                })
+        })
+        .sum()
+}
+
+/// `u_j = 1 / Σ_k (d_j / d_k)^(2 / (m - 1))`. A point on centroids belongs to
+/// them equally and to nothing else.
+fn memberships_of<P: Features>(
+    point: &P,
+    centroids: &[P],
+    fuzzifier: f32,
+    metric: &dyn Metric,
+) -> Vec<f32> {
+    let distances: Vec<f32> = centroids
+        .iter()
+        .map(|centroid| metric.between(point, centroid))
+        .collect();
+    let coinciding = distances
+        .iter()
+        .filter(|&&distance| distance == 0.0)
+        .count();
+    if coinciding > 0 {
+        let share = 1.0 / coinciding as f32;
+        return distances
+            .iter()
+            .map(|&distance| if distance == 0.0 { share } else { 0.0 })
+            .collect();
+    }
+
+    let exponent = 2.0 / (fuzzifier - 1.0);
+    distances
+        .iter()
+        .map(|distance| {
+            let sum: f32 = distances
+                .iter()
+                .map(|other| (distance / other).powf(exponent))
+                .sum();
+            1.0 / sum
+        })
+        .collect()
+}
+
+/// Mean of the points weighted by their memberships in the cluster raised to
+/// the fuzzifier, `None` if the cluster has no weight at all
+fn weighted_mean<P: Features>(
+    points: &[P],
+    memberships: &[Vec<f32>],
+    cluster: usize,
+    fuzzifier: f32,
+) -> Option<P> {
+    let mut sums: Vec<f64> = Vec::new();
+    let mut total = 0.0_f64;
+    for (point, memberships) in points.iter().zip(memberships) {
+        let weight = memberships[cluster].powf(fuzzifier) as f64;
+        let coordinates = point.coordinates();
+        let coordinates = coordinates.as_ref();
+        if sums.len() < coordinates.len() {
+            sums.resize(coordinates.len(), 0.0);
+        }
+        for (sum, coordinate) in sums.iter_mut().zip(coordinates) {
+            *sum += weight * *coordinate as f64;
+        }
+        total += weight;
+    }
+    if total <= 0.0 {
+        return None;
+    }
+    let coordinates: Vec<f32> = sums.into_iter().map(|sum| (sum / total) as f32).collect();
+    Some(P::from_coordinates(&coordinates))
+}
+
+impl<P: Features> FuzzyClustering<P> {
+    pub fn centroid(&self, label: usize) -> Option<&P> {
+        self.centroids.get(label.checked_sub(1)?)
+    }
+
+    /// Cluster with the largest membership of every point, counted from 1
+    pub fn labels(&self) -> Vec<usize> {
+        self.memberships
+            .iter()
+            .map(|memberships| {
+                (1..)
+                    .zip(memberships)
+                    .max_by(|first, second| first.1.total_cmp(second.1))
+                    .map_or(1, |(label, _)| label)
+            })
+            .collect()
+    }
+
+    /// Memberships of a new point in the found clusters
+    pub fn predict(&self, point: &P, metric: &dyn Metric) -> Vec<f32> {
+        memberships_of(point, &self.centroids, self.fuzzifier, metric)
+    }
+
+    /// Mean of the squared memberships, from `1 / c` for a completely fuzzy
+    /// partition to 1 for a hard one
+    pub fn partition_coefficient(&self) -> f32 {
+        let sum: f32 = self.memberships.iter().flatten().map(|u| u * u).sum();
+        sum / self.memberships.len().max(1) as f32
+    }
+
+    /// Mean entropy of the memberships of a point, 0 for a hard partition and
+    /// `ln c` for a completely fuzzy one
+    pub fn partition_entropy(&self) -> f32 {
+        let sum: f32 = self
+            .memberships
+            .iter()
+            .flatten()
+            .filter(|&&u| u > 0.0)
+            .map(|u| -u * u.ln())
+            .sum();
+        sum / self.memberships.len().max(1) as f32
+    }
+
+    /// Points in the colors of the clusters blended by the memberships (of the
+    /// first two features), centroids as cores
+    pub fn draw<T: AsRef<Path>>(&self, drawing: &mut Image<T>, points: &[P]) {
+        let on_plane = |point: &P| {
+            let coordinates = point.coordinates();
+            let coordinate = |axis: usize| coordinates.as_ref().get(axis).copied().unwrap_or(0.0);
+            Point::new(coordinate(0), coordinate(1))
+        };
+        for (point, memberships) in points.iter().zip(&self.memberships) {
+            let _ = drawing.draw_point_with_memberships(on_plane(point), memberships);
+        }
+        for (class, centroid) in (1..).zip(&self.centroids) {
+            let _ = drawing.draw_point_with_class(on_plane(centroid), class, true);
+        }
+    }
+}
+
+impl<P: Features + Display> Display for FuzzyClustering<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}",
+            Message::FuzzyHeader {
+                fuzzifier: &self.fuzzifier,
+                iterations: &self.iterations
+            }
+        )?;
+        let points_count = self.memberships.len().max(1) as f32;
+        for (index, centroid) in self.centroids.iter().enumerate() {
+            let share: f32 = self.memberships.iter().map(|u| u[index]).sum::<f32>() / points_count;
+            writeln!(
+                f,
+                "{}",
+                Message::FuzzyCluster {
+                    class: &(index + 1),
+                    share: &share.round_to_dp(3),
+                    centroid
+                }
+            )?;
+        }
+        write!(
+            f,
+            "{}",
+            Message::FuzzyPartition {
+                coefficient: &self.partition_coefficient().round_to_dp(3),
+                entropy: &self.partition_entropy().round_to_dp(3)
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        clustering::fuzzy::fuzzy_c_means,
+        distance::{Euclidean, Manhattan, Metric},
+        geometry::Point,
+    };
+
+    fn assert_sums_to_one(memberships: &[f32]) {
+        let sum: f32 = memberships.iter().sum();
+        assert!((sum - 1.0).abs() < 1e-4, "{:?} sum to {}", memberships, sum);
+    }
+
+    #[test]
+    fn memberships_sum_to_one() {
+        // Two groups and a point halfway between them
+        let mut points: Vec<Point> = (0..20)
+            .map(|i| Point::new((i % 5) as f32, (i / 5) as f32))
+            .chain((0..20).map(|i| Point::new(20.0 + (i % 5) as f32, (i / 5) as f32)))
+            .collect();
+        points.push(Point::new(12.0, 1.5));
+
+        let metrics: [&dyn Metric; 2] = [&Euclidean, &Manhattan];
+        for metric in metrics {
+            let fuzzy = fuzzy_c_means(&points, 2, None, metric, None, None, None).unwrap();
+            assert_eq!(fuzzy.memberships.len(), points.len());
+            for memberships in &fuzzy.memberships {
+                assert_sums_to_one(memberships);
+            }
+
+            // Also for new points, one of them right on a centroid
+            for point in [Point::new(-5.0, 10.0), Point::new(10.0, 0.0)]
+                .iter()
+                .chain(&fuzzy.centroids)
+            {
+                assert_sums_to_one(&fuzzy.predict(point, metric));
+            }
+        }
+    }
+}
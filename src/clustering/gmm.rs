@@ -0,0 +1,579 @@
+use std::{f64::consts::TAU, fmt::Display, path::Path};
+
+use crate::{
+    clustering::{Observer, kmeans::k_means},
+    distance::Euclidean,
+    error::{Error, Result},
+    geometry::Point,
+    messages::Message,
+    utils::RoundToDecimalPlaces,
+    vector::{Features, Matrix, Vector},
+    visual::Image,
+};
+
+const DEFAULT_MAX_ITERATIONS: usize = 200;
+/// Iterations stop once the mean log-likelihood of a point changes less than this
+const DEFAULT_TOLERANCE: f64 = 1e-4;
+/// Added to the variances as a share of the mean variance of the data, keeps
+/// components from collapsing onto single points
+const COVARIANCE_REGULARIZATION: f32 = 1e-4;
+/// Sides of the polygons the ellipses are drawn with
+const ELLIPSE_SEGMENTS: usize = 72;
+
+/// Shape of the covariance matrices of the components
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CovarianceType {
+    /// Any ellipsoid
+    #[default]
+    Full,
+    /// Ellipsoids along the feature axes
+    Diagonal,
+    /// Spheres, every component with its own radius
+    Spherical,
+}
+
+impl CovarianceType {
+    pub const ALL: [CovarianceType; 3] = [
+        CovarianceType::Full,
+        CovarianceType::Diagonal,
+        CovarianceType::Spherical,
+    ];
+
+    pub fn name(&self) -> Message<'static> {
+        match self {
+            CovarianceType::Full => Message::FullCovariance,
+            CovarianceType::Diagonal => Message::DiagonalCovariance,
+            CovarianceType::Spherical => Message::SphericalCovariance,
+        }
+    }
+
+    /// Covariance of this shape from the full one given by its elements
+    fn shape(&self, dimension: usize, covariance: impl Fn(usize, usize) -> f32) -> Matrix {
+        let mut shaped = Matrix::zeros(dimension);
+        match self {
+            CovarianceType::Full => {
+                for row in 0..dimension {
+                    for column in 0..dimension {
+                        shaped[(row, column)] = covariance(row, column);
+                    }
+                }
+            }
+            CovarianceType::Diagonal => {
+                for i in 0..dimension {
+                    shaped[(i, i)] = covariance(i, i);
+                }
+            }
+            CovarianceType::Spherical => {
+                let mean_variance =
+                    (0..dimension).map(|i| covariance(i, i)).sum::<f32>() / dimension as f32;
+                for i in 0..dimension {
+                    shaped[(i, i)] = mean_variance;
+                }
+            }
+        }
+        shaped
+    }
+}
+
+/// Normal distribution of a mixture with its share of the points
+#[derive(Debug, Clone, PartialEq)]
+pub struct Component {
+    pub weight: f32,
+    pub mean: Vector,
+    pub covariance: Matrix,
+    /// Cholesky factor of the covariance
+    lower: Matrix,
+}
+
+impl Component {
+    fn new(weight: f32, mean: Vector, covariance: Matrix) -> Result<Self> {
+        let lower = covariance.cholesky().ok_or(Error::SingularCovariance)?;
+        Ok(Self {
+            weight,
+            mean,
+            covariance,
+            lower,
+        })
+    }
+
+    /// Logarithm of the density of the distribution at the point
+    pub fn log_density(&self, coordinates: &[f32]) -> f64 {
+        let deviation: Vec<f32> = coordinates
+            .iter()
+            .zip(self.mean.as_slice())
+            .map(|(coordinate, mean)| coordinate - mean)
+            .collect();
+        let whitened = self.lower.solve_lower(&deviation);
+        let distance: f64 = whitened.iter().map(|&value| (value as f64).powi(2)).sum();
+        let log_determinant: f64 = (0..self.lower.size())
+            .map(|i| 2.0 * (self.lower[(i, i)] as f64).ln())
+            .sum();
+        -0.5 * (self.mean.dimension() as f64 * TAU.ln() + log_determinant + distance)
+    }
+}
+
+/// Gaussian mixture fitted by expectation-maximization: every point belongs to
+/// every component with some probability, components may be of any shape and size
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianMixture {
+    pub components: Vec<Component>,
+    pub covariance_type: CovarianceType,
+    /// Log-likelihood of the data after every iteration, never decreases
+    pub log_likelihoods: Vec<f64>,
+    pub converged: bool,
+    points_count: usize,
+}
+
+impl GaussianMixture {
+    /// EM started from a k-means clustering. `k` is limited by the number of
+    /// points, at most 200 iterations are made by default. `on_iteration` gets
+    /// the mixture after every expectation-maximization step.
+    pub fn fit<P: Features>(
+        points: &[P],
+        k: usize,
+        covariance_type: Option<CovarianceType>,
+        max_iterations: Option<usize>,
+        tolerance: Option<f64>,
+        mut on_iteration: Option<Observer<Self>>,
+    ) -> Result<Self> {
+        let covariance_type = covariance_type.unwrap_or_default();
+        let max_iterations = max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS);
+        let tolerance = tolerance.unwrap_or(DEFAULT_TOLERANCE);
+        if points.is_empty() {
+            return Err(Error::EmptyPointSet);
+        }
+
+        let covariance = Matrix::covariance(points)?;
+        let dimension = covariance.size();
+        let regularization = COVARIANCE_REGULARIZATION
+            * ((0..dimension).map(|i| covariance[(i, i)]).sum::<f32>() / dimension as f32)
+                .max(f32::MIN_POSITIVE);
+
+        // Hard k-means memberships are the first expectation step
+        let clustering = k_means(
+            points,
+            k.clamp(1, points.len()),
+            &Euclidean,
+            None,
+            None,
+            None,
+        );
+        let mut responsibilities: Vec<Vec<f64>> = clustering
+            .labels
+            .iter()
+            .map(|&label| {
+                (1..=clustering.centroids.len())
+                    .map(|other| if other == label { 1.0 } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+
+        let mut mixture = Self {
+            components: Vec::new(),
+            covariance_type,
+            log_likelihoods: Vec::new(),
+            converged: false,
+            points_count: points.len(),
+        };
+        for _ in 0..max_iterations {
+            mixture.maximize(points, &responsibilities, regularization)?;
+            let log_likelihood = mixture.expect(points, &mut responsibilities);
+            let previous = mixture.log_likelihoods.last().copied();
+            mixture.log_likelihoods.push(log_likelihood);
+            if let Some(on_iteration) = on_iteration.as_mut() {
+                on_iteration(&mixture);
+            }
+            let change = previous.map(|previous| (log_likelihood - previous).abs());
+            if change.is_some_and(|change| change / points.len() as f64 <= tolerance) {
+                mixture.converged = true;
+                break;
+            }
+        }
+        Ok(mixture)
+    }
+
+    /// Mixtures of 1 to `max_k` components (limited by the number of points) for
+    /// the choice of their number by [`bic`](Self::bic) or [`aic`](Self::aic)
+    pub fn sweep<P: Features>(
+        points: &[P],
+        max_k: usize,
+        covariance_type: Option<CovarianceType>,
+    ) -> Result<Vec<Self>> {
+        (1..=max_k.min(points.len()))
+            .map(|k| Self::fit(points, k, covariance_type, None, None, None))
+            .collect()
+    }
+
+    /// New components from the memberships. A component without points would
+    /// never get any back, it is seeded again at the point the others explain worst
+    /// with the covariance of all the data.
+    fn maximize<P: Features>(
+        &mut self,
+        points: &[P],
+        responsibilities: &[Vec<f64>],
+        regularization: f32,
+    ) -> Result<()> {
+        let count = responsibilities.first().map_or(0, Vec::len);
+        let dimension = points[0].dimension();
+        let mut components: Vec<Option<Component>> = Vec::with_capacity(count);
+
+        for index in 0..count {
+            let total: f64 = responsibilities.iter().map(|r| r[index]).sum();
+            if total < f64::EPSILON {
+                components.push(None);
+                continue;
+            }
+
+            let mut center = vec![0.0_f64; dimension];
+            for (point, r) in points.iter().zip(responsibilities) {
+                for (sum, coordinate) in center.iter_mut().zip(point.coordinates().as_ref()) {
+                    *sum += r[index] * *coordinate as f64;
+                }
+            }
+            center.iter_mut().for_each(|sum| *sum /= total);
+
+            let mut scatter = vec![0.0_f64; dimension * dimension];
+            for (point, r) in points.iter().zip(responsibilities) {
+                let deviation: Vec<f64> = point
+                    .coordinates()
+                    .as_ref()
+                    .iter()
+                    .zip(&center)
+                    .map(|(coordinate, mean)| *coordinate as f64 - mean)
+                    .collect();
+                for row in 0..dimension {
+                    for column in 0..dimension {
+                        scatter[row * dimension + column] +=
+                            r[index] * deviation[row] * deviation[column];
+                    }
+                }
+            }
+
+            let mut covariance = self.covariance_type.shape(dimension, |row, column| {
+                (scatter[row * dimension + column] / total) as f32
+            });
+            for i in 0..dimension {
+                covariance[(i, i)] += regularization;
+            }
+
+            components.push(Some(Component::new(
+                (total / points.len() as f64) as f32,
+                Vector::new(center.into_iter().map(|value| value as f32).collect()),
+                covariance,
+            )?));
+        }
+
+        if components.iter().any(Option::is_none) {
+            self.reseed(points, &mut components, regularization)?;
+        }
+        self.components = components.into_iter().flatten().collect();
+        Ok(())
+    }
+
+    /// Fills the missing components, each at the point the present ones explain
+    /// worst, and shares the weights out again so that they sum up to 1
+    fn reseed<P: Features>(
+        &self,
+        points: &[P],
+        components: &mut [Option<Component>],
+        regularization: f32,
+    ) -> Result<()> {
+        let data_covariance = Matrix::covariance(points)?;
+        let dimension = data_covariance.size();
+        let seed_weight = 1.0 / points.len() as f32;
+
+        for index in 0..components.len() {
+            if components[index].is_some() {
+                continue;
+            }
+            let explained = |point: &P| {
+                components
+                    .iter()
+                    .flatten()
+                    .map(|component| {
+                        (component.weight as f64).ln()
+                            + component.log_density(point.coordinates().as_ref())
+                    })
+                    .fold(f64::NEG_INFINITY, f64::max)
+            };
+            let worst = points
+                .iter()
+                .min_by(|first, second| explained(first).total_cmp(&explained(second)))
+                .ok_or(Error::EmptyPointSet)?;
+
+            let mut covariance = self
+                .covariance_type
+                .shape(dimension, |row, column| data_covariance[(row, column)]);
+            for i in 0..dimension {
+                covariance[(i, i)] += regularization;
+            }
+            components[index] = Some(Component::new(
+                seed_weight,
+                Vector::new(worst.coordinates().as_ref().to_vec()),
+                covariance,
+            )?);
+        }
+
+        let total: f32 = components.iter().flatten().map(|c| c.weight).sum();
+        for component in components.iter_mut().flatten() {
+            component.weight /= total;
+        }
+        Ok(())
+    }
+
+    /// Memberships of every point, returns the log-likelihood of the data
+    fn expect<P: Features>(&self, points: &[P], responsibilities: &mut [Vec<f64>]) -> f64 {
+        let mut log_likelihood = 0.0;
+        for (point, r) in points.iter().zip(responsibilities) {
+            let (memberships, log_density) = self.posterior(point.coordinates().as_ref());
+            *r = memberships;
+            log_likelihood += log_density;
+        }
+        log_likelihood
+    }
+
+    /// Probabilities of the components for the point and the log of its density
+    fn posterior(&self, coordinates: &[f32]) -> (Vec<f64>, f64) {
+        let logs: Vec<f64> = self
+            .components
+            .iter()
+            .map(|component| (component.weight as f64).ln() + component.log_density(coordinates))
+            .collect();
+        let largest = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if !largest.is_finite() {
+            let share = 1.0 / logs.len().max(1) as f64;
+            return (vec![share; logs.len()], largest);
+        }
+        let sum: f64 = logs.iter().map(|log| (log - largest).exp()).sum();
+        let log_density = largest + sum.ln();
+        (
+            logs.iter().map(|log| (log - log_density).exp()).collect(),
+            log_density,
+        )
+    }
+
+    /// Probability of every component for the point (soft membership), they sum up to 1
+    pub fn memberships<P: Features>(&self, point: &P) -> Vec<f32> {
+        self.posterior(point.coordinates().as_ref())
+            .0
+            .into_iter()
+            .map(|probability| probability as f32)
+            .collect()
+    }
+
+    /// Most probable component of every point, counted from 1
+    pub fn labels<P: Features>(&self, points: &[P]) -> Vec<usize> {
+        points
+            .iter()
+            .map(|point| {
+                self.memberships(point)
+                    .iter()
+                    .enumerate()
+                    .max_by(|first, second| first.1.total_cmp(second.1))
+                    .map_or(1, |(index, _)| index + 1)
+            })
+            .collect()
+    }
+
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihoods
+            .last()
+            .copied()
+            .unwrap_or(f64::NEG_INFINITY)
+    }
+
+    /// Free parameters: weights, means and the covariances of the chosen shape
+    pub fn parameters_count(&self) -> usize {
+        let k = self.components.len();
+        let dimension = self.components.first().map_or(0, |c| c.mean.dimension());
+        let covariance = match self.covariance_type {
+            CovarianceType::Full => dimension * (dimension + 1) / 2,
+            CovarianceType::Diagonal => dimension,
+            CovarianceType::Spherical => 1,
+        };
+        k.saturating_sub(1) + k * dimension + k * covariance
+    }
+
+    /// Bayesian information criterion, the smaller the better. Penalises the
+    /// parameters harder than [`aic`](Self::aic) and prefers fewer components.
+    pub fn bic(&self) -> f64 {
+        self.parameters_count() as f64 * (self.points_count as f64).ln()
+            - 2.0 * self.log_likelihood()
+    }
+
+    /// Akaike information criterion, the smaller the better
+    pub fn aic(&self) -> f64 {
+        2.0 * self.parameters_count() as f64 - 2.0 * self.log_likelihood()
+    }
+
+    /// 1σ and 2σ ellipses of every component (of the first two features) and its
+    /// mean as a core, in the colors of the classes counted from 1
+    pub fn draw_ellipses<T: AsRef<Path>>(&self, drawing: &mut Image<T>) {
+        for (class, component) in (1..).zip(&self.components) {
+            let coordinate = |axis: usize| component.mean.as_slice().get(axis).copied();
+            let center = Point::new(coordinate(0).unwrap_or(0.0), coordinate(1).unwrap_or(0.0));
+            let variance = |row: usize, column: usize| {
+                if row < component.covariance.size() && column < component.covariance.size() {
+                    component.covariance[(row, column)]
+                } else {
+                    0.0
+                }
+            };
+            let Ok(plane) = Matrix::from_rows(&[
+                vec![variance(0, 0), variance(0, 1)],
+                vec![variance(1, 0), variance(1, 1)],
+            ]) else {
+                continue;
+            };
+            let axes = plane.symmetric_eigen();
+
+            let (core, color) = drawing.class_color(class);
+            for sigmas in [1.0, 2.0] {
+                let outline: Vec<Point> = (0..=ELLIPSE_SEGMENTS)
+                    .map(|step| {
+                        let angle = std::f32::consts::TAU * step as f32 / ELLIPSE_SEGMENTS as f32;
+                        let (sin, cos) = angle.sin_cos();
+                        let (first, second) = (&axes[0], &axes[1]);
+                        let a = sigmas * first.0.max(0.0).sqrt() * cos;
+                        let b = sigmas * second.0.max(0.0).sqrt() * sin;
+                        Point::new(
+                            center.x + a * first.1[0] + b * second.1[0],
+                            center.y + a * first.1[1] + b * second.1[1],
+                        )
+                    })
+                    .collect();
+                drawing.draw_polyline(&outline, Some(if sigmas == 1.0 { core } else { color }));
+            }
+            let _ = drawing.draw_point_with_class(center, class, true);
+        }
+    }
+}
+
+impl Display for GaussianMixture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", Message::GmmHeader)?;
+        for (iteration, log_likelihood) in (1..).zip(&self.log_likelihoods) {
+            writeln!(
+                f,
+                "{}",
+                Message::GmmIteration {
+                    iteration: &iteration,
+                    log_likelihood: &log_likelihood.round_to_dp(3)
+                }
+            )?;
+        }
+        for (class, component) in (1..).zip(&self.components) {
+            writeln!(
+                f,
+                "{}",
+                Message::GmmComponent {
+                    class: &class,
+                    weight: &component.weight.round_to_dp(3),
+                    mean: &component.mean
+                }
+            )?;
+        }
+        write!(
+            f,
+            "{}",
+            Message::GmmCriteria {
+                log_likelihood: &self.log_likelihood().round_to_dp(3),
+                bic: &self.bic().round_to_dp(3),
+                aic: &self.aic().round_to_dp(3)
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        clustering::gmm::{CovarianceType, GaussianMixture},
+        geometry::Point,
+        utils::rand_normal,
+    };
+
+    /// Three overlapping groups, so that EM needs many iterations
+    fn points() -> Vec<Point> {
+        let centers = [(0.0, 0.0), (3.0, 1.0), (1.0, 4.0)];
+        (0..300)
+            .map(|i| {
+                let (x, y) = centers[i % centers.len()];
+                Point::new(rand_normal(x, 1.5), rand_normal(y, 1.0))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn log_likelihood_never_decreases() {
+        for covariance_type in [
+            CovarianceType::Full,
+            CovarianceType::Diagonal,
+            CovarianceType::Spherical,
+        ] {
+            let points = points();
+            let mixture =
+                GaussianMixture::fit(&points, 3, Some(covariance_type), None, Some(0.0), None)
+                    .unwrap();
+
+            assert!(mixture.log_likelihoods.len() > 1);
+            for pair in mixture.log_likelihoods.windows(2) {
+                // Rounding of the single precision parameters
+                let slack = 1e-6 * pair[0].abs();
+                assert!(
+                    pair[1] >= pair[0] - slack,
+                    "{:?}: {} after {}",
+                    covariance_type,
+                    pair[1],
+                    pair[0]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn memberships_sum_to_one() {
+        let points = points();
+        let mixture = GaussianMixture::fit(&points, 3, None, None, None, None).unwrap();
+        for point in &points {
+            let sum: f32 = mixture.memberships(point).iter().sum();
+            assert!((sum - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn empty_components_are_seeded_again() {
+        // Two distinct places only, the third k-means seed coincides with one of
+        // the others and gets no points
+        let points: Vec<Point> = (0..20)
+            .map(|i| Point::new((i % 2) as f32 * 5.0, (i % 2) as f32 * 5.0))
+            .collect();
+        let mixture = GaussianMixture::fit(&points, 3, None, None, None, None).unwrap();
+
+        assert_eq!(mixture.components.len(), 3);
+        let total: f32 = mixture.components.iter().map(|c| c.weight).sum();
+        assert!((total - 1.0).abs() < 1e-4);
+        for point in &points {
+            assert_eq!(mixture.memberships(point).len(), 3);
+        }
+    }
+
+    #[test]
+    fn bic_picks_the_number_of_groups() {
+        let centers = [(0.0, 0.0), (20.0, 0.0), (0.0, 20.0)];
+        let points: Vec<Point> = (0..300)
+            .map(|i| {
+                let (x, y) = centers[i % centers.len()];
+                Point::new(rand_normal(x, 1.0), rand_normal(y, 1.0))
+            })
+            .collect();
+        let mixtures = GaussianMixture::sweep(&points, 6, None).unwrap();
+
+        assert_eq!(mixtures.len(), 6);
+        let best = mixtures
+            .iter()
+            .min_by(|first, second| first.bic().total_cmp(&second.bic()))
+            .unwrap();
+        assert_eq!(best.components.len(), 3);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    clustering::Observer,
+    distance::{Euclidean, Metric},
+    geometry::Point,
+    vector::{Features, mean},
+};
+
+const DEFAULT_MAX_ITERATIONS: usize = 100;
+/// Iterations stop once no centroid moves further than this
+const DEFAULT_TOLERANCE: f32 = 1e-3;
+
+/// Result of a clustering: centroid of every cluster and the cluster of every
+/// point. Clusters are numbered from 1, `labels[i]` belongs to `points[i]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clustering<P = Point> {
+    pub centroids: Vec<P>,
+    pub labels: Vec<usize>,
+    /// Sum of the squared distances (by the metric) of the points to their centroids
+    pub inertia: f32,
+    pub iterations: usize,
+}
+
+impl<P: Features> Clustering<P> {
+    pub fn centroid(&self, label: usize) -> Option<&P> {
+        self.centroids.get(label.checked_sub(1)?)
+    }
+}
+
+/// Lloyd's k-means with k-means++ seeding. `k` is limited by the number of points,
+/// clusters that lose all of their points keep their previous centroid.
+/// Points go to the nearest centroid by the metric, but the centroids are still
+/// the means of their clusters (optimal only for the squared Euclidean distance).
+/// The tolerance is a Euclidean shift in field units. `on_iteration` gets the
+/// clustering after every move of the centroids.
+pub fn k_means<P: Features>(
+    points: &[P],
+    k: usize,
+    metric: &dyn Metric,
+    max_iterations: Option<usize>,
+    tolerance: Option<f32>,
+    mut on_iteration: Option<Observer<Clustering<P>>>,
+) -> Clustering<P> {
+    let max_iterations = max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS);
+    let tolerance = tolerance.unwrap_or(DEFAULT_TOLERANCE);
+
+    let mut centroids = k_means_plus_plus(points, k.min(points.len()), metric);
+    let mut labels = vec![1; points.len()];
+    let mut iterations = 0;
+
+    while iterations < max_iterations {
+        iterations += 1;
+        for (label, point) in labels.iter_mut().zip(points) {
+            *label = nearest(point, &centroids, metric).0 + 1;
+        }
+
+        let mut clusters: BTreeMap<usize, Vec<&P>> = BTreeMap::new();
+        for (label, point) in labels.iter().zip(points) {
+            clusters.entry(*label).or_default().push(point);
+        }
+
+        let mut largest_shift: f32 = 0.0;
+        for (label, members) in clusters {
+            let Some(moved) = mean(members) else {
+                continue;
+            };
+            let centroid = &mut centroids[label - 1];
+            let shift = Euclidean.distance(
+                moved.coordinates().as_ref(),
+                centroid.coordinates().as_ref(),
+            );
+            largest_shift = largest_shift.max(shift);
+            *centroid = moved;
+        }
+        if let Some(on_iteration) = on_iteration.as_mut() {
+            on_iteration(&Clustering {
+                centroids: centroids.clone(),
+                labels: labels.clone(),
+                inertia: inertia(points, &labels, &centroids, metric),
+                iterations,
+            });
+        }
+        if largest_shift <= tolerance {
+            break;
+        }
+    }
+
+    // Centroids moved after the last assignment
+    for (label, point) in labels.iter_mut().zip(points) {
+        *label = nearest(point, &centroids, metric).0 + 1;
+    }
+    let inertia = inertia(points, &labels, &centroids, metric);
+
+    Clustering {
+        centroids,
+        labels,
+        inertia,
+        iterations,
+    }
+}
+
+/// Sum of the squared distances of the points to the centroids of their clusters
+fn inertia<P: Features>(
+    points: &[P],
+    labels: &[usize],
+    centroids: &[P],
+    metric: &dyn Metric,
+) -> f32 {
+    points
+        .iter()
+        .zip(labels)
+        .map(|(point, label)| metric.between(point, &centroids[label - 1]).powi(2))
+        .sum()
+}
+
+/// Seeds: the first one is random, every next one is picked with probability
+/// proportional to the squared distance to the closest seed so far
+pub fn k_means_plus_plus<P: Features>(points: &[P], k: usize, metric: &dyn Metric) -> Vec<P> {
+    if points.is_empty() || k == 0 {
+        return Vec::new();
+    }
+
+    let mut seeds = vec![points[rand::random_range(0..points.len())].clone()];
+    let mut distances: Vec<f32> = points
+        .iter()
+        .map(|point| metric.between(point, &seeds[0]).powi(2))
+        .collect();
+
+    while seeds.len() < k {
+        let total: f32 = distances.iter().sum();
+        let seed = if total > 0.0 {
+            let mut target = rand::random::<f32>() * total;
+            let index = distances
+                .iter()
+                .position(|distance| {
+                    target -= distance;
+                    target <= 0.0
+                })
+                .unwrap_or(points.len() - 1);
+            &points[index]
+        } else {
+            // Every point coincides with a seed already
+            &points[rand::random_range(0..points.len())]
+        };
+
+        for (distance, point) in distances.iter_mut().zip(points) {
+            *distance = distance.min(metric.between(point, seed).powi(2));
+        }
+        seeds.push(seed.clone());
+    }
+    seeds
+}
+
+/// Index of the closest of the centers and the distance to it
+pub fn nearest<P: Features>(point: &P, centers: &[P], metric: &dyn Metric) -> (usize, f32) {
+    centers
+        .iter()
+        .map(|center| metric.between(point, center))
+        .enumerate()
+        .fold((0, f32::INFINITY), |best, (index, distance)| {
+            if distance < best.1 {
+                (index, distance)
+            } else {
+                best
+            }
+        })
+}
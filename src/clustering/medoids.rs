@@ -0,0 +1,341 @@
+use std::fmt::Display;
+
+use rayon::prelude::*;
+
+use crate::{
+    distance::Metric,
+    error::{Error, Result},
+    messages::Message,
+    utils::RoundToDecimalPlaces,
+    vector::Features,
+};
+
+/// Swaps stop after this many even if the cost still goes down
+const DEFAULT_MAX_SWAPS: usize = 1000;
+const DEFAULT_CLARA_SAMPLES: usize = 5;
+/// Swaps that lower the cost less than this are not made, so rounding errors
+/// can not swap the same medoids back and forth
+const MIN_IMPROVEMENT: f64 = 1e-6;
+
+/// Distances between every two of `n` objects, row by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceMatrix {
+    size: usize,
+    values: Vec<f32>,
+}
+
+impl DistanceMatrix {
+    /// Distances between the points by the metric
+    pub fn new<P: Features>(points: &[P], metric: &dyn Metric) -> Self {
+        let values = points
+            .par_iter()
+            .flat_map_iter(|first| points.iter().map(|second| metric.between(first, second)))
+            .collect();
+        Self {
+            size: points.len(),
+            values,
+        }
+    }
+
+    /// Precomputed distances, every row must be as long as there are rows
+    pub fn from_rows(rows: &[Vec<f32>]) -> Result<Self> {
+        let size = rows.len();
+        if let Some(row) = rows.iter().find(|row| row.len() != size) {
+            return Err(Error::DimensionMismatch {
+                expected: size,
+                found: row.len(),
+            });
+        }
+        Ok(Self {
+            size,
+            values: rows.concat(),
+        })
+    }
+
+    /// Number of objects
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn get(&self, first: usize, second: usize) -> f32 {
+        self.values[first * self.size + second]
+    }
+}
+
+/// Result of a k-medoids clustering. Medoids are objects of the data set, so
+/// only the distances are ever needed. Clusters are numbered from 1,
+/// `labels[i]` belongs to object `i` and the medoid of cluster `c` is `medoids[c - 1]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Medoids {
+    /// Indices of the objects that are medoids
+    pub medoids: Vec<usize>,
+    pub labels: Vec<usize>,
+    /// Sum of the distances of the objects to their medoids
+    pub cost: f32,
+    pub swaps: usize,
+}
+
+impl Medoids {
+    /// Index of the object that is the medoid of the cluster
+    pub fn medoid(&self, label: usize) -> Option<usize> {
+        self.medoids.get(label.checked_sub(1)?).copied()
+    }
+
+    /// Medoids themselves, in the order of the clusters
+    pub fn centers<P: Clone>(&self, points: &[P]) -> Vec<P> {
+        self.medoids
+            .iter()
+            .map(|&index| points[index].clone())
+            .collect()
+    }
+
+    /// Closest medoid of every object and the total distance to them
+    fn assign(medoids: Vec<usize>, count: usize, distance: impl Fn(usize, usize) -> f32) -> Self {
+        let mut cost = 0.0_f64;
+        let labels = (0..count)
+            .map(|object| {
+                let (label, closest) = (1..)
+                    .zip(&medoids)
+                    .map(|(label, &medoid)| (label, distance(object, medoid)))
+                    .fold((1, f32::INFINITY), |best, candidate| {
+                        if candidate.1 < best.1 {
+                            candidate
+                        } else {
+                            best
+                        }
+                    });
+                cost += closest as f64;
+                label
+            })
+            .collect();
+        Self {
+            medoids,
+            labels,
+            cost: cost as f32,
+            swaps: 0,
+        }
+    }
+}
+
+/// Partitioning Around Medoids (Kaufman & Rousseeuw): greedy BUILD of `k`
+/// medoids, then SWAP of a medoid and an ordinary object while it lowers the
+/// total distance. Every pass costs `O(k * n²)`, [`clara`] is for larger sets.
+/// `k` is limited by the number of objects.
+pub fn pam(distances: &DistanceMatrix, k: usize, max_swaps: Option<usize>) -> Result<Medoids> {
+    if distances.is_empty() {
+        return Err(Error::EmptyPointSet);
+    }
+    let max_swaps = max_swaps.unwrap_or(DEFAULT_MAX_SWAPS);
+    let count = distances.len();
+    let distance = |first: usize, second: usize| distances.get(first, second) as f64;
+
+    let mut medoids = build(count, k.clamp(1, count), &distance);
+    let mut swaps = 0;
+    while swaps < max_swaps {
+        // Distances to the closest and the second closest medoids, with the position of the closest
+        let closest: Vec<(usize, f64, f64)> = (0..count)
+            .map(|object| {
+                medoids.iter().enumerate().fold(
+                    (0, f64::INFINITY, f64::INFINITY),
+                    |(position, first, second), (candidate, &medoid)| {
+                        let d = distance(object, medoid);
+                        if d < first {
+                            (candidate, d, first)
+                        } else {
+                            (position, first, second.min(d))
+                        }
+                    },
+                )
+            })
+            .collect();
+
+        let mut best: Option<(usize, usize, f64)> = None;
+        for (position, _) in medoids.iter().enumerate() {
+            for candidate in (0..count).filter(|object| !medoids.contains(object)) {
+                let change: f64 = closest
+                    .iter()
+                    .enumerate()
+                    .map(|(object, &(nearest, first, second))| {
+                        let to_candidate = distance(object, candidate);
+                        if nearest == position {
+                            to_candidate.min(second) - first
+                        } else {
+                            (to_candidate - first).min(0.0)
+                        }
+                    })
+                    .sum();
+                if change < -MIN_IMPROVEMENT && best.is_none_or(|best| change < best.2) {
+                    best = Some((position, candidate, change));
+                }
+            }
+        }
+
+        let Some((position, candidate, _)) = best else {
+            break;
+        };
+        medoids[position] = candidate;
+        swaps += 1;
+    }
+
+    let mut result = Medoids::assign(medoids, count, |first, second| distances.get(first, second));
+    result.swaps = swaps;
+    Ok(result)
+}
+
+/// BUILD: the object with the smallest total distance first, then every time
+/// the one that brings the most objects closer to their medoids
+fn build(count: usize, k: usize, distance: &impl Fn(usize, usize) -> f64) -> Vec<usize> {
+    let total = |object: usize| (0..count).map(|other| distance(object, other)).sum::<f64>();
+    let first = (0..count)
+        .min_by(|&a, &b| total(a).total_cmp(&total(b)))
+        .unwrap_or(0);
+    let mut medoids = vec![first];
+    let mut closest: Vec<f64> = (0..count).map(|object| distance(object, first)).collect();
+
+    while medoids.len() < k {
+        let gain = |candidate: usize| -> f64 {
+            closest
+                .iter()
+                .enumerate()
+                .map(|(object, current)| (current - distance(object, candidate)).max(0.0))
+                .sum()
+        };
+        let Some(next) = (0..count)
+            .filter(|object| !medoids.contains(object))
+            .max_by(|&a, &b| gain(a).total_cmp(&gain(b)))
+        else {
+            break;
+        };
+        for (object, current) in closest.iter_mut().enumerate() {
+            *current = current.min(distance(object, next));
+        }
+        medoids.push(next);
+    }
+    medoids
+}
+
+/// Clustering LARge Applications: [`pam`] on random samples of the objects
+/// (5 of `40 + 2k` by default), the medoids of the sample with the smallest
+/// cost over the whole set win. Every sample keeps the best medoids found so far.
+/// Distances are given by the indices of the objects, e.g.
+/// `|i, j| metric.between(&points[i], &points[j])` or `|i, j| matrix.get(i, j)`,
+/// and only `O(n * k)` of them are needed outside of the samples.
+pub fn clara(
+    count: usize,
+    k: usize,
+    distance: impl Fn(usize, usize) -> f32 + Sync,
+    samples: Option<usize>,
+    sample_size: Option<usize>,
+) -> Result<Medoids> {
+    if count == 0 {
+        return Err(Error::EmptyPointSet);
+    }
+    let k = k.clamp(1, count);
+    let samples = samples.unwrap_or(DEFAULT_CLARA_SAMPLES).max(1);
+    let sample_size = sample_size.unwrap_or(40 + 2 * k).clamp(k, count);
+
+    let mut best: Option<Medoids> = None;
+    for _ in 0..samples {
+        let mut sample: Vec<usize> = best
+            .as_ref()
+            .map(|best| best.medoids.clone())
+            .unwrap_or_default();
+        for index in rand::seq::index::sample(&mut rand::rng(), count, sample_size) {
+            if sample.len() >= sample_size {
+                break;
+            }
+            if !sample.contains(&index) {
+                sample.push(index);
+            }
+        }
+
+        let rows: Vec<Vec<f32>> = sample
+            .par_iter()
+            .map(|&first| {
+                sample
+                    .iter()
+                    .map(|&second| distance(first, second))
+                    .collect()
+            })
+            .collect();
+        let fitted = pam(&DistanceMatrix::from_rows(&rows)?, k, None)?;
+        let medoids = fitted.medoids.iter().map(|&index| sample[index]).collect();
+
+        let mut candidate = Medoids::assign(medoids, count, &distance);
+        candidate.swaps = fitted.swaps;
+        if best.as_ref().is_none_or(|best| candidate.cost < best.cost) {
+            best = Some(candidate);
+        }
+        if sample_size == count {
+            // The only sample is the whole set
+            break;
+        }
+    }
+    best.ok_or(Error::EmptyPointSet)
+}
+
+impl Display for Medoids {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", Message::MedoidsHeader { swaps: &self.swaps })?;
+        for (class, medoid) in (1..).zip(&self.medoids) {
+            let size = self.labels.iter().filter(|&&label| label == class).count();
+            writeln!(
+                f,
+                "{}",
+                Message::MedoidCluster {
+                    class: &class,
+                    medoid: &(medoid + 1),
+                    size: &size
+                }
+            )?;
+        }
+        write!(
+            f,
+            "{}",
+            Message::MedoidsCost {
+                cost: &self.cost.round_to_dp(3)
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clustering::medoids::{DistanceMatrix, pam};
+
+    /// Objects 0, 1 and 2 lie in a row with 1 in the middle, 3 is far from them
+    fn distances() -> DistanceMatrix {
+        DistanceMatrix::from_rows(&[
+            vec![0.0, 1.0, 3.0, 10.0],
+            vec![1.0, 0.0, 1.0, 10.0],
+            vec![3.0, 1.0, 0.0, 10.0],
+            vec![10.0, 10.0, 10.0, 0.0],
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn pam_finds_known_medoids() {
+        let medoids = pam(&distances(), 2, None).unwrap();
+        assert_eq!(medoids.medoids, vec![1, 3]);
+        assert_eq!(medoids.labels, vec![1, 1, 1, 2]);
+        assert_eq!(medoids.cost, 2.0);
+        assert_eq!(medoids.medoid(2), Some(3));
+
+        let medoids = pam(&distances(), 1, None).unwrap();
+        assert_eq!(medoids.medoids, vec![1]);
+        assert_eq!(medoids.cost, 12.0);
+    }
+
+    #[test]
+    fn pam_limits_k() {
+        let medoids = pam(&distances(), 10, None).unwrap();
+        assert_eq!(medoids.medoids.len(), 4);
+        assert_eq!(medoids.cost, 0.0);
+        assert!(DistanceMatrix::from_rows(&[vec![0.0, 1.0]]).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use std::fmt::Display;
+
+use crate::{
+    clustering::kmeans::{Clustering, k_means_plus_plus, nearest},
+    distance::{Euclidean, Metric},
+    geometry::Point,
+    messages::Message,
+    utils::RoundToDecimalPlaces,
+    vector::Features,
+};
+
+const DEFAULT_BATCH_SIZE: usize = 1024;
+const DEFAULT_MAX_BATCHES: usize = 100;
+/// Batches stop once no centroid moves further than this in one of them
+const DEFAULT_TOLERANCE: f32 = 1e-3;
+
+/// Mini-batch k-means (Sculley): centroids are moved towards every batch of
+/// points as it comes, each with a learning rate of one over the number of
+/// points it has got so far. Only the centroids are kept, so the points may be
+/// read from a stream of any length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MiniBatchKMeans<P = Point> {
+    k: usize,
+    centroids: Vec<P>,
+    /// Points every centroid has got so far
+    counts: Vec<usize>,
+    /// Largest Euclidean shift of a centroid in every batch, in field units
+    drifts: Vec<f32>,
+    points_seen: usize,
+}
+
+impl<P: Features> MiniBatchKMeans<P> {
+    /// Centroids are seeded by k-means++ from the first batches
+    pub fn new(k: usize) -> Self {
+        Self {
+            k: k.max(1),
+            centroids: Vec::new(),
+            counts: Vec::new(),
+            drifts: Vec::new(),
+            points_seen: 0,
+        }
+    }
+
+    /// Moves the centroids towards one batch and returns their largest shift.
+    /// Every point goes to the closest centroid as it was before the batch.
+    pub fn partial_fit(&mut self, batch: &[P], metric: &dyn Metric) -> f32 {
+        if batch.is_empty() {
+            return 0.0;
+        }
+        if self.centroids.len() < self.k {
+            let seeds = k_means_plus_plus(batch, self.k - self.centroids.len(), metric);
+            self.counts.resize(self.centroids.len() + seeds.len(), 0);
+            self.centroids.extend(seeds);
+        }
+
+        let before = self.centroids.clone();
+        let assignments: Vec<usize> = batch
+            .iter()
+            .map(|point| nearest(point, &before, metric).0)
+            .collect();
+        for (point, index) in batch.iter().zip(assignments) {
+            self.counts[index] += 1;
+            let rate = 1.0 / self.counts[index] as f32;
+            let moved: Vec<f32> = self.centroids[index]
+                .coordinates()
+                .as_ref()
+                .iter()
+                .zip(point.coordinates().as_ref())
+                .map(|(centroid, coordinate)| centroid + rate * (coordinate - centroid))
+                .collect();
+            self.centroids[index] = P::from_coordinates(&moved);
+        }
+
+        let drift = before
+            .iter()
+            .zip(&self.centroids)
+            .map(|(before, after)| {
+                Euclidean.distance(before.coordinates().as_ref(), after.coordinates().as_ref())
+            })
+            .fold(0.0, f32::max);
+        self.drifts.push(drift);
+        self.points_seen += batch.len();
+        drift
+    }
+
+    /// Reads the stream in batches of `batch_size` (1024 by default), holding
+    /// only one batch at a time. Returns the number of batches read.
+    pub fn fit_stream(
+        &mut self,
+        stream: impl IntoIterator<Item = P>,
+        batch_size: Option<usize>,
+        metric: &dyn Metric,
+    ) -> usize {
+        let batch_size = batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut batches = 0;
+        for point in stream {
+            batch.push(point);
+            if batch.len() == batch_size {
+                self.partial_fit(&batch, metric);
+                batch.clear();
+                batches += 1;
+            }
+        }
+        if !batch.is_empty() {
+            self.partial_fit(&batch, metric);
+            batches += 1;
+        }
+        batches
+    }
+
+    pub fn centroids(&self) -> &[P] {
+        &self.centroids
+    }
+
+    pub fn drifts(&self) -> &[f32] {
+        &self.drifts
+    }
+
+    pub fn points_seen(&self) -> usize {
+        self.points_seen
+    }
+
+    /// Cluster of the point, counted from 1
+    pub fn predict(&self, point: &P, metric: &dyn Metric) -> usize {
+        nearest(point, &self.centroids, metric).0 + 1
+    }
+}
+
+/// Mini-batch k-means over points in memory: batches of `batch_size` (1024 by
+/// default) random points until a batch moves no centroid further than the
+/// tolerance or `max_batches` (100) are made. Much faster than [`k_means`](crate::clustering::k_means)
+/// on large sets, at the cost of a slightly larger inertia.
+pub fn mini_batch_k_means<P: Features>(
+    points: &[P],
+    k: usize,
+    metric: &dyn Metric,
+    batch_size: Option<usize>,
+    max_batches: Option<usize>,
+    tolerance: Option<f32>,
+) -> Clustering<P> {
+    let batch_size = batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+    let max_batches = max_batches.unwrap_or(DEFAULT_MAX_BATCHES);
+    let tolerance = tolerance.unwrap_or(DEFAULT_TOLERANCE);
+
+    let mut model = MiniBatchKMeans::new(k.min(points.len()));
+    let mut iterations = 0;
+    if !points.is_empty() {
+        while iterations < max_batches {
+            iterations += 1;
+            let batch: Vec<P> = (0..batch_size.min(points.len()))
+                .map(|_| points[rand::random_range(0..points.len())].clone())
+                .collect();
+            // Fresh seeds always move far in the first batch
+            if model.partial_fit(&batch, metric) <= tolerance && iterations > 1 {
+                break;
+            }
+        }
+    }
+
+    let labels: Vec<usize> = points
+        .iter()
+        .map(|point| model.predict(point, metric))
+        .collect();
+    let inertia = points
+        .iter()
+        .zip(&labels)
+        .map(|(point, label)| metric.between(point, &model.centroids[label - 1]).powi(2))
+        .sum();
+    Clustering {
+        centroids: model.centroids,
+        labels,
+        inertia,
+        iterations,
+    }
+}
+
+impl<P: Features + Display> Display for MiniBatchKMeans<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            Message::MiniBatchHeader {
+                points: &self.points_seen,
+                batches: &self.drifts.len()
+            }
+        )?;
+        for (batch, drift) in (1..).zip(&self.drifts) {
+            write!(
+                f,
+                "\n{}",
+                Message::MiniBatchDrift {
+                    batch: &batch,
+                    drift: &drift.round_to_dp(3)
+                }
+            )?;
+        }
+        for (class, (centroid, count)) in (1..).zip(self.centroids.iter().zip(&self.counts)) {
+            write!(
+                f,
+                "\n{}",
+                Message::MiniBatchCentroid {
+                    class: &class,
+                    centroid,
+                    count
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        clustering::MiniBatchKMeans,
+        distance::{Euclidean, Metric},
+        geometry::Point,
+        utils::rand_normal,
+    };
+
+    const CENTERS: [(f32, f32); 3] = [(0.0, 0.0), (50.0, 0.0), (25.0, 40.0)];
+
+    /// Endless points of the groups around the centers, taking turns
+    fn stream() -> impl Iterator<Item = Point> {
+        (0..).map(|i| {
+            let (x, y) = CENTERS[i % CENTERS.len()];
+            Point::new(rand_normal(x, 1.0), rand_normal(y, 1.0))
+        })
+    }
+
+    #[test]
+    fn stream_converges_to_the_centers() {
+        let mut model = MiniBatchKMeans::new(3);
+        let batches = model.fit_stream(stream().take(30_000), Some(500), &Euclidean);
+
+        assert_eq!(batches, 60);
+        assert_eq!(model.points_seen(), 30_000);
+        assert_eq!(model.drifts().len(), 60);
+        assert!(model.drifts()[59] < model.drifts()[0]);
+        for (x, y) in CENTERS {
+            let closest = model
+                .centroids()
+                .iter()
+                .map(|centroid| Euclidean.distance(&[centroid.x, centroid.y], &[x, y]))
+                .fold(f32::MAX, f32::min);
+            assert!(closest < 0.5, "no centroid near ({}; {})", x, y);
+        }
+        for &count in &model.counts {
+            assert!(count.abs_diff(10_000) < 500, "{:?}", model.counts);
+        }
+    }
+
+    #[test]
+    fn counts_build_up_with_every_batch() {
+        let mut model = MiniBatchKMeans::new(3);
+        let mut points = stream();
+
+        let batch: Vec<Point> = points.by_ref().take(300).collect();
+        model.partial_fit(&batch, &Euclidean);
+        assert_eq!(model.counts.iter().sum::<usize>(), 300);
+
+        // The last batch is read even when it is not full
+        let batches = model.fit_stream(points.take(1_050), Some(500), &Euclidean);
+        assert_eq!(batches, 3);
+        assert_eq!(model.points_seen(), 1_350);
+        assert_eq!(model.counts.iter().sum::<usize>(), 1_350);
+        assert_eq!(model.drifts().len(), 4);
+    }
+}
@@ -0,0 +1,322 @@
+use std::{fmt::Display, path::Path};
+
+use crate::{
+    clustering::kmeans::k_means,
+    distance::Metric,
+    error::{Error, Result},
+    font::{HorizontalAlign, TextStyle, VerticalAlign},
+    geometry::{FixedPosition, Point, Rectangle},
+    messages::Message,
+    metrics::internal::silhouette,
+    utils::RoundToDecimalPlaces,
+    vector::Features,
+    visual::{Color, Image},
+};
+
+/// Uniform data sets the gap statistic compares the clustering with
+const DEFAULT_REFERENCES_COUNT: usize = 10;
+
+const PLOT_WIDTH: u32 = 800;
+const PLOT_HEIGHT: u32 = 500;
+/// Free space around the curves along `k`, leaves room for the numbers at the ends
+const K_PADDING: f32 = 0.3;
+/// Free space above and below the curves, in units of the normalized score
+const SCORE_PADDING: f32 = 0.1;
+
+const SUGGESTED_LINE_COLOR: Color = Color::rgba(0x00, 0x00, 0x00, 76);
+
+const INERTIA_CLASS: usize = 1;
+const SILHOUETTE_CLASS: usize = 2;
+const GAP_CLASS: usize = 3;
+
+/// Scores of the clustering into `k` clusters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate {
+    pub k: usize,
+    /// Within-cluster sum of squares, always falls as `k` grows
+    pub inertia: f32,
+    /// `None` for a single cluster
+    pub silhouette: Option<f32>,
+    /// How much tighter the clusters are than in uniform data
+    pub gap: f32,
+    /// Standard error of the gap over the reference data sets
+    pub gap_error: f32,
+}
+
+/// Scores for a range of cluster counts and the counts suggested by every method
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelSelection {
+    pub candidates: Vec<Candidate>,
+    /// Point of the inertia curve furthest from the chord between its ends
+    pub elbow: usize,
+    /// Highest mean silhouette
+    pub best_silhouette: Option<usize>,
+    /// Smallest `k` with `gap(k) >= gap(k + 1) - error(k + 1)` (Tibshirani et al.)
+    pub gap: usize,
+}
+
+impl ModelSelection {
+    /// Runs k-means with the metric for every `k` from 1 to `max_k`. The reference
+    /// data of the gap statistic is drawn uniformly from the box between the lowest
+    /// and the highest corner (fitted to the points by default), `references` sets
+    /// are used, 10 by default.
+    pub fn new<P: Features>(
+        points: &[P],
+        max_k: usize,
+        metric: &dyn Metric,
+        bounds: Option<(P, P)>,
+        references: Option<usize>,
+    ) -> Result<Self> {
+        let bounds = match bounds {
+            Some(bounds) => bounds,
+            None => bounding_box(points).ok_or(Error::EmptyPointSet)?,
+        };
+        let references = references.unwrap_or(DEFAULT_REFERENCES_COUNT).max(1);
+        let reference_sets: Vec<Vec<P>> = (0..references)
+            .map(|_| (0..points.len()).map(|_| random_point(&bounds)).collect())
+            .collect();
+
+        let candidates: Vec<Candidate> = (1..=max_k.clamp(1, points.len().max(1)))
+            .map(|k| {
+                let clustering = k_means(points, k, metric, None, None, None);
+                let log_inertia: Vec<f32> = reference_sets
+                    .iter()
+                    .map(|set| log_dispersion(k_means(set, k, metric, None, None, None).inertia))
+                    .collect();
+                let mean = log_inertia.iter().sum::<f32>() / references as f32;
+                let deviation = (log_inertia
+                    .iter()
+                    .map(|value| (value - mean).powi(2))
+                    .sum::<f32>()
+                    / references as f32)
+                    .sqrt();
+
+                Candidate {
+                    k,
+                    inertia: clustering.inertia,
+                    silhouette: silhouette(points, &clustering.labels, metric),
+                    gap: mean - log_dispersion(clustering.inertia),
+                    gap_error: deviation * (1.0 + 1.0 / references as f32).sqrt(),
+                }
+            })
+            .collect();
+
+        let gap = candidates
+            .windows(2)
+            .find(|pair| pair[0].gap >= pair[1].gap - pair[1].gap_error)
+            .or(candidates.last().map(std::slice::from_ref))
+            .map_or(1, |pair| pair[0].k);
+        let best_silhouette = candidates
+            .iter()
+            .filter_map(|candidate| Some((candidate.k, candidate.silhouette?)))
+            .max_by(|first, second| first.1.total_cmp(&second.1))
+            .map(|(k, _)| k);
+
+        Ok(Self {
+            elbow: elbow(&candidates),
+            best_silhouette,
+            gap,
+            candidates,
+        })
+    }
+
+    /// The gap statistic is the most reliable of the three, it also detects
+    /// data without any clusters (k = 1)
+    pub fn suggested(&self) -> usize {
+        self.gap
+    }
+
+    /// Curves of the inertia, the silhouette and the gap over `k`. The curves are
+    /// of different scales, so each one is stretched over the height of the plot.
+    pub fn plot<T: AsRef<Path>>(&self, path: T) -> Result<Image<T>> {
+        let max_k = self.candidates.len().max(1) as f32;
+        let rect = Rectangle::new(
+            Point::new(1.0 - K_PADDING, -SCORE_PADDING),
+            Point::new(max_k + K_PADDING, 1.0 + SCORE_PADDING),
+        )?;
+
+        let mut drawing = Image::new(path, rect, true, Some(PLOT_WIDTH), Some(PLOT_HEIGHT));
+        drawing.set_title(Message::ModelSelectionTitle.to_string());
+        drawing.set_x_axis_title(Message::ClustersCountAxis.to_string());
+        drawing.set_y_axis_title(Message::NormalizedScoreAxis.to_string());
+        drawing.set_legend(Some(FixedPosition::TopRight(-2.0, -2.0)));
+        drawing.set_class_label(INERTIA_CLASS, Message::InertiaLabel.to_string());
+        drawing.set_class_label(SILHOUETTE_CLASS, Message::SilhouetteLabel.to_string());
+        drawing.set_class_label(GAP_CLASS, Message::GapLabel.to_string());
+
+        let suggested = self.suggested() as f32;
+        drawing.draw_polyline(
+            &[Point::new(suggested, 0.0), Point::new(suggested, 1.0)],
+            Some(SUGGESTED_LINE_COLOR),
+        );
+        let style = TextStyle {
+            horizontal_align: HorizontalAlign::Center,
+            vertical_align: VerticalAlign::Middle,
+            ..drawing.text_style()
+        };
+        for candidate in &self.candidates {
+            let anchor = Point::new(candidate.k as f32, -SCORE_PADDING / 2.0);
+            drawing.write_styled(anchor, candidate.k.to_string(), None, &style);
+        }
+
+        let curves: [(usize, Vec<(usize, f32)>); 3] = [
+            (
+                INERTIA_CLASS,
+                self.candidates.iter().map(|c| (c.k, c.inertia)).collect(),
+            ),
+            (
+                SILHOUETTE_CLASS,
+                self.candidates
+                    .iter()
+                    .filter_map(|c| Some((c.k, c.silhouette?)))
+                    .collect(),
+            ),
+            (
+                GAP_CLASS,
+                self.candidates.iter().map(|c| (c.k, c.gap)).collect(),
+            ),
+        ];
+        for (class, values) in curves {
+            let min = values.iter().map(|v| v.1).fold(f32::INFINITY, f32::min);
+            let max = values.iter().map(|v| v.1).fold(f32::NEG_INFINITY, f32::max);
+            let range = if max > min { max - min } else { 1.0 };
+            let curve: Vec<Point> = values
+                .iter()
+                .map(|(k, value)| Point::new(*k as f32, (value - min) / range))
+                .collect();
+
+            // Markers go first, they are not drawn over other colors
+            for &point in &curve {
+                let _ = drawing.draw_point_with_class(point, class, false);
+            }
+            let color = drawing.class_color(class).1;
+            drawing.draw_polyline(&curve, Some(color));
+        }
+        Ok(drawing)
+    }
+}
+
+impl Display for ModelSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", Message::ModelSelectionHeader)?;
+        for candidate in &self.candidates {
+            let silhouette = match candidate.silhouette {
+                Some(value) => value.round_to_dp(3).to_string(),
+                None => Message::Undefined.to_string(),
+            };
+            writeln!(
+                f,
+                "{} | {} | {} | {} ± {}",
+                candidate.k,
+                candidate.inertia.round_to_dp(2),
+                silhouette,
+                candidate.gap.round_to_dp(3),
+                candidate.gap_error.round_to_dp(3)
+            )?;
+        }
+
+        let best_silhouette = match self.best_silhouette {
+            Some(k) => k.to_string(),
+            None => Message::Undefined.to_string(),
+        };
+        write!(
+            f,
+            "{}",
+            Message::SuggestedClustersCount {
+                elbow: &self.elbow,
+                silhouette: &best_silhouette,
+                gap: &self.gap
+            }
+        )
+    }
+}
+
+fn random_point<P: Features>((lowest, highest): &(P, P)) -> P {
+    let coordinates: Vec<f32> = lowest
+        .coordinates()
+        .as_ref()
+        .iter()
+        .zip(highest.coordinates().as_ref())
+        .map(|(low, high)| low + rand::random::<f32>() * (high - low))
+        .collect();
+    P::from_coordinates(&coordinates)
+}
+
+/// Lowest and highest value of every feature, `None` without points
+fn bounding_box<P: Features>(points: &[P]) -> Option<(P, P)> {
+    let first = points.first()?.coordinates().as_ref().to_vec();
+    let (mut lowest, mut highest) = (first.clone(), first);
+    for point in points {
+        for (axis, &coordinate) in point.coordinates().as_ref().iter().enumerate() {
+            if let (Some(low), Some(high)) = (lowest.get_mut(axis), highest.get_mut(axis)) {
+                *low = low.min(coordinate);
+                *high = high.max(coordinate);
+            }
+        }
+    }
+    Some((P::from_coordinates(&lowest), P::from_coordinates(&highest)))
+}
+
+/// Logarithm of the inertia, coinciding points would give minus infinity
+fn log_dispersion(inertia: f32) -> f32 {
+    inertia.max(f32::MIN_POSITIVE).ln()
+}
+
+/// `k` with the inertia furthest below the straight line from the first to the last candidate
+fn elbow(candidates: &[Candidate]) -> usize {
+    let (Some(first), Some(last)) = (candidates.first(), candidates.last()) else {
+        return 1;
+    };
+    if last.k == first.k {
+        return first.k;
+    }
+    let slope = (last.inertia - first.inertia) / (last.k - first.k) as f32;
+    candidates
+        .iter()
+        .map(|candidate| {
+            let chord = first.inertia + slope * (candidate.k - first.k) as f32;
+            (candidate.k, chord - candidate.inertia)
+        })
+        .max_by(|first, second| first.1.total_cmp(&second.1))
+        .map_or(1, |(k, _)| k)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        clustering::ModelSelection, distance::Euclidean, geometry::Point, utils::rand_normal,
+    };
+
+    /// Three tight groups at the corners of a triangle far apart from each other
+    fn blobs() -> Vec<Point> {
+        let centers = [(0.0, 0.0), (100.0, 0.0), (50.0, 87.0)];
+        (0..150)
+            .map(|i| {
+                let (x, y) = centers[i % centers.len()];
+                Point::new(rand_normal(x, 1.0), rand_normal(y, 1.0))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_method_finds_the_groups() {
+        let selection = ModelSelection::new(&blobs(), 8, &Euclidean, None, None).unwrap();
+
+        assert_eq!(selection.candidates.len(), 8);
+        assert_eq!(selection.elbow, 3);
+        assert_eq!(selection.best_silhouette, Some(3));
+        assert_eq!(selection.gap, 3);
+        assert_eq!(selection.suggested(), 3);
+        assert_eq!(selection.candidates[0].silhouette, None);
+    }
+
+    #[test]
+    fn counts_are_limited_by_the_points() {
+        let points = &blobs()[..3];
+        let selection = ModelSelection::new(points, 8, &Euclidean, None, None).unwrap();
+
+        let ks: Vec<usize> = selection.candidates.iter().map(|c| c.k).collect();
+        assert_eq!(ks, [1, 2, 3]);
+        assert!(ModelSelection::new::<Point>(&[], 8, &Euclidean, None, None).is_err());
+    }
+}
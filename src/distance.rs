@@ -0,0 +1,257 @@
+use crate::{
+    error::{Error, Result},
+    messages::Message,
+    vector::{Features, Matrix},
+};
+
+/// Distance between two points of a feature space, given by their coordinates.
+/// Every clustering routine and validity index takes one, [`Euclidean`] is the
+/// usual choice. Points of different dimensions are compared by the common features.
+pub trait Metric: Send + Sync {
+    fn distance(&self, first: &[f32], second: &[f32]) -> f32;
+
+    /// Name of the metric for the user
+    fn name(&self) -> Message<'_>;
+}
+
+impl dyn Metric + '_ {
+    /// Distance between the points of any [`Features`] type
+    pub fn between<P: Features>(&self, first: &P, second: &P) -> f32 {
+        self.distance(first.coordinates().as_ref(), second.coordinates().as_ref())
+    }
+}
+
+/// Differences of the coordinates, without the sign
+fn differences<'a>(first: &'a [f32], second: &'a [f32]) -> impl Iterator<Item = f32> + 'a {
+    first
+        .iter()
+        .zip(second)
+        .map(|(first, second)| (first - second).abs())
+}
+
+/// Straight line distance, the same as [`Point::distance_to`](crate::geometry::Point::distance_to)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Euclidean;
+
+impl Metric for Euclidean {
+    fn distance(&self, first: &[f32], second: &[f32]) -> f32 {
+        SquaredEuclidean.distance(first, second).sqrt()
+    }
+
+    fn name(&self) -> Message<'_> {
+        Message::EuclideanMetric
+    }
+}
+
+/// Square of the [`Euclidean`] distance. Not a true metric (breaks the triangle
+/// inequality), but keeps the same nearest points and penalises outliers more
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SquaredEuclidean;
+
+impl Metric for SquaredEuclidean {
+    fn distance(&self, first: &[f32], second: &[f32]) -> f32 {
+        differences(first, second)
+            .map(|difference| difference.powi(2))
+            .sum()
+    }
+
+    fn name(&self) -> Message<'_> {
+        Message::SquaredEuclideanMetric
+    }
+}
+
+/// Sum of the differences along the axes (city block), circles of it are diamonds
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Manhattan;
+
+impl Metric for Manhattan {
+    fn distance(&self, first: &[f32], second: &[f32]) -> f32 {
+        differences(first, second).sum()
+    }
+
+    fn name(&self) -> Message<'_> {
+        Message::ManhattanMetric
+    }
+}
+
+/// Largest of the differences along the axes, circles of it are squares
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Chebyshev;
+
+impl Metric for Chebyshev {
+    fn distance(&self, first: &[f32], second: &[f32]) -> f32 {
+        differences(first, second).fold(0.0, f32::max)
+    }
+
+    fn name(&self) -> Message<'_> {
+        Message::ChebyshevMetric
+    }
+}
+
+/// `p`-th root of the sum of the `p`-th powers of the differences: [`Manhattan`]
+/// for 1, [`Euclidean`] for 2 and closer to [`Chebyshev`] as `p` grows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Minkowski {
+    p: f32,
+}
+
+impl Minkowski {
+    /// Orders below 1 do not give a metric
+    pub fn new(p: f32) -> Result<Self> {
+        if p.is_finite() && p >= 1.0 {
+            Ok(Self { p })
+        } else {
+            Err(Error::InvalidMinkowskiOrder(p))
+        }
+    }
+
+    pub fn p(&self) -> f32 {
+        self.p
+    }
+}
+
+impl Metric for Minkowski {
+    fn distance(&self, first: &[f32], second: &[f32]) -> f32 {
+        differences(first, second)
+            .map(|difference| difference.powf(self.p))
+            .sum::<f32>()
+            .powf(1.0 / self.p)
+    }
+
+    fn name(&self) -> Message<'_> {
+        Message::MinkowskiMetric { p: &self.p }
+    }
+}
+
+/// One minus the cosine of the angle between the points seen from the origin,
+/// in [0; 2]. Only the directions matter, the origin is 1 away from everything.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Cosine;
+
+impl Metric for Cosine {
+    fn distance(&self, first: &[f32], second: &[f32]) -> f32 {
+        let length = |coordinates: &[f32]| coordinates.iter().map(|c| c * c).sum::<f32>().sqrt();
+        let lengths = length(first) * length(second);
+        if lengths == 0.0 {
+            return 1.0;
+        }
+        let dot: f32 = first.iter().zip(second).map(|(a, b)| a * b).sum();
+        1.0 - (dot / lengths).clamp(-1.0, 1.0)
+    }
+
+    fn name(&self) -> Message<'_> {
+        Message::CosineMetric
+    }
+}
+
+/// Euclidean distance after the data is decorrelated and scaled to unit variance
+/// by the covariance matrix, circles of it are ellipses of the data's shape
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mahalanobis {
+    inverse: Matrix,
+}
+
+impl Mahalanobis {
+    /// Covariance must be symmetric and positive definite
+    pub fn new(covariance: &Matrix) -> Result<Self> {
+        if !covariance.is_symmetric() || covariance.cholesky().is_none() {
+            return Err(Error::SingularCovariance);
+        }
+        let inverse = covariance.inverse().ok_or(Error::SingularCovariance)?;
+        Ok(Self { inverse })
+    }
+
+    /// Metric with the sample covariance of the points
+    pub fn fit<P: Features>(points: &[P]) -> Result<Self> {
+        if points.len() < 2 {
+            return Err(Error::SingularCovariance);
+        }
+        Self::new(&Matrix::covariance(points)?)
+    }
+}
+
+impl Metric for Mahalanobis {
+    fn distance(&self, first: &[f32], second: &[f32]) -> f32 {
+        let difference: Vec<f32> = first.iter().zip(second).map(|(a, b)| a - b).collect();
+        self.inverse.quadratic_form(&difference).max(0.0).sqrt()
+    }
+
+    fn name(&self) -> Message<'_> {
+        Message::MahalanobisMetric
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        distance::{
+            Chebyshev, Cosine, Euclidean, Mahalanobis, Manhattan, Metric, Minkowski,
+            SquaredEuclidean,
+        },
+        error::Error,
+        vector::Matrix,
+    };
+
+    const FIRST: [f32; 3] = [1.0, -2.0, 4.0];
+    const SECOND: [f32; 3] = [4.0, 2.0, 4.0];
+
+    fn close(first: f32, second: f32) -> bool {
+        (first - second).abs() < 1e-4
+    }
+
+    #[test]
+    fn axis_metrics_of_known_points() {
+        assert_eq!(Euclidean.distance(&FIRST, &SECOND), 5.0);
+        assert_eq!(SquaredEuclidean.distance(&FIRST, &SECOND), 25.0);
+        assert_eq!(Manhattan.distance(&FIRST, &SECOND), 7.0);
+        assert_eq!(Chebyshev.distance(&FIRST, &SECOND), 4.0);
+        assert_eq!(Chebyshev.distance(&FIRST, &FIRST), 0.0);
+    }
+
+    #[test]
+    fn minkowski_joins_the_axis_metrics() {
+        let manhattan = Minkowski::new(1.0).unwrap();
+        let euclidean = Minkowski::new(2.0).unwrap();
+        let high = Minkowski::new(50.0).unwrap();
+        assert!(close(manhattan.distance(&FIRST, &SECOND), 7.0));
+        assert!(close(euclidean.distance(&FIRST, &SECOND), 5.0));
+        assert!((high.distance(&FIRST, &SECOND) - 4.0).abs() < 0.1);
+
+        for p in [0.5, 0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert!(matches!(
+                Minkowski::new(p),
+                Err(Error::InvalidMinkowskiOrder(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn cosine_depends_on_the_directions_only() {
+        assert!(close(Cosine.distance(&[1.0, 0.0], &[5.0, 0.0]), 0.0));
+        assert!(close(Cosine.distance(&[1.0, 0.0], &[0.0, 3.0]), 1.0));
+        assert!(close(Cosine.distance(&[1.0, 1.0], &[-2.0, -2.0]), 2.0));
+        assert!(close(Cosine.distance(&[0.0, 0.0], &[1.0, 2.0]), 1.0));
+        assert!(close(
+            Cosine.distance(&FIRST, &SECOND),
+            Cosine.distance(&[2.0, -4.0, 8.0], &[0.4, 0.2, 0.4])
+        ));
+    }
+
+    #[test]
+    fn mahalanobis_with_identity_covariance_is_euclidean() {
+        let mahalanobis = Mahalanobis::new(&Matrix::identity(3)).unwrap();
+        assert!(close(mahalanobis.distance(&FIRST, &SECOND), 5.0));
+
+        // Variance 4 along the first axis halves the distances along it
+        let stretched = Matrix::from_rows(&[vec![4.0, 0.0], vec![0.0, 1.0]]).unwrap();
+        let mahalanobis = Mahalanobis::new(&stretched).unwrap();
+        assert!(close(mahalanobis.distance(&[0.0, 0.0], &[4.0, 0.0]), 2.0));
+        assert!(close(mahalanobis.distance(&[0.0, 0.0], &[0.0, 4.0]), 4.0));
+
+        let singular = Matrix::from_rows(&[vec![1.0, 1.0], vec![1.0, 1.0]]).unwrap();
+        assert!(matches!(
+            Mahalanobis::new(&singular),
+            Err(Error::SingularCovariance)
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+use std::{fmt::Display, io, path::PathBuf};
+
+use crate::{
+    font::FontError,
+    geometry::{Point, Rectangle},
+    messages::Message,
+};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors returned by the library, the binary turns them into messages for the user.
+#[derive(Debug)]
+pub enum Error {
+    /// Top right corner lies to the left of or below the bottom left one
+    InvalidRectangle {
+        bottom_left: Point,
+        top_right: Point,
+    },
+    InvalidHexColor {
+        hex: String,
+        reason: String,
+    },
+    /// Alpha channel outside of (0; 1)
+    AlphaOutOfRange(f32),
+    /// Bounds can not be fitted to a set without finite points
+    EmptyPointSet,
+    /// Aspect ratio (width / height) that is not a positive number
+    InvalidAspectRatio(f32),
+    Font(FontError),
+    /// Picture or animation could not be written
+    ImageIo {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// External program used to open the picture could not be started
+    Viewer {
+        command: String,
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// Reading the user's answers failed
+    Input(io::Error),
+    /// Minkowski distance is a metric only for orders from 1
+    InvalidMinkowskiOrder(f32),
+    /// Covariance matrix that can not be inverted (or is not a covariance at all)
+    SingularCovariance,
+    /// Vectors or matrices of different dimensions where they must match
+    DimensionMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// Feature index (counted from 0) not less than the dimension
+    FeatureOutOfRange {
+        feature: usize,
+        dimension: usize,
+    },
+    /// Fuzzy c-means needs a fuzzifier above 1
+    InvalidFuzzifier(f32),
+    /// Point lies outside of the field of the picture and was not drawn
+    PointOutOfField {
+        point: Point,
+        rect: Rectangle,
+    },
+    /// Point would cover another one and was not drawn
+    PointOverlap(Point),
+    /// Point has no positive membership to blend the colors of the classes by
+    NoMemberships(Point),
+    /// Frames can only be taken from raster pictures
+    NotRasterImage(PathBuf),
+    /// Animation without a single captured frame
+    NoFrames(PathBuf),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidRectangle {
+                bottom_left,
+                top_right,
+            } => Message::InvalidRectangle {
+                width: &(top_right.x - bottom_left.x),
+                height: &(top_right.y - bottom_left.y),
+                bottom_left,
+                top_right,
+            }
+            .fmt(f),
+            Error::InvalidHexColor { hex, reason } => {
+                Message::InvalidHexColor { hex, reason }.fmt(f)
+            }
+            Error::AlphaOutOfRange(alpha) => Message::AlphaOutOfRange { alpha }.fmt(f),
+            Error::EmptyPointSet => Message::EmptyPointSet.fmt(f),
+            Error::InvalidAspectRatio(ratio) => Message::InvalidAspectRatio { ratio }.fmt(f),
+            Error::Font(error) => Message::FontLoadFailed { error }.fmt(f),
+            Error::ImageIo { path, source } => Message::ImageSaveFailed {
+                path: &path.to_string_lossy(),
+                error: source,
+            }
+            .fmt(f),
+            Error::Viewer {
+                command,
+                path,
+                source,
+            } => Message::ViewerFailed {
+                path: &path.to_string_lossy(),
+                command,
+                error: source,
+            }
+            .fmt(f),
+            Error::Input(error) => Message::InputFailed { error }.fmt(f),
+            Error::InvalidMinkowskiOrder(p) => Message::InvalidMinkowskiOrder { p }.fmt(f),
+            Error::SingularCovariance => Message::SingularCovariance.fmt(f),
+            Error::DimensionMismatch { expected, found } => {
+                Message::DimensionMismatch { expected, found }.fmt(f)
+            }
+            Error::FeatureOutOfRange { feature, dimension } => {
+                Message::FeatureOutOfRange { feature, dimension }.fmt(f)
+            }
+            Error::InvalidFuzzifier(m) => Message::InvalidFuzzifier { m }.fmt(f),
+            Error::PointOutOfField { point, rect } => {
+                Message::PointOutOfField { point, rect }.fmt(f)
+            }
+            Error::PointOverlap(point) => Message::PointOverlap { point }.fmt(f),
+            Error::NoMemberships(point) => Message::NoMemberships { point }.fmt(f),
+            Error::NotRasterImage(path) => Message::NotRasterImage {
+                path: &path.to_string_lossy(),
+            }
+            .fmt(f),
+            Error::NoFrames(path) => Message::NoFrames {
+                path: &path.to_string_lossy(),
+            }
+            .fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Font(err) => Some(err),
+            Error::ImageIo { source, .. } | Error::Viewer { source, .. } => Some(source),
+            Error::Input(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<FontError> for Error {
+    fn from(err: FontError) -> Self {
+        Error::Font(err)
+    }
+}
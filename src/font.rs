@@ -1,4 +1,8 @@
-pub const AVAILABLE_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 .";
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::messages::Message;
+
+pub const AVAILABLE_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyzАБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯабвгдеёжзийклмнопрстуфхцчшщъыьэюя0123456789 .-+=:;,()[]%/<>!?_№";
 
 pub const SYMBOL_WIDTH: usize = 6;
 pub const SYMBOL_HEIGHT: usize = 5;
@@ -63,6 +67,101 @@ impl CharSymbol {
             'X' => Self(X),
             'Y' => Self(Y),
             'Z' => Self(Z),
+            // Lowercase Alphabet
+            'a' => Self(SMALL_A),
+            'b' => Self(SMALL_B),
+            'c' => Self(SMALL_C),
+            'd' => Self(SMALL_D),
+            'e' => Self(SMALL_E),
+            'f' => Self(SMALL_F),
+            'g' => Self(SMALL_G),
+            'h' => Self(SMALL_H),
+            'i' => Self(SMALL_I),
+            'j' => Self(SMALL_J),
+            'k' => Self(SMALL_K),
+            'l' => Self(SMALL_L),
+            'm' => Self(SMALL_M),
+            'n' => Self(SMALL_N),
+            'o' => Self(SMALL_O),
+            'p' => Self(SMALL_P),
+            'q' => Self(SMALL_Q),
+            'r' => Self(SMALL_R),
+            's' => Self(SMALL_S),
+            't' => Self(SMALL_T),
+            'u' => Self(SMALL_U),
+            'v' => Self(SMALL_V),
+            'w' => Self(SMALL_W),
+            'x' => Self(SMALL_X),
+            'y' => Self(SMALL_Y),
+            'z' => Self(SMALL_Z),
+            // Cyrillic Uppercase Alphabet
+            'А' => Self(A),
+            'Б' => Self(CYR_BE),
+            'В' => Self(B),
+            'Г' => Self(CYR_GE),
+            'Д' => Self(CYR_DE),
+            'Е' => Self(E),
+            'Ё' => Self(CYR_YO),
+            'Ж' => Self(CYR_ZHE),
+            'З' => Self(CYR_ZE),
+            'И' => Self(CYR_I),
+            'Й' => Self(CYR_SHORT_I),
+            'К' => Self(K),
+            'Л' => Self(CYR_EL),
+            'М' => Self(M),
+            'Н' => Self(H),
+            'О' => Self(O),
+            'П' => Self(CYR_PE),
+            'Р' => Self(P),
+            'С' => Self(C),
+            'Т' => Self(T),
+            'У' => Self(CYR_U),
+            'Ф' => Self(CYR_EF),
+            'Х' => Self(X),
+            'Ц' => Self(CYR_TSE),
+            'Ч' => Self(CYR_CHE),
+            'Ш' => Self(CYR_SHA),
+            'Щ' => Self(CYR_SHCHA),
+            'Ъ' => Self(CYR_HARD_SIGN),
+            'Ы' => Self(CYR_YERU),
+            'Ь' => Self(CYR_SOFT_SIGN),
+            'Э' => Self(CYR_E),
+            'Ю' => Self(CYR_YU),
+            'Я' => Self(CYR_YA),
+            // Cyrillic Lowercase Alphabet
+            'а' => Self(SMALL_A),
+            'б' => Self(CYR_SMALL_BE),
+            'в' => Self(CYR_SMALL_VE),
+            'г' => Self(CYR_SMALL_GE),
+            'д' => Self(CYR_SMALL_DE),
+            'е' => Self(SMALL_E),
+            'ё' => Self(CYR_SMALL_YO),
+            'ж' => Self(CYR_SMALL_ZHE),
+            'з' => Self(CYR_SMALL_ZE),
+            'и' => Self(CYR_SMALL_I),
+            'й' => Self(CYR_SMALL_SHORT_I),
+            'к' => Self(CYR_SMALL_KA),
+            'л' => Self(CYR_SMALL_EL),
+            'м' => Self(CYR_SMALL_EM),
+            'н' => Self(CYR_SMALL_EN),
+            'о' => Self(SMALL_O),
+            'п' => Self(CYR_SMALL_PE),
+            'р' => Self(SMALL_P),
+            'с' => Self(SMALL_C),
+            'т' => Self(CYR_SMALL_TE),
+            'у' => Self(SMALL_Y),
+            'ф' => Self(CYR_EF),
+            'х' => Self(SMALL_X),
+            'ц' => Self(CYR_SMALL_TSE),
+            'ч' => Self(CYR_SMALL_CHE),
+            'ш' => Self(CYR_SMALL_SHA),
+            'щ' => Self(CYR_SMALL_SHCHA),
+            'ъ' => Self(CYR_SMALL_HARD_SIGN),
+            'ы' => Self(CYR_SMALL_YERU),
+            'ь' => Self(CYR_SMALL_SOFT_SIGN),
+            'э' => Self(CYR_SMALL_E),
+            'ю' => Self(CYR_SMALL_YU),
+            'я' => Self(CYR_SMALL_YA),
             // Digits
             '0' => Self(ZERO),
             '1' => Self(ONE),
@@ -77,38 +176,572 @@ impl CharSymbol {
             // Punctuation
             ' ' => Self(BACKSPACE),
             '.' => Self(POINT),
+            '-' => Self(MINUS),
+            '+' => Self(PLUS),
+            '=' => Self(EQUALS),
+            ':' => Self(COLON),
+            ';' => Self(SEMICOLON),
+            ',' => Self(COMMA),
+            '(' => Self(LEFT_PARENTHESIS),
+            ')' => Self(RIGHT_PARENTHESIS),
+            '[' => Self(LEFT_BRACKET),
+            ']' => Self(RIGHT_BRACKET),
+            '%' => Self(PERCENT),
+            '/' => Self(SLASH),
+            '<' => Self(LESS_THAN),
+            '>' => Self(GREATER_THAN),
+            '!' => Self(EXCLAMATION),
+            '?' => Self(QUESTION),
+            '_' => Self(UNDERSCORE),
+            '№' => Self(NUMERO),
             // Unknown char
             _ => Self(UNKNOWN),
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::font::{AVAILABLE_CHARS, CharSymbol, SYMBOL_HEIGHT, SYMBOL_WIDTH, UNKNOWN};
+/// Monochrome glyph bitmap, `pixels` are stored row by row starting from the top row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glyph {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<bool>,
+}
 
-    #[test]
-    fn chars_consistency() {
-        for char in AVAILABLE_CHARS.chars() {
-            let symbol = CharSymbol::get(char);
-            assert_ne!(symbol.0, UNKNOWN);
-            assert_eq!(
-                symbol.height(),
-                SYMBOL_HEIGHT,
-                "height mismatch (got {} instead of {}) {}",
-                symbol.height(),
-                SYMBOL_HEIGHT,
-                symbol.0
-            );
-            assert_eq!(
-                symbol.width(),
-                SYMBOL_WIDTH,
-                "width mismatch (got {} instead of {}) {}",
-                symbol.width(),
-                SYMBOL_WIDTH,
-                symbol.0
-            );
+impl Glyph {
+    pub fn empty(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    pub fn is_set(&self, column: usize, row: usize) -> bool {
+        column < self.width && row < self.height && self.pixels[row * self.width + column]
+    }
+
+    pub fn set(&mut self, column: usize, row: usize) {
+        if column < self.width && row < self.height {
+            self.pixels[row * self.width + column] = true;
+        }
+    }
+
+    /// Every pixel becomes a `factor`x`factor` square.
+    pub fn scaled(&self, factor: usize) -> Glyph {
+        let mut scaled = Glyph::empty(self.width * factor, self.height * factor);
+        for row in 0..scaled.height {
+            for column in 0..scaled.width {
+                if self.is_set(column / factor, row / factor) {
+                    scaled.set(column, row);
+                }
+            }
+        }
+        scaled
+    }
+
+    pub fn rotated(&self, rotation: Rotation) -> Glyph {
+        match rotation {
+            Rotation::None => self.clone(),
+            Rotation::Clockwise => {
+                let mut rotated = Glyph::empty(self.height, self.width);
+                for row in 0..self.height {
+                    for column in 0..self.width {
+                        if self.is_set(column, row) {
+                            rotated.set(self.height - 1 - row, column);
+                        }
+                    }
+                }
+                rotated
+            }
+            Rotation::CounterClockwise => {
+                let mut rotated = Glyph::empty(self.height, self.width);
+                for row in 0..self.height {
+                    for column in 0..self.width {
+                        if self.is_set(column, row) {
+                            rotated.set(row, self.width - 1 - column);
+                        }
+                    }
+                }
+                rotated
+            }
+        }
+    }
+}
+
+impl From<CharSymbol> for Glyph {
+    fn from(symbol: CharSymbol) -> Self {
+        let lines: Vec<&str> = symbol.0.lines().filter(|line| !line.is_empty()).collect();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut pixels = Vec::with_capacity(width * lines.len());
+        for line in &lines {
+            let mut chars = line.chars();
+            for _ in 0..width {
+                pixels.push(chars.next().is_some_and(|char| char != ' '));
+            }
+        }
+        Self {
+            width,
+            height: lines.len(),
+            pixels,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum HorizontalAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    #[default]
+    Bottom,
+}
+
+/// Rotation of the text by 90°, `CounterClockwise` text reads from bottom to top
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise,
+    CounterClockwise,
+}
+
+/// How text is rendered relative to its anchor point. All sizes are in output pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStyle {
+    /// Output pixels per glyph pixel
+    pub scale: u32,
+    pub bold: bool,
+    pub horizontal_align: HorizontalAlign,
+    pub vertical_align: VerticalAlign,
+    pub rotation: Rotation,
+    /// Shift from the anchor, X points right and Y points up
+    pub offset: (i32, i32),
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            bold: false,
+            horizontal_align: HorizontalAlign::default(),
+            vertical_align: VerticalAlign::default(),
+            rotation: Rotation::default(),
+            offset: (0, 0),
+        }
+    }
+}
+
+impl TextStyle {
+    /// Position of the top left corner of a `width`x`height` text box relative
+    /// to the anchor (pixel Y axis points down).
+    pub fn top_left_offset(&self, width: u32, height: u32) -> (i32, i32) {
+        let x = match self.horizontal_align {
+            HorizontalAlign::Left => 0,
+            HorizontalAlign::Center => -(width as i32) / 2,
+            HorizontalAlign::Right => -(width as i32),
+        };
+        let y = match self.vertical_align {
+            VerticalAlign::Top => 0,
+            VerticalAlign::Middle => -(height as i32) / 2,
+            VerticalAlign::Bottom => -(height as i32),
+        };
+        (x + self.offset.0, y - self.offset.1)
+    }
+}
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(std::io::Error),
+    UnknownFormat,
+    Malformed(String),
+}
+
+impl std::fmt::Display for FontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontError::Io(error) => Message::FontReadFailed { error }.fmt(f),
+            FontError::UnknownFormat => Message::FontUnknownFormat.fmt(f),
+            FontError::Malformed(reason) => Message::FontMalformed { reason }.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+impl From<std::io::Error> for FontError {
+    fn from(err: std::io::Error) -> Self {
+        FontError::Io(err)
+    }
+}
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE_512: u8 = 0x01;
+const PSF1_MODE_HAS_TAB: u8 = 0x02;
+const PSF1_SEPARATOR: u16 = 0xFFFF;
+const PSF1_START_SEQ: u16 = 0xFFFE;
+
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+const PSF2_SEPARATOR: u8 = 0xFF;
+const PSF2_START_SEQ: u8 = 0xFE;
+
+/// Bitmap font used for text rendering. Characters missing from a loaded font
+/// are taken from the built-in [`CharSymbol`] glyphs.
+#[derive(Debug, Clone)]
+pub struct BitmapFont {
+    glyphs: HashMap<char, Glyph>,
+    height: usize,
+    letter_spacing: usize,
+}
+
+impl Default for BitmapFont {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl BitmapFont {
+    /// Built-in 6x5 font
+    pub fn builtin() -> Self {
+        Self {
+            glyphs: HashMap::new(),
+            height: SYMBOL_HEIGHT,
+            letter_spacing: 1,
+        }
+    }
+
+    /// Loads a BDF or PSF (version 1 or 2) font, the format is detected by the file contents.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, FontError> {
+        let bytes = fs::read(path)?;
+        if bytes.starts_with(&PSF1_MAGIC) || bytes.starts_with(&PSF2_MAGIC) {
+            Self::parse_psf(&bytes)
+        } else if bytes.starts_with(b"STARTFONT") {
+            let text = String::from_utf8_lossy(&bytes);
+            Self::parse_bdf(&text)
+        } else {
+            Err(FontError::UnknownFormat)
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn letter_spacing(&self) -> usize {
+        self.letter_spacing
+    }
+
+    pub fn glyph(&self, char: char) -> Glyph {
+        self.glyphs
+            .get(&char)
+            .cloned()
+            .unwrap_or_else(|| CharSymbol::get(char).into())
+    }
+
+    /// Size of the rendered text in pixels (width; height), without drawing it.
+    pub fn measure(&self, text: &str, style: &TextStyle) -> (u32, u32) {
+        let (width, height) = self.line_size(text, style.bold);
+        let (width, height) = (
+            width as u32 * style.scale.max(1),
+            height as u32 * style.scale.max(1),
+        );
+        match style.rotation {
+            Rotation::None => (width, height),
+            Rotation::Clockwise | Rotation::CounterClockwise => (height, width),
+        }
+    }
+
+    /// Renders a line of text into a single bitmap with the style's scale, weight and rotation applied.
+    pub fn render(&self, text: &str, style: &TextStyle) -> Glyph {
+        let (width, height) = self.line_size(text, style.bold);
+        let mut line = Glyph::empty(width, height);
+
+        let mut x = 0;
+        for char in text.chars() {
+            let glyph = self.glyph(char);
+            // Glyphs shorter than the font (built-in fallback) stand on the bottom line
+            let y = height.saturating_sub(glyph.height);
+            for row in 0..glyph.height {
+                for column in 0..glyph.width {
+                    if glyph.is_set(column, row) {
+                        line.set(x + column, y + row);
+                        if style.bold {
+                            line.set(x + column + 1, y + row);
+                        }
+                    }
+                }
+            }
+            x += glyph.width + style.bold as usize + self.letter_spacing;
+        }
+
+        line.scaled(style.scale.max(1) as usize)
+            .rotated(style.rotation)
+    }
+
+    fn line_size(&self, text: &str, bold: bool) -> (usize, usize) {
+        let mut width = 0;
+        let mut height = self.height;
+        for char in text.chars() {
+            let glyph = self.glyph(char);
+            width += glyph.width + bold as usize + self.letter_spacing;
+            height = height.max(glyph.height);
+        }
+        (width.saturating_sub(self.letter_spacing), height)
+    }
+
+    pub fn parse_bdf(source: &str) -> Result<Self, FontError> {
+        let malformed = |reason: &str| FontError::Malformed(format!("BDF: {}", reason));
+        let numbers = |rest: &str| -> Result<Vec<i32>, FontError> {
+            rest.split_whitespace()
+                .map(|value| value.parse::<i32>())
+                .collect::<Result<_, _>>()
+                .map_err(|err| malformed(&err.to_string()))
+        };
+
+        let mut bounding_box: Option<(i32, i32, i32, i32)> = None;
+        let mut glyphs = HashMap::new();
+
+        let mut lines = source.lines();
+        while let Some(line) = lines.next() {
+            let (keyword, rest) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+            match keyword {
+                "FONTBOUNDINGBOX" => {
+                    let values = numbers(rest)?;
+                    if values.len() != 4 {
+                        return Err(malformed("FONTBOUNDINGBOX"));
+                    }
+                    bounding_box = Some((values[0], values[1], values[2], values[3]));
+                }
+                "STARTCHAR" => {
+                    let (_, font_height, _, font_y_offset) =
+                        bounding_box.ok_or_else(|| malformed("нет FONTBOUNDINGBOX"))?;
+                    let ascent = font_height + font_y_offset;
+
+                    let mut encoding: Option<i32> = None;
+                    let mut advance: Option<i32> = None;
+                    let mut glyph_box = (0, 0, 0, 0);
+                    let mut rows: Vec<u32> = Vec::new();
+                    let mut in_bitmap = false;
+
+                    for line in lines.by_ref() {
+                        let (keyword, rest) =
+                            line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+                        match keyword {
+                            "ENCODING" => encoding = numbers(rest)?.first().copied(),
+                            "DWIDTH" => advance = numbers(rest)?.first().copied(),
+                            "BBX" => {
+                                let values = numbers(rest)?;
+                                if values.len() != 4 {
+                                    return Err(malformed("BBX"));
+                                }
+                                glyph_box = (values[0], values[1], values[2], values[3]);
+                            }
+                            "BITMAP" => in_bitmap = true,
+                            "ENDCHAR" => break,
+                            row if in_bitmap && !row.is_empty() => {
+                                rows.push(
+                                    u32::from_str_radix(row, 16)
+                                        .map_err(|err| malformed(&err.to_string()))?,
+                                );
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    let Some(char) = encoding
+                        .filter(|code| *code >= 0)
+                        .and_then(|code| char::from_u32(code as u32))
+                    else {
+                        continue;
+                    };
+
+                    let (box_width, box_height, box_x, box_y) = glyph_box;
+                    let width = advance.unwrap_or(box_width).max(box_x + box_width).max(0) as usize;
+                    let height = font_height.max(0) as usize;
+                    let mut pixels = vec![false; width * height];
+                    let row_bits = ((box_width + 7) / 8 * 8) as u32;
+
+                    for (row_index, row) in rows.iter().enumerate() {
+                        let y = ascent - (box_y + box_height) + row_index as i32;
+                        for column in 0..box_width {
+                            let x = box_x + column;
+                            let bit = row >> (row_bits - 1 - column as u32) & 1;
+                            if bit == 1
+                                && (0..width as i32).contains(&x)
+                                && (0..height as i32).contains(&y)
+                            {
+                                pixels[y as usize * width + x as usize] = true;
+                            }
+                        }
+                    }
+                    glyphs.insert(
+                        char,
+                        Glyph {
+                            width,
+                            height,
+                            pixels,
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        let (_, font_height, _, _) =
+            bounding_box.ok_or_else(|| malformed("нет FONTBOUNDINGBOX"))?;
+        Ok(Self {
+            glyphs,
+            height: font_height.max(0) as usize,
+            letter_spacing: 0,
+        })
+    }
+
+    pub fn parse_psf(bytes: &[u8]) -> Result<Self, FontError> {
+        let malformed = |reason: &str| FontError::Malformed(format!("PSF: {}", reason));
+        let read_u32 = |offset: usize| -> Result<u32, FontError> {
+            bytes
+                .get(offset..offset + 4)
+                .map(|slice| u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+                .ok_or_else(|| malformed("неполный заголовок"))
+        };
+
+        let (glyphs_offset, glyphs_count, glyph_size, width, height, table_entries) = if bytes
+            .starts_with(&PSF2_MAGIC)
+        {
+            let header_size = read_u32(8)? as usize;
+            let flags = read_u32(12)?;
+            let count = read_u32(16)? as usize;
+            let size = read_u32(20)? as usize;
+            let height = read_u32(24)? as usize;
+            let width = read_u32(28)? as usize;
+
+            let table_start = header_size + count * size;
+            let table = if flags & PSF2_HAS_UNICODE_TABLE != 0 {
+                let table = bytes
+                    .get(table_start..)
+                    .ok_or_else(|| malformed("нет таблицы Unicode"))?;
+                Some(Self::psf2_table(table, count))
+            } else {
+                None
+            };
+            (header_size, count, size, width, height, table)
+        } else if bytes.starts_with(&PSF1_MAGIC) {
+            let mode = *bytes
+                .get(2)
+                .ok_or_else(|| malformed("неполный заголовок"))?;
+            let height = *bytes
+                .get(3)
+                .ok_or_else(|| malformed("неполный заголовок"))? as usize;
+            let count = if mode & PSF1_MODE_512 != 0 { 512 } else { 256 };
+
+            let table_start = 4 + count * height;
+            let table = if mode & PSF1_MODE_HAS_TAB != 0 {
+                let table = bytes
+                    .get(table_start..)
+                    .ok_or_else(|| malformed("нет таблицы Unicode"))?;
+                Some(Self::psf1_table(table, count))
+            } else {
+                None
+            };
+            (4, count, height, 8, height, table)
+        } else {
+            return Err(FontError::UnknownFormat);
+        };
+
+        let row_size = width.div_ceil(8);
+        if row_size * height > glyph_size {
+            return Err(malformed("размер символа меньше его битовой карты"));
+        }
+
+        let mut glyphs = HashMap::new();
+        for index in 0..glyphs_count {
+            let start = glyphs_offset + index * glyph_size;
+            let data = bytes
+                .get(start..start + glyph_size)
+                .ok_or_else(|| malformed("файл обрывается посреди символа"))?;
+
+            let mut pixels = Vec::with_capacity(width * height);
+            for row in 0..height {
+                for column in 0..width {
+                    let byte = data[row * row_size + column / 8];
+                    pixels.push(byte >> (7 - column % 8) & 1 == 1);
+                }
+            }
+            let glyph = Glyph {
+                width,
+                height,
+                pixels,
+            };
+
+            let chars = match &table_entries {
+                Some(table) => table.get(index).cloned().unwrap_or_default(),
+                None => char::from_u32(index as u32).into_iter().collect(),
+            };
+            for char in chars {
+                glyphs.insert(char, glyph.clone());
+            }
+        }
+
+        Ok(Self {
+            glyphs,
+            height,
+            letter_spacing: 0,
+        })
+    }
+
+    /// Single code points of every glyph; multi code point sequences are skipped.
+    fn psf1_table(table: &[u8], count: usize) -> Vec<Vec<char>> {
+        let mut entries = vec![Vec::new(); count];
+        let mut index = 0;
+        let mut in_sequence = false;
+        for pair in table.chunks_exact(2) {
+            if index >= count {
+                break;
+            }
+            match u16::from_le_bytes([pair[0], pair[1]]) {
+                PSF1_SEPARATOR => {
+                    index += 1;
+                    in_sequence = false;
+                }
+                PSF1_START_SEQ => in_sequence = true,
+                code if !in_sequence => {
+                    if let Some(char) = char::from_u32(code as u32) {
+                        entries[index].push(char);
+                    }
+                }
+                _ => {}
+            }
         }
+        entries
+    }
+
+    /// Single code points of every glyph; multi code point sequences are skipped.
+    fn psf2_table(table: &[u8], count: usize) -> Vec<Vec<char>> {
+        let mut entries = vec![Vec::new(); count];
+        for (index, entry) in table
+            .split(|byte| *byte == PSF2_SEPARATOR)
+            .take(count)
+            .enumerate()
+        {
+            let singles = entry
+                .split(|byte| *byte == PSF2_START_SEQ)
+                .next()
+                .unwrap_or(&[]);
+            entries[index].extend(String::from_utf8_lossy(singles).chars());
+        }
+        entries
     }
 }
 
@@ -303,90 +936,819 @@ pub const Z: &str = "
  ■    
 ■■■■■ ";
 
-// Digits
+// Lowercase Letters
 
-pub const ZERO: &str = "
+pub const SMALL_A: &str = "
+      
  ■■■  
 ■   ■ 
+■  ■■ 
+ ■■ ■ ";
+
+pub const SMALL_B: &str = "
+■     
+■     
+■■■■  
 ■   ■ 
-■   ■ 
- ■■■  ";
+■■■■  ";
 
-pub const ONE: &str = "
-  ■   
- ■■   
-  ■   
-  ■   
+pub const SMALL_C: &str = "
+      
+ ■■■  
+■     
+■     
  ■■■  ";
 
-pub const TWO: &str = "
- ■■■  
+pub const SMALL_D: &str = "
+    ■ 
+    ■ 
+ ■■■■ 
 ■   ■ 
-  ■■  
- ■    
-■■■■■ ";
+ ■■■■ ";
 
-pub const THREE: &str = "
+pub const SMALL_E: &str = "
+      
  ■■■  
-■   ■ 
-  ■■  
-■   ■ 
+■■■■■ 
+■     
  ■■■  ";
 
-pub const FOUR: &str = "
+pub const SMALL_F: &str = "
   ■■  
- ■ ■  
-■  ■  
-■■■■■ 
-   ■  ";
-
-pub const FIVE: &str = "
-■■■■■ 
-■     
+ ■    
 ■■■■  
+ ■    
+ ■    ";
+
+pub const SMALL_G: &str = "
+ ■■■■ 
+■   ■ 
+ ■■■■ 
     ■ 
-■■■■  ";
+ ■■■  ";
 
-pub const SIX: &str = "
- ■■■  
+pub const SMALL_H: &str = "
+■     
 ■     
 ■■■■  
 ■   ■ 
+■   ■ ";
+
+pub const SMALL_I: &str = "
+  ■   
+      
+ ■■   
+  ■   
  ■■■  ";
 
-pub const SEVEN: &str = "
-■■■■■ 
+pub const SMALL_J: &str = "
+   ■  
+      
+  ■■  
    ■  
+ ■■   ";
+
+pub const SMALL_K: &str = "
+■     
+■  ■  
+■■■   
+■  ■  
+■   ■ ";
+
+pub const SMALL_L: &str = "
+ ■■   
   ■   
- ■    
-■     ";
+  ■   
+  ■   
+ ■■■  ";
 
-pub const EIGHT: &str = "
- ■■■  
+pub const SMALL_M: &str = "
+      
+■■ ■  
+■ ■ ■ 
+■ ■ ■ 
+■   ■ ";
+
+pub const SMALL_N: &str = "
+      
+■■■■  
 ■   ■ 
- ■■■  
 ■   ■ 
- ■■■  ";
+■   ■ ";
 
-pub const NINE: &str = "
+pub const SMALL_O: &str = "
+      
  ■■■  
 ■   ■ 
- ■■■■ 
-    ■ 
+■   ■ 
  ■■■  ";
 
-// Punctuation
-
-const BACKSPACE: &str = "
-      
-      
+pub const SMALL_P: &str = "
       
-      
-      ";
+■■■■  
+■   ■ 
+■■■■  
+■     ";
 
-pub const POINT: &str = "
-      
+pub const SMALL_Q: &str = "
       
+ ■■■■ 
+■   ■ 
+ ■■■■ 
+    ■ ";
+
+pub const SMALL_R: &str = "
       
+■ ■■  
+■■    
+■     
+■     ";
+
+pub const SMALL_S: &str = "
       
+ ■■■■ 
+■■■   
+   ■■ 
+■■■■  ";
+
+pub const SMALL_T: &str = "
+ ■    
+■■■■  
+ ■    
+ ■  ■ 
   ■■  ";
+
+pub const SMALL_U: &str = "
+      
+■   ■ 
+■   ■ 
+■   ■ 
+ ■■■■ ";
+
+pub const SMALL_V: &str = "
+      
+■   ■ 
+■   ■ 
+ ■ ■  
+  ■   ";
+
+pub const SMALL_W: &str = "
+      
+■   ■ 
+■ ■ ■ 
+■ ■ ■ 
+ ■ ■  ";
+
+pub const SMALL_X: &str = "
+      
+■  ■  
+ ■■   
+ ■■   
+■  ■  ";
+
+pub const SMALL_Y: &str = "
+      
+■   ■ 
+ ■ ■  
+  ■   
+ ■    ";
+
+pub const SMALL_Z: &str = "
+      
+■■■■■ 
+   ■  
+ ■    
+■■■■■ ";
+
+// Cyrillic Uppercase Letters
+
+pub const CYR_BE: &str = "
+■■■■■ 
+■     
+■■■■  
+■   ■ 
+■■■■  ";
+
+pub const CYR_GE: &str = "
+■■■■■ 
+■     
+■     
+■     
+■     ";
+
+pub const CYR_DE: &str = "
+  ■■■ 
+ ■  ■ 
+ ■  ■ 
+■■■■■■
+■    ■";
+
+pub const CYR_YO: &str = "
+ ■ ■  
+■■■■■ 
+■■■■  
+■     
+■■■■■ ";
+
+pub const CYR_ZHE: &str = "
+■ ■ ■ 
+■ ■ ■ 
+ ■■■  
+■ ■ ■ 
+■ ■ ■ ";
+
+pub const CYR_ZE: &str = "
+■■■■  
+    ■ 
+ ■■■  
+    ■ 
+■■■■  ";
+
+pub const CYR_I: &str = "
+■    ■
+■   ■■
+■  ■ ■
+■ ■  ■
+■■   ■";
+
+pub const CYR_SHORT_I: &str = "
+■ ■■ ■
+■   ■■
+■  ■ ■
+■ ■  ■
+■■   ■";
+
+pub const CYR_EL: &str = "
+  ■■■ 
+ ■  ■ 
+ ■  ■ 
+ ■  ■ 
+■   ■ ";
+
+pub const CYR_PE: &str = "
+■■■■■ 
+■   ■ 
+■   ■ 
+■   ■ 
+■   ■ ";
+
+pub const CYR_U: &str = "
+■   ■ 
+■   ■ 
+ ■■■■ 
+    ■ 
+ ■■■  ";
+
+pub const CYR_EF: &str = "
+  ■   
+ ■■■  
+■ ■ ■ 
+ ■■■  
+  ■   ";
+
+pub const CYR_TSE: &str = "
+■   ■ 
+■   ■ 
+■   ■ 
+■■■■■ 
+     ■";
+
+pub const CYR_CHE: &str = "
+■   ■ 
+■   ■ 
+ ■■■■ 
+    ■ 
+    ■ ";
+
+pub const CYR_SHA: &str = "
+■ ■ ■ 
+■ ■ ■ 
+■ ■ ■ 
+■ ■ ■ 
+■■■■■ ";
+
+pub const CYR_SHCHA: &str = "
+■ ■ ■ 
+■ ■ ■ 
+■ ■ ■ 
+■■■■■ 
+     ■";
+
+pub const CYR_HARD_SIGN: &str = "
+■■    
+ ■    
+ ■■■  
+ ■  ■ 
+ ■■■  ";
+
+pub const CYR_YERU: &str = "
+■    ■
+■    ■
+■■■  ■
+■  ■ ■
+■■■  ■";
+
+pub const CYR_SOFT_SIGN: &str = "
+■     
+■     
+■■■■  
+■   ■ 
+■■■■  ";
+
+pub const CYR_E: &str = "
+ ■■■  
+■   ■ 
+  ■■■ 
+■   ■ 
+ ■■■  ";
+
+pub const CYR_YU: &str = "
+■  ■■ 
+■ ■  ■
+■■■  ■
+■ ■  ■
+■  ■■ ";
+
+pub const CYR_YA: &str = "
+ ■■■■ 
+■   ■ 
+ ■■■■ 
+  ■ ■ 
+■   ■ ";
+
+// Cyrillic Lowercase Letters
+
+pub const CYR_SMALL_BE: &str = "
+ ■■■  
+■     
+■■■■  
+■   ■ 
+ ■■■  ";
+
+pub const CYR_SMALL_VE: &str = "
+      
+■■■   
+■■■■  
+■   ■ 
+■■■■  ";
+
+pub const CYR_SMALL_GE: &str = "
+      
+■■■■  
+■     
+■     
+■     ";
+
+pub const CYR_SMALL_DE: &str = "
+      
+ ■■■  
+ ■ ■  
+■■■■■ 
+■   ■ ";
+
+pub const CYR_SMALL_YO: &str = "
+ ■ ■  
+ ■■■  
+■■■■■ 
+■     
+ ■■■  ";
+
+pub const CYR_SMALL_ZHE: &str = "
+      
+■ ■ ■ 
+ ■■■  
+ ■■■  
+■ ■ ■ ";
+
+pub const CYR_SMALL_ZE: &str = "
+      
+■■■■  
+  ■■  
+    ■ 
+■■■■  ";
+
+pub const CYR_SMALL_I: &str = "
+      
+■   ■ 
+■  ■■ 
+■ ■ ■ 
+■■  ■ ";
+
+pub const CYR_SMALL_SHORT_I: &str = "
+ ■■■  
+■   ■ 
+■  ■■ 
+■ ■ ■ 
+■■  ■ ";
+
+pub const CYR_SMALL_KA: &str = "
+      
+■  ■  
+■■■   
+■  ■  
+■   ■ ";
+
+pub const CYR_SMALL_EL: &str = "
+      
+  ■■■ 
+ ■  ■ 
+ ■  ■ 
+■   ■ ";
+
+pub const CYR_SMALL_EM: &str = "
+      
+■   ■ 
+■■ ■■ 
+■ ■ ■ 
+■   ■ ";
+
+pub const CYR_SMALL_EN: &str = "
+      
+■   ■ 
+■■■■■ 
+■   ■ 
+■   ■ ";
+
+pub const CYR_SMALL_PE: &str = "
+      
+■■■■■ 
+■   ■ 
+■   ■ 
+■   ■ ";
+
+pub const CYR_SMALL_TE: &str = "
+      
+■■■■■ 
+  ■   
+  ■   
+  ■   ";
+
+pub const CYR_SMALL_TSE: &str = "
+      
+■   ■ 
+■   ■ 
+■■■■■ 
+     ■";
+
+pub const CYR_SMALL_CHE: &str = "
+      
+■   ■ 
+■   ■ 
+ ■■■■ 
+    ■ ";
+
+pub const CYR_SMALL_SHA: &str = "
+      
+■ ■ ■ 
+■ ■ ■ 
+■ ■ ■ 
+■■■■■ ";
+
+pub const CYR_SMALL_SHCHA: &str = "
+      
+■ ■ ■ 
+■ ■ ■ 
+■■■■■ 
+     ■";
+
+pub const CYR_SMALL_HARD_SIGN: &str = "
+      
+■■    
+ ■■■  
+ ■  ■ 
+ ■■■  ";
+
+pub const CYR_SMALL_YERU: &str = "
+      
+■    ■
+■■■  ■
+■  ■ ■
+■■■  ■";
+
+pub const CYR_SMALL_SOFT_SIGN: &str = "
+      
+■     
+■■■■  
+■   ■ 
+■■■■  ";
+
+pub const CYR_SMALL_E: &str = "
+      
+■■■■  
+  ■■■ 
+    ■ 
+■■■■  ";
+
+pub const CYR_SMALL_YU: &str = "
+      
+■  ■■ 
+■ ■  ■
+■■■  ■
+■  ■■ ";
+
+pub const CYR_SMALL_YA: &str = "
+      
+ ■■■■ 
+■   ■ 
+ ■■■■ 
+■   ■ ";
+
+// Digits
+
+pub const ZERO: &str = "
+ ■■■  
+■   ■ 
+■   ■ 
+■   ■ 
+ ■■■  ";
+
+pub const ONE: &str = "
+  ■   
+ ■■   
+  ■   
+  ■   
+ ■■■  ";
+
+pub const TWO: &str = "
+ ■■■  
+■   ■ 
+  ■■  
+ ■    
+■■■■■ ";
+
+pub const THREE: &str = "
+ ■■■  
+■   ■ 
+  ■■  
+■   ■ 
+ ■■■  ";
+
+pub const FOUR: &str = "
+  ■■  
+ ■ ■  
+■  ■  
+■■■■■ 
+   ■  ";
+
+pub const FIVE: &str = "
+■■■■■ 
+■     
+■■■■  
+    ■ 
+■■■■  ";
+
+pub const SIX: &str = "
+ ■■■  
+■     
+■■■■  
+■   ■ 
+ ■■■  ";
+
+pub const SEVEN: &str = "
+■■■■■ 
+   ■  
+  ■   
+ ■    
+■     ";
+
+pub const EIGHT: &str = "
+ ■■■  
+■   ■ 
+ ■■■  
+■   ■ 
+ ■■■  ";
+
+pub const NINE: &str = "
+ ■■■  
+■   ■ 
+ ■■■■ 
+    ■ 
+ ■■■  ";
+
+// Punctuation
+
+const BACKSPACE: &str = "
+      
+      
+      
+      
+      ";
+
+pub const POINT: &str = "
+      
+      
+      
+      
+  ■■  ";
+
+pub const MINUS: &str = "
+      
+      
+ ■■■  
+      
+      ";
+
+pub const PLUS: &str = "
+      
+  ■   
+ ■■■  
+  ■   
+      ";
+
+pub const EQUALS: &str = "
+      
+■■■■■ 
+      
+■■■■■ 
+      ";
+
+pub const COLON: &str = "
+      
+  ■   
+      
+  ■   
+      ";
+
+pub const SEMICOLON: &str = "
+      
+  ■   
+      
+  ■   
+ ■    ";
+
+pub const COMMA: &str = "
+      
+      
+      
+  ■   
+ ■    ";
+
+pub const LEFT_PARENTHESIS: &str = "
+   ■  
+  ■   
+  ■   
+  ■   
+   ■  ";
+
+pub const RIGHT_PARENTHESIS: &str = "
+ ■    
+  ■   
+  ■   
+  ■   
+ ■    ";
+
+pub const LEFT_BRACKET: &str = "
+  ■■  
+  ■   
+  ■   
+  ■   
+  ■■  ";
+
+pub const RIGHT_BRACKET: &str = "
+ ■■   
+  ■   
+  ■   
+  ■   
+ ■■   ";
+
+pub const PERCENT: &str = "
+■■  ■ 
+■■ ■  
+  ■   
+ ■ ■■ 
+■  ■■ ";
+
+pub const SLASH: &str = "
+    ■ 
+   ■  
+  ■   
+ ■    
+■     ";
+
+pub const LESS_THAN: &str = "
+   ■  
+  ■   
+ ■    
+  ■   
+   ■  ";
+
+pub const GREATER_THAN: &str = "
+ ■    
+  ■   
+   ■  
+  ■   
+ ■    ";
+
+pub const EXCLAMATION: &str = "
+  ■   
+  ■   
+  ■   
+      
+  ■   ";
+
+pub const QUESTION: &str = "
+ ■■■  
+■   ■ 
+  ■■  
+      
+  ■   ";
+
+pub const UNDERSCORE: &str = "
+      
+      
+      
+      
+■■■■■ ";
+
+pub const NUMERO: &str = "
+■  ■  
+■■ ■  
+■ ■■  
+■  ■  
+■  ■■ ";
+
+#[cfg(test)]
+mod tests {
+    use crate::font::{
+        AVAILABLE_CHARS, BitmapFont, CharSymbol, Glyph, SYMBOL_HEIGHT, SYMBOL_WIDTH, UNKNOWN,
+    };
+
+    const TEST_BDF: &str = "STARTFONT 2.1
+FONT -test-fixed-medium-r-normal--4-40-75-75-c-40-iso10646-1
+SIZE 4 75 75
+FONTBOUNDINGBOX 4 4 0 -1
+CHARS 1
+STARTCHAR T
+ENCODING 84
+SWIDTH 1000 0
+DWIDTH 4 0
+BBX 3 3 0 0
+BITMAP
+E0
+40
+40
+ENDCHAR
+ENDFONT
+";
+
+    #[test]
+    fn chars_consistency() {
+        for char in AVAILABLE_CHARS.chars() {
+            let symbol = CharSymbol::get(char);
+            assert_ne!(symbol.0, UNKNOWN);
+            assert_eq!(
+                symbol.height(),
+                SYMBOL_HEIGHT,
+                "height mismatch (got {} instead of {}) {}",
+                symbol.height(),
+                SYMBOL_HEIGHT,
+                symbol.0
+            );
+            assert_eq!(
+                symbol.width(),
+                SYMBOL_WIDTH,
+                "width mismatch (got {} instead of {}) {}",
+                symbol.width(),
+                SYMBOL_WIDTH,
+                symbol.0
+            );
+        }
+    }
+
+    #[test]
+    fn bdf_loading() {
+        let font = BitmapFont::parse_bdf(TEST_BDF).unwrap();
+        assert_eq!(font.height(), 4);
+
+        let glyph = font.glyph('T');
+        assert_eq!((glyph.width, glyph.height), (4, 4));
+        let rows: Vec<String> = (0..glyph.height)
+            .map(|row| {
+                (0..glyph.width)
+                    .map(|column| if glyph.is_set(column, row) { '#' } else { ' ' })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(rows, ["### ", " #  ", " #  ", "    "]);
+
+        // Missing characters fall back to the built-in glyphs
+        assert_eq!(font.glyph('A'), Glyph::from(CharSymbol::get('A')));
+    }
+
+    #[test]
+    fn psf2_loading() {
+        let mut bytes = vec![0x72, 0xb5, 0x4a, 0x86];
+        for value in [0_u32, 32, 1, 1, 2, 2, 8] {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.extend([0b1000_0000, 0b0100_0000]);
+        bytes.extend("ж".as_bytes());
+        bytes.push(0xFF);
+
+        let font = BitmapFont::parse_psf(&bytes).unwrap();
+        let glyph = font.glyph('ж');
+        assert_eq!((glyph.width, glyph.height), (8, 2));
+        assert!(glyph.is_set(0, 0) && glyph.is_set(1, 1));
+        assert!(!glyph.is_set(1, 0) && !glyph.is_set(0, 1));
+    }
+}
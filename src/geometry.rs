@@ -1,13 +1,27 @@
-use std::process::exit;
+use rand::seq::SliceRandom;
 
-use crate::utils::rand_f32_in_range;
+use crate::{
+    error::{Error, Result},
+    messages::Message,
+    utils::{rand_f32_in_range, rand_normal},
+};
 
 const DEFAULT_BOTTOM_LEFT_X: f32 = -100.0;
 const DEFAULT_BOTTOM_LEFT_Y: f32 = -100.0;
 const DEFAULT_TOP_RIGHT_X: f32 = 100.0;
 const DEFAULT_TOP_RIGHT_Y: f32 = 100.0;
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+/// Default padding of [`Rectangle::bounding`] as a share of the larger side
+const DEFAULT_BOUNDS_PADDING: f32 = 0.05;
+/// Padding used when all of the points coincide
+const MIN_BOUNDS_PADDING: f32 = 1.0;
+
+/// Default standard deviation of [`Rectangle::create_rand_clusters`] as a share of the smaller side
+const DEFAULT_CLUSTER_SPREAD: f32 = 0.08;
+/// Draws of a clustered point before it is pulled into the rectangle
+const MAX_CLUSTER_SAMPLE_ATTEMPTS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Point {
     pub x: f32,
     pub y: f32,
@@ -30,9 +44,18 @@ impl std::fmt::Display for Point {
 
 impl Eq for Point {}
 
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for Point {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+        self.x
+            .partial_cmp(&other.x)
+            .unwrap()
+            .then(self.y.partial_cmp(&other.y).unwrap())
     }
 }
 
@@ -55,36 +78,72 @@ pub enum FixedPosition {
     Center(f32, f32),
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Rectangle {
     pub bottom_left: Point,
     pub top_right: Point,
 }
 
 impl Rectangle {
-    pub fn new(bottom_left: Point, top_right: Point) -> Self {
-        if top_right.x < bottom_left.x {
-            eprintln!(
-                "ОШИБКА: Попытка создать прямоугольник с отрциательной шириной ({}): Нижний левый угол - {}; Правый верхний угол - {}",
-                top_right.x - bottom_left.x,
+    pub fn new(bottom_left: Point, top_right: Point) -> Result<Self> {
+        if top_right.x < bottom_left.x || top_right.y < bottom_left.y {
+            return Err(Error::InvalidRectangle {
+                bottom_left,
                 top_right,
-                bottom_left
-            );
-            exit(1);
-        }
-        if top_right.y < bottom_left.y {
-            eprintln!(
-                "ОШИБКА: Попытка создать прямоугольник с отрциательной высотой ({}): Нижний левый угол - {}; Правый верхний угол - {}",
-                top_right.y - bottom_left.y,
-                top_right,
-                bottom_left
-            );
-            exit(1);
+            });
         }
-        Self {
+        Ok(Self {
             bottom_left,
             top_right,
+        })
+    }
+
+    /// Bounding box of the points grown by `padding` field units on every side
+    /// (5% of the larger side by default). With `aspect_ratio` (width / height) the
+    /// shorter side is grown around the center until the box has that ratio.
+    /// Points with infinite or NaN coordinates are ignored.
+    pub fn bounding(
+        points: &[Point],
+        padding: Option<f32>,
+        aspect_ratio: Option<f32>,
+    ) -> Result<Self> {
+        let mut finite = points
+            .iter()
+            .filter(|point| point.x.is_finite() && point.y.is_finite());
+        let first = finite.next().ok_or(Error::EmptyPointSet)?;
+        let (mut min, mut max) = (*first, *first);
+        for point in finite {
+            min = Point::new(min.x.min(point.x), min.y.min(point.y));
+            max = Point::new(max.x.max(point.x), max.y.max(point.y));
+        }
+
+        let larger_side = (max.x - min.x).max(max.y - min.y);
+        let padding = padding
+            .unwrap_or(larger_side * DEFAULT_BOUNDS_PADDING)
+            .max(0.0);
+        let padding = if larger_side == 0.0 && padding == 0.0 {
+            MIN_BOUNDS_PADDING
+        } else {
+            padding
+        };
+        let mut rect = Self {
+            bottom_left: Point::new(min.x - padding, min.y - padding),
+            top_right: Point::new(max.x + padding, max.y + padding),
+        };
+
+        if let Some(aspect_ratio) = aspect_ratio {
+            if !(aspect_ratio.is_finite() && aspect_ratio > 0.0) {
+                return Err(Error::InvalidAspectRatio(aspect_ratio));
+            }
+            let center = rect.center();
+            let width = rect.width().max(rect.height() * aspect_ratio);
+            let height = rect.height().max(rect.width() / aspect_ratio);
+            rect = Self {
+                bottom_left: Point::new(center.x - width / 2.0, center.y - height / 2.0),
+                top_right: Point::new(center.x + width / 2.0, center.y + height / 2.0),
+            };
         }
+        Ok(rect)
     }
     pub fn get_position(&self, position: FixedPosition) -> Point {
         let x: f32;
@@ -119,8 +178,8 @@ impl Rectangle {
                     .clamp(self.bottom_left.y, self.top_right.y);
             }
             FixedPosition::BottomRight(offset_x, offset_y) => {
-                x = (self.bottom_left.x + offset_x).clamp(self.bottom_left.x, self.top_right.x);
-                y = (self.top_right.y + offset_y).clamp(self.bottom_left.y, self.top_right.y);
+                x = (self.top_right.x + offset_x).clamp(self.bottom_left.x, self.top_right.x);
+                y = (self.bottom_left.y + offset_y).clamp(self.bottom_left.y, self.top_right.y);
             }
             FixedPosition::BottomMiddle(offset_x, offset_y) => {
                 x = (self.bottom_left.x + self.width() / 2.0 + offset_x)
@@ -137,6 +196,35 @@ impl Rectangle {
         Point::new(x, y)
     }
 
+    /// Places a box of the given size inside the rectangle so that the box's own
+    /// corner (or side middle) named by `position` lies on that position.
+    pub fn place(&self, position: FixedPosition, width: f32, height: f32) -> Rectangle {
+        let anchor = self.get_position(position.clone());
+        let (x, y) = match position {
+            FixedPosition::BottomLeft(..) => (anchor.x, anchor.y),
+            FixedPosition::MiddleLeft(..) => (anchor.x, anchor.y - height / 2.0),
+            FixedPosition::TopLeft(..) => (anchor.x, anchor.y - height),
+            FixedPosition::TopMiddle(..) => (anchor.x - width / 2.0, anchor.y - height),
+            FixedPosition::TopRight(..) => (anchor.x - width, anchor.y - height),
+            FixedPosition::MiddleRight(..) => (anchor.x - width, anchor.y - height / 2.0),
+            FixedPosition::BottomRight(..) => (anchor.x - width, anchor.y),
+            FixedPosition::BottomMiddle(..) => (anchor.x - width / 2.0, anchor.y),
+            FixedPosition::Center(..) => (anchor.x - width / 2.0, anchor.y - height / 2.0),
+        };
+        let x = x.clamp(
+            self.bottom_left.x,
+            (self.top_right.x - width).max(self.bottom_left.x),
+        );
+        let y = y.clamp(
+            self.bottom_left.y,
+            (self.top_right.y - height).max(self.bottom_left.y),
+        );
+        Rectangle {
+            bottom_left: Point::new(x, y),
+            top_right: Point::new(x + width, y + height),
+        }
+    }
+
     pub fn center(&self) -> Point {
         self.get_position(FixedPosition::Center(0.0, 0.0))
     }
@@ -152,19 +240,95 @@ impl Rectangle {
             rand_f32_in_range(self.bottom_left.y, self.top_right.y, 0),
         )
     }
+
+    /// `count` points split evenly between `clusters` normally distributed groups
+    /// with random centers inside the rectangle. Every point comes with its true
+    /// class (from 1), the points are shuffled and never leave the rectangle.
+    /// `spread` is the standard deviation, 8% of the smaller side by default.
+    pub fn create_rand_clusters(
+        &self,
+        count: usize,
+        clusters: usize,
+        spread: Option<f32>,
+    ) -> Vec<(Point, usize)> {
+        let mut points: Vec<(Point, usize)> = self
+            .rand_clusters_stream(clusters, spread)
+            .take(count)
+            .collect();
+        points.shuffle(&mut rand::rng());
+        points
+    }
+
+    /// Endless points of the groups of [`create_rand_clusters`](Self::create_rand_clusters),
+    /// the groups take turns. For sets too large to be kept in memory.
+    pub fn rand_clusters_stream(
+        &self,
+        clusters: usize,
+        spread: Option<f32>,
+    ) -> impl Iterator<Item = (Point, usize)> {
+        let clusters = clusters.max(1);
+        let spread = spread.unwrap_or(DEFAULT_CLUSTER_SPREAD * self.width().min(self.height()));
+        let centers: Vec<Point> = (0..clusters).map(|_| self.create_rand_point()).collect();
+
+        // Coordinates are whole numbers like the ones of `create_rand_point`,
+        // unless the rectangle has no point with whole coordinates at all
+        let whole = self.bottom_left.x.ceil() <= self.top_right.x
+            && self.bottom_left.y.ceil() <= self.top_right.y;
+        let snap = move |value: f32, min: f32, max: f32| {
+            if whole {
+                value.round().clamp(min.ceil(), max.floor())
+            } else {
+                value.clamp(min, max)
+            }
+        };
+
+        (0..).map(move |i| {
+            let center = centers[i % clusters];
+            let sample =
+                || Point::new(rand_normal(center.x, spread), rand_normal(center.y, spread));
+            // Samples outside are redrawn a few times, then pulled to the border
+            let point = (0..MAX_CLUSTER_SAMPLE_ATTEMPTS)
+                .map(|_| sample())
+                .find(|point| self.contains(*point))
+                .unwrap_or_else(sample);
+            let point = Point::new(
+                snap(point.x, self.bottom_left.x, self.top_right.x),
+                snap(point.y, self.bottom_left.y, self.top_right.y),
+            );
+            (point, i % clusters + 1)
+        })
+    }
+
+    /// Borders belong to the rectangle
+    pub fn contains(&self, point: Point) -> bool {
+        (self.bottom_left.x..=self.top_right.x).contains(&point.x)
+            && (self.bottom_left.y..=self.top_right.y).contains(&point.y)
+    }
 }
 
 impl std::fmt::Display for Rectangle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(format!("От {} до {}", self.bottom_left, self.top_right).as_str())
+        Message::RectangleBounds {
+            bottom_left: &self.bottom_left,
+            top_right: &self.top_right,
+        }
+        .fmt(f)
     }
 }
 
 impl Eq for Rectangle {}
 
+impl PartialOrd for Rectangle {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for Rectangle {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+        self.bottom_left
+            .cmp(&other.bottom_left)
+            .then(self.top_right.cmp(&other.top_right))
     }
 }
 
@@ -176,3 +340,207 @@ impl Default for Rectangle {
         }
     }
 }
+
+/// Affine map `(x; y) -> (a * x + b * y + c; d * x + e * y + f)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self::scale(1.0, 1.0)
+    }
+
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self {
+            a: x,
+            b: 0.0,
+            c: 0.0,
+            d: 0.0,
+            e: y,
+            f: 0.0,
+        }
+    }
+
+    pub fn translate(x: f32, y: f32) -> Self {
+        Self {
+            c: x,
+            f: y,
+            ..Self::identity()
+        }
+    }
+
+    /// Transform that applies `self` first and `next` after it
+    pub fn then(&self, next: &Transform) -> Self {
+        Self {
+            a: next.a * self.a + next.b * self.d,
+            b: next.a * self.b + next.b * self.e,
+            c: next.a * self.c + next.b * self.f + next.c,
+            d: next.d * self.a + next.e * self.d,
+            e: next.d * self.b + next.e * self.e,
+            f: next.d * self.c + next.e * self.f + next.f,
+        }
+    }
+
+    /// Maps the rectangle onto a `width`x`height` picture, the Y axis of the picture points down
+    pub fn to_pixels(rect: &Rectangle, width: u32, height: u32) -> Self {
+        let scale_x = width as f32 / rect.width().max(f32::EPSILON);
+        let scale_y = height as f32 / rect.height().max(f32::EPSILON);
+        Self::translate(-rect.bottom_left.x, -rect.top_right.y)
+            .then(&Self::scale(scale_x, -scale_y))
+    }
+
+    pub fn apply(&self, point: Point) -> Point {
+        Point::new(
+            self.a * point.x + self.b * point.y + self.c,
+            self.d * point.x + self.e * point.y + self.f,
+        )
+    }
+
+    /// `None` if the transform collapses the plane
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.a * self.e - self.b * self.d;
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        Some(Self {
+            a: self.e / determinant,
+            b: -self.b / determinant,
+            c: (self.b * self.f - self.e * self.c) / determinant,
+            d: -self.d / determinant,
+            e: self.a / determinant,
+            f: (self.d * self.c - self.a * self.f) / determinant,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::Error,
+        geometry::{Point, Rectangle, Transform},
+    };
+
+    fn rect(left: f32, bottom: f32, right: f32, top: f32) -> Rectangle {
+        Rectangle::new(Point::new(left, bottom), Point::new(right, top)).unwrap()
+    }
+
+    #[test]
+    fn bounding_is_padded_by_the_larger_side() {
+        let points = [Point::new(0.0, 0.0), Point::new(100.0, 20.0)];
+        assert_eq!(
+            Rectangle::bounding(&points, None, None).unwrap(),
+            rect(-5.0, -5.0, 105.0, 25.0)
+        );
+        assert_eq!(
+            Rectangle::bounding(&points, Some(1.0), None).unwrap(),
+            rect(-1.0, -1.0, 101.0, 21.0)
+        );
+        assert_eq!(
+            Rectangle::bounding(&points, Some(0.0), None).unwrap(),
+            rect(0.0, 0.0, 100.0, 20.0)
+        );
+    }
+
+    #[test]
+    fn bounding_grows_the_shorter_side_to_the_aspect_ratio() {
+        let points = [Point::new(0.0, 0.0), Point::new(100.0, 20.0)];
+        let wide = Rectangle::bounding(&points, Some(0.0), Some(2.0)).unwrap();
+        assert_eq!(wide, rect(0.0, -15.0, 100.0, 35.0));
+
+        let tall = Rectangle::bounding(&points, Some(0.0), Some(0.5)).unwrap();
+        assert_eq!(tall, rect(0.0, -90.0, 100.0, 110.0));
+        assert_eq!(tall.center(), Point::new(50.0, 10.0));
+    }
+
+    #[test]
+    fn single_point_gets_an_area() {
+        let points = [Point::new(3.0, -2.0)];
+        let bounds = Rectangle::bounding(&points, None, None).unwrap();
+        assert_eq!(bounds, rect(2.0, -3.0, 4.0, -1.0));
+
+        let square = Rectangle::bounding(&points, Some(0.0), Some(1.0)).unwrap();
+        assert!(square.width() > 0.0);
+        assert_eq!(square.width(), square.height());
+    }
+
+    #[test]
+    fn non_finite_points_are_ignored() {
+        let points = [
+            Point::new(f32::NAN, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(f32::INFINITY, 1000.0),
+            Point::new(10.0, f32::NEG_INFINITY),
+            Point::new(10.0, 10.0),
+        ];
+        assert_eq!(
+            Rectangle::bounding(&points, Some(0.0), None).unwrap(),
+            rect(0.0, 0.0, 10.0, 10.0)
+        );
+
+        let nothing = [
+            Point::new(f32::NAN, f32::NAN),
+            Point::new(f32::INFINITY, 0.0),
+        ];
+        assert!(matches!(
+            Rectangle::bounding(&nothing, None, None),
+            Err(Error::EmptyPointSet)
+        ));
+        assert!(matches!(
+            Rectangle::bounding(&[], None, None),
+            Err(Error::EmptyPointSet)
+        ));
+    }
+
+    #[test]
+    fn invalid_aspect_ratio_is_an_error() {
+        let points = [Point::new(0.0, 0.0), Point::new(1.0, 1.0)];
+        for aspect_ratio in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert!(matches!(
+                Rectangle::bounding(&points, None, Some(aspect_ratio)),
+                Err(Error::InvalidAspectRatio(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn transforms_apply_in_order() {
+        let point = Point::new(2.0, 3.0);
+        let scaled_then_moved = Transform::scale(2.0, -1.0).then(&Transform::translate(1.0, 1.0));
+        assert_eq!(scaled_then_moved.apply(point), Point::new(5.0, -2.0));
+        let moved_then_scaled = Transform::translate(1.0, 1.0).then(&Transform::scale(2.0, -1.0));
+        assert_eq!(moved_then_scaled.apply(point), Point::new(6.0, -4.0));
+        assert_eq!(Transform::identity().apply(point), point);
+    }
+
+    #[test]
+    fn pixels_map_back_to_the_field() {
+        let field = rect(-10.0, -5.0, 30.0, 15.0);
+        let to_pixels = Transform::to_pixels(&field, 400, 200);
+        assert_eq!(to_pixels.apply(field.bottom_left), Point::new(0.0, 200.0));
+        assert_eq!(to_pixels.apply(field.top_right), Point::new(400.0, 0.0));
+
+        let to_field = to_pixels.inverse().unwrap();
+        for point in [
+            Point::new(-10.0, -5.0),
+            Point::new(12.5, 7.25),
+            Point::new(30.0, 15.0),
+        ] {
+            let back = to_field.apply(to_pixels.apply(point));
+            assert!((back.x - point.x).abs() < 1e-4 && (back.y - point.y).abs() < 1e-4);
+        }
+        assert_eq!(Transform::scale(0.0, 1.0).inverse(), None);
+    }
+}
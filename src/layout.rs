@@ -0,0 +1,262 @@
+use crate::{
+    font::{BitmapFont, HorizontalAlign, Rotation, TextStyle, VerticalAlign},
+    geometry::{FixedPosition, Point, Rectangle},
+    visual::Color,
+};
+
+/// Data area size (in pixels) that corresponds to one text pixel per glyph cell
+const TEXT_SCALE_STEP: u32 = 200;
+
+const MARGIN: u32 = 4;
+
+const LEGEND_PADDING: u32 = 3;
+const LEGEND_ROW_SPACING: u32 = 2;
+
+const TEXT_COLOR: Color = Color::rgba(0, 0, 0, 255);
+const FRAME_COLOR: Color = Color::rgba(0, 0, 0, 255);
+
+/// Decorations drawn around (title, axis titles) and on top of (legend) the data area.
+#[derive(Debug, Clone, Default)]
+pub struct Layout {
+    pub title: Option<String>,
+    pub x_axis_title: Option<String>,
+    pub y_axis_title: Option<String>,
+    /// Anchor of the legend box inside the data area (offsets are in pixels)
+    pub legend: Option<FixedPosition>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LegendEntry {
+    pub color: Color,
+    pub label: String,
+    pub count: usize,
+}
+
+/// Text placed on the final picture, `anchor` is in pixels of the whole picture.
+#[derive(Debug, Clone)]
+pub struct PlacedText {
+    pub anchor: (i32, i32),
+    pub text: String,
+    pub color: Color,
+    pub style: TextStyle,
+}
+
+/// Filled rectangle placed on the final picture, coordinates are in pixels.
+#[derive(Debug, Clone)]
+pub struct PlacedBox {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub fill: Color,
+    pub frame: Option<Color>,
+}
+
+/// Result of the layout: size of the whole picture, position of the data area in it
+/// and the decorations to draw. Boxes are drawn before texts.
+#[derive(Debug, Clone)]
+pub struct Arrangement {
+    pub width: u32,
+    pub height: u32,
+    /// Top left corner of the data area
+    pub data_origin: (u32, u32),
+    pub boxes: Vec<PlacedBox>,
+    pub texts: Vec<PlacedText>,
+}
+
+impl Layout {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.x_axis_title.is_none()
+            && self.y_axis_title.is_none()
+            && self.legend.is_none()
+    }
+
+    /// Places title bars around a `data_width`x`data_height` data area and the legend
+    /// box over it. Drawing is left to the backend.
+    pub fn arrange(
+        &self,
+        data_width: u32,
+        data_height: u32,
+        legend: &[LegendEntry],
+        background: Color,
+        font: &BitmapFont,
+    ) -> Arrangement {
+        let scale = text_scale(data_width, data_height);
+        let title_style = TextStyle {
+            scale,
+            bold: true,
+            horizontal_align: HorizontalAlign::Center,
+            vertical_align: VerticalAlign::Top,
+            ..Default::default()
+        };
+        let x_axis_style = TextStyle {
+            scale,
+            horizontal_align: HorizontalAlign::Center,
+            vertical_align: VerticalAlign::Top,
+            ..Default::default()
+        };
+        let y_axis_style = TextStyle {
+            scale,
+            vertical_align: VerticalAlign::Middle,
+            rotation: Rotation::CounterClockwise,
+            ..Default::default()
+        };
+
+        let title_size = self
+            .title
+            .as_ref()
+            .map(|title| font.measure(title, &title_style));
+        let x_axis_size = self
+            .x_axis_title
+            .as_ref()
+            .map(|title| font.measure(title, &x_axis_style));
+        let y_axis_size = self
+            .y_axis_title
+            .as_ref()
+            .map(|title| font.measure(title, &y_axis_style));
+
+        let top = title_size.map_or(0, |(_, height)| height + 2 * MARGIN);
+        let mut bottom = x_axis_size.map_or(0, |(_, height)| height + 2 * MARGIN);
+        let left = y_axis_size.map_or(0, |(width, _)| width + 2 * MARGIN);
+
+        let mut width = left + data_width;
+        if let Some((title_width, _)) = title_size {
+            width = width.max(title_width + 2 * MARGIN);
+        }
+        if let Some((x_axis_width, _)) = x_axis_size {
+            width = width.max(left + x_axis_width + 2 * MARGIN);
+        }
+        if let Some((_, y_axis_height)) = y_axis_size {
+            bottom += (y_axis_height + 2 * MARGIN).saturating_sub(data_height + bottom);
+        }
+        let height = top + data_height + bottom;
+
+        let mut arrangement = Arrangement {
+            width,
+            height,
+            data_origin: (left, top),
+            boxes: Vec::new(),
+            texts: Vec::new(),
+        };
+
+        if let Some(title) = &self.title {
+            arrangement.texts.push(PlacedText {
+                anchor: ((width / 2) as i32, MARGIN as i32),
+                text: title.clone(),
+                color: TEXT_COLOR,
+                style: title_style,
+            });
+        }
+        if let Some(x_axis_title) = &self.x_axis_title {
+            arrangement.texts.push(PlacedText {
+                anchor: (
+                    (left + data_width / 2) as i32,
+                    (top + data_height + MARGIN) as i32,
+                ),
+                text: x_axis_title.clone(),
+                color: TEXT_COLOR,
+                style: x_axis_style,
+            });
+        }
+        if let Some(y_axis_title) = &self.y_axis_title {
+            arrangement.texts.push(PlacedText {
+                anchor: (MARGIN as i32, (top + data_height / 2) as i32),
+                text: y_axis_title.clone(),
+                color: TEXT_COLOR,
+                style: y_axis_style,
+            });
+        }
+        if let Some(position) = &self.legend
+            && !legend.is_empty()
+        {
+            // Picture pixels with the Y axis pointing up
+            let data_area = Rectangle {
+                bottom_left: Point::new(left as f32, (height - top - data_height) as f32),
+                top_right: Point::new((left + data_width) as f32, (height - top) as f32),
+            };
+            let row_style = TextStyle {
+                scale,
+                vertical_align: VerticalAlign::Top,
+                ..Default::default()
+            };
+            arrange_legend(
+                &mut arrangement,
+                data_area,
+                position,
+                legend,
+                background,
+                font,
+                &row_style,
+            );
+        }
+
+        arrangement
+    }
+}
+
+fn arrange_legend(
+    arrangement: &mut Arrangement,
+    data_area: Rectangle,
+    position: &FixedPosition,
+    entries: &[LegendEntry],
+    background: Color,
+    font: &BitmapFont,
+    row_style: &TextStyle,
+) {
+    let rows: Vec<String> = entries
+        .iter()
+        .map(|entry| format!("{} ({})", entry.label, entry.count))
+        .collect();
+    let row_sizes: Vec<(u32, u32)> = rows
+        .iter()
+        .map(|row| font.measure(row, row_style))
+        .collect();
+    let row_height = row_sizes.iter().map(|size| size.1).max().unwrap_or(0);
+    let text_column = row_sizes.iter().map(|size| size.0).max().unwrap_or(0);
+
+    let box_width = 3 * LEGEND_PADDING + row_height + text_column;
+    let box_height = 2 * LEGEND_PADDING + entries.len() as u32 * (row_height + LEGEND_ROW_SPACING)
+        - LEGEND_ROW_SPACING;
+
+    let placed = data_area.place(position.clone(), box_width as f32, box_height as f32);
+    let x = placed.bottom_left.x.round() as u32;
+    let y = (arrangement.height as f32 - placed.top_right.y).round() as u32;
+
+    arrangement.boxes.push(PlacedBox {
+        x,
+        y,
+        width: box_width,
+        height: box_height,
+        fill: background,
+        frame: Some(FRAME_COLOR),
+    });
+
+    let mut row_y = y + LEGEND_PADDING;
+    for (entry, row) in entries.iter().zip(rows) {
+        let swatch_x = x + LEGEND_PADDING;
+        arrangement.boxes.push(PlacedBox {
+            x: swatch_x,
+            y: row_y,
+            width: row_height,
+            height: row_height,
+            fill: entry.color,
+            frame: None,
+        });
+        arrangement.texts.push(PlacedText {
+            anchor: (
+                (swatch_x + row_height + LEGEND_PADDING) as i32,
+                row_y as i32,
+            ),
+            text: row,
+            color: TEXT_COLOR,
+            style: row_style.clone(),
+        });
+        row_y += row_height + LEGEND_ROW_SPACING;
+    }
+}
+
+/// Glyph pixel size in output pixels that keeps text readable on a picture of that size.
+pub fn text_scale(width: u32, height: u32) -> u32 {
+    (width.min(height) / TEXT_SCALE_STEP).max(1)
+}
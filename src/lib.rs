@@ -1,55 +1,74 @@
-use std::{io::stdin, process::exit};
+use std::io::stdin;
 
-use crate::tasks::{
-    classification, hierarchy_grouping, k_mean, n_classes_functions, two_classes_function,
+use crate::{
+    error::{Error, Result},
+    messages::Message,
+    tasks::{
+        classification, features_clustering, fuzzy_clustering, gaussian_mixture,
+        hierarchy_grouping, k_mean, n_classes_functions, streaming_k_mean, two_classes_function,
+    },
 };
 
+pub mod backend;
+pub mod clustering;
+pub mod distance;
+pub mod error;
 #[allow(non_upper_case_globals)]
 pub mod font;
 pub mod geometry;
+pub mod layout;
+pub mod messages;
+pub mod metrics;
+pub mod palette;
+pub mod pca;
+pub mod perceptron;
+pub mod preprocessing;
+pub mod recorder;
 pub mod tasks;
 pub mod utils;
+pub mod vector;
 pub mod visual;
 
-pub fn interactive() {
+/// Runs tasks chosen by the user until `0` is entered. Errors of a task are
+/// printed and the menu comes back, only failed reading of the input ends it.
+pub fn interactive() -> Result<()> {
     loop {
-        println!("Какое задание выполнить? (1-5, 0 для выхода)");
+        println!("{}", Message::TaskPrompt);
         let mut buf = String::new();
-        stdin()
-            .read_line(&mut buf)
-            .expect("Не удалось прочитать из стандартного ввода.");
+        stdin().read_line(&mut buf).map_err(Error::Input)?;
         let index = buf.trim().parse::<usize>();
-        if let Ok(index) = index {
-            match index {
-                0 => {
-                    println!("Работа программы завершена");
-                    exit(0);
-                }
-                1 => {
-                    k_mean::execute();
-                }
-                2 => {
-                    classification::execute();
-                }
-                3 => {
-                    two_classes_function::execute();
-                }
-                4 => {
-                    n_classes_functions::execute();
-                }
-                5 => {
-                    hierarchy_grouping::execute();
-                }
-                _ => {
-                    eprintln!("Указанного задания не существует.");
-                    eprintln!("---------------------------------");
-                    continue;
-                }
+        let result = match index {
+            Ok(0) => {
+                println!("{}", Message::ProgramFinished);
+                return Ok(());
             }
-        } else {
-            eprintln!("Введено неправильное число.");
-            eprintln!("---------------------------------");
-            continue;
+            Ok(1) => k_mean::execute(),
+            Ok(2) => classification::execute(),
+            Ok(3) => two_classes_function::execute(),
+            Ok(4) => n_classes_functions::execute(),
+            Ok(5) => hierarchy_grouping::execute(),
+            Ok(6) => gaussian_mixture::execute(),
+            Ok(7) => fuzzy_clustering::execute(),
+            Ok(8) => streaming_k_mean::execute(),
+            Ok(9) => features_clustering::execute(),
+            Ok(_) => {
+                eprintln!("{}", Message::UnknownTask);
+                eprintln!("---------------------------------");
+                continue;
+            }
+            Err(_) => {
+                eprintln!("{}", Message::InvalidNumber);
+                eprintln!("---------------------------------");
+                continue;
+            }
+        };
+
+        // A failed task is reported and the next one may be chosen, only the
+        // input itself ends the session
+        match result {
+            Err(err @ Error::Input(_)) => return Err(err),
+            Err(err) => eprintln!("{}", Message::Error { error: &err }),
+            Ok(()) => {}
         }
 
         println!("---------------------------------")
@@ -1,5 +1,39 @@
-use ai_k_mean::interactive;
+use std::{env, process::ExitCode};
 
-pub fn main() {
-    interactive()
+use ai_k_mean::{
+    interactive,
+    messages::{Language, Message, set_language},
+};
+
+pub fn main() -> ExitCode {
+    set_language(select_language());
+
+    match interactive() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", Message::Error { error: &err });
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// `--lang en` / `--lang=en` takes priority over the locale (`LANG`)
+fn select_language() -> Language {
+    let mut args = env::args().skip(1);
+    let mut code = None;
+    while let Some(arg) = args.next() {
+        if arg == "--lang" {
+            code = args.next();
+        } else if let Some(value) = arg.strip_prefix("--lang=") {
+            code = Some(value.to_string());
+        }
+    }
+
+    if let Some(code) = code {
+        match Language::from_code(&code) {
+            Some(language) => return language,
+            None => eprintln!("{}", Message::UnknownLanguage { code: &code }),
+        }
+    }
+    Language::from_env().unwrap_or_default()
 }
@@ -0,0 +1,1576 @@
+use std::{
+    env,
+    fmt::{Display, Formatter, Result},
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+/// Language of the messages shown to the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    Russian,
+    English,
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::Russian as u8);
+
+/// Environment variables checked for the locale, in order of priority
+const LOCALE_VARIABLES: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+impl Language {
+    /// Parses `ru`, `en` or a locale name like `en_US.UTF-8`
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().to_ascii_lowercase();
+        if code.starts_with("ru") {
+            Some(Language::Russian)
+        } else if code.starts_with("en") {
+            Some(Language::English)
+        } else {
+            None
+        }
+    }
+
+    /// Language of the first set locale variable (`LC_ALL`, `LC_MESSAGES`, `LANG`)
+    pub fn from_env() -> Option<Self> {
+        LOCALE_VARIABLES
+            .iter()
+            .filter_map(|variable| env::var(variable).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_code(&value))
+    }
+}
+
+/// Selects the language of all messages printed from now on.
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        value if value == Language::English as u8 => Language::English,
+        _ => Language::Russian,
+    }
+}
+
+/// Every text shown to the user. Displaying a message writes it in the selected
+/// [`Language`]; all translations of a message take the same arguments.
+pub enum Message<'a> {
+    // Menu
+    TaskPrompt,
+    ProgramFinished,
+    UnknownTask,
+    InvalidNumber,
+    Error {
+        error: &'a dyn Display,
+    },
+    Warning {
+        warning: &'a dyn Display,
+    },
+    UnknownLanguage {
+        code: &'a dyn Display,
+    },
+
+    // Errors
+    InvalidRectangle {
+        width: &'a dyn Display,
+        height: &'a dyn Display,
+        bottom_left: &'a dyn Display,
+        top_right: &'a dyn Display,
+    },
+    InvalidHexColor {
+        hex: &'a dyn Display,
+        reason: &'a dyn Display,
+    },
+    HexWithoutHash,
+    AlphaOutOfRange {
+        alpha: &'a dyn Display,
+    },
+    EmptyPointSet,
+    InvalidAspectRatio {
+        ratio: &'a dyn Display,
+    },
+    FontLoadFailed {
+        error: &'a dyn Display,
+    },
+    ImageSaveFailed {
+        path: &'a dyn Display,
+        error: &'a dyn Display,
+    },
+    ViewerFailed {
+        path: &'a dyn Display,
+        command: &'a dyn Display,
+        error: &'a dyn Display,
+    },
+    InputFailed {
+        error: &'a dyn Display,
+    },
+    FontReadFailed {
+        error: &'a dyn Display,
+    },
+    FontUnknownFormat,
+    FontMalformed {
+        reason: &'a dyn Display,
+    },
+
+    // Drawing
+    PointOutOfField {
+        point: &'a dyn Display,
+        rect: &'a dyn Display,
+    },
+    PointOverlap {
+        point: &'a dyn Display,
+    },
+    NoMemberships {
+        point: &'a dyn Display,
+    },
+    ImageSaved {
+        path: &'a dyn Display,
+    },
+    NotRasterImage {
+        path: &'a dyn Display,
+    },
+    NoFrames {
+        path: &'a dyn Display,
+    },
+    AnimationSaved {
+        count: &'a dyn Display,
+        path: &'a dyn Display,
+    },
+    RectangleBounds {
+        bottom_left: &'a dyn Display,
+        top_right: &'a dyn Display,
+    },
+
+    // Dialogues
+    PointsCountPrompt {
+        default: &'a dyn Display,
+    },
+    PointsCountChosen {
+        count: &'a dyn Display,
+    },
+    CoresCountPrompt {
+        default: &'a dyn Display,
+    },
+    CoresCountChosen {
+        count: &'a dyn Display,
+    },
+    ClassesCountPrompt {
+        default: &'a dyn Display,
+    },
+    ClassesCountChosen {
+        count: &'a dyn Display,
+    },
+    YesNoPrompt {
+        question: &'a dyn Display,
+    },
+    ElementsCountPrompt {
+        default: &'a dyn Display,
+    },
+
+    // Tasks
+    Boundaries {
+        rect: &'a dyn Display,
+    },
+    ClassLabel {
+        class: &'a dyn Display,
+    },
+
+    KMeanTitle,
+    KMeanPointsLabel,
+    KMeanHeader {
+        rect: &'a dyn Display,
+        center: &'a dyn Display,
+    },
+    KMeanPoint {
+        index: &'a dyn Display,
+        point: &'a dyn Display,
+        distance: &'a dyn Display,
+    },
+    KMeanResult {
+        center: &'a dyn Display,
+        distance: &'a dyn Display,
+    },
+
+    ClassificationTitle,
+    Cores,
+    ClassificationClass {
+        class: &'a dyn Display,
+        core: &'a dyn Display,
+    },
+    ClassificationPoint {
+        index: &'a dyn Display,
+        point: &'a dyn Display,
+        distance: &'a dyn Display,
+    },
+
+    TwoClassesTitle,
+    FirstClass,
+    SecondClass,
+    DividingFunction {
+        w_0: &'a dyn Display,
+        w_1: &'a dyn Display,
+        w_2: &'a dyn Display,
+    },
+    TrueDividingFunction {
+        w_0: &'a dyn Display,
+        w_1: &'a dyn Display,
+        w_2: &'a dyn Display,
+    },
+    TwoClassesPoint {
+        index: &'a dyn Display,
+        point: &'a dyn Display,
+        value: &'a dyn Display,
+        class: &'a dyn Display,
+    },
+
+    NClassesTitle,
+    DividingFunctions,
+    TrueDividingFunctions,
+    NClassesClass {
+        class: &'a dyn Display,
+    },
+    NClassesPoints,
+    NClassesPoint {
+        index: &'a dyn Display,
+        point: &'a dyn Display,
+    },
+    Chosen,
+    PerceptronPrompt,
+    PerceptronEpoch {
+        epoch: &'a dyn Display,
+        errors: &'a dyn Display,
+    },
+
+    HierarchyTitle,
+    InitialDistances,
+    ResultingHierarchy,
+    HierarchyNode,
+    HierarchyLeaf,
+    HierarchyElementsAxis,
+    MergeDistanceAxis,
+    HierarchyNodeEntry {
+        id: &'a dyn Display,
+        distance: &'a dyn Display,
+        first_kind: &'a dyn Display,
+        first: &'a dyn Display,
+        second_kind: &'a dyn Display,
+        second: &'a dyn Display,
+    },
+    HierarchyCut {
+        count: &'a dyn Display,
+    },
+    HierarchyCutGroup {
+        class: &'a dyn Display,
+        elements: &'a dyn Display,
+    },
+    MedoidsAgreementHeader,
+
+    ConfusionMatrixTitle,
+    ConfusionMatrixHeader,
+    TrueClass,
+    PredictedClass,
+    Accuracy {
+        value: &'a dyn Display,
+    },
+    ClassificationReportHeader,
+    MacroAverage,
+    MicroAverage,
+    MatchedClass {
+        predicted: &'a dyn Display,
+        truth: &'a dyn Display,
+    },
+
+    ValidityHeader,
+    Undefined,
+    Wcss {
+        value: &'a dyn Display,
+    },
+    Silhouette {
+        value: &'a dyn Display,
+    },
+    DaviesBouldin {
+        value: &'a dyn Display,
+    },
+    CalinskiHarabasz {
+        value: &'a dyn Display,
+    },
+    Dunn {
+        value: &'a dyn Display,
+    },
+
+    AgreementHeader,
+    AdjustedRandIndex {
+        value: &'a dyn Display,
+    },
+    MutualInformation {
+        normalized: &'a dyn Display,
+        adjusted: &'a dyn Display,
+    },
+    FowlkesMallows {
+        value: &'a dyn Display,
+    },
+    Purity {
+        value: &'a dyn Display,
+    },
+    VMeasure {
+        value: &'a dyn Display,
+        homogeneity: &'a dyn Display,
+        completeness: &'a dyn Display,
+    },
+
+    ModelSelectionTitle,
+    ModelSelectionHeader,
+    ModelSelectionPrompt,
+    ClustersCountAxis,
+    NormalizedScoreAxis,
+    InertiaLabel,
+    SilhouetteLabel,
+    GapLabel,
+    SuggestedClustersCount {
+        elbow: &'a dyn Display,
+        silhouette: &'a dyn Display,
+        gap: &'a dyn Display,
+    },
+
+    SilhouettePlotTitle,
+    SilhouetteAxis,
+    ClusterAxis,
+    MeanSilhouette {
+        value: &'a dyn Display,
+    },
+
+    InvalidMinkowskiOrder {
+        p: &'a dyn Display,
+    },
+    SingularCovariance,
+    EuclideanMetric,
+    SquaredEuclideanMetric,
+    ManhattanMetric,
+    ChebyshevMetric,
+    MinkowskiMetric {
+        p: &'a dyn Display,
+    },
+    CosineMetric,
+    MahalanobisMetric,
+    MetricPrompt {
+        default: &'a dyn Display,
+    },
+    MetricOption {
+        index: &'a dyn Display,
+        metric: &'a dyn Display,
+    },
+    MetricChosen {
+        metric: &'a dyn Display,
+    },
+    RegionsTitle {
+        metric: &'a dyn Display,
+    },
+    RegionsPrompt,
+
+    DimensionMismatch {
+        expected: &'a dyn Display,
+        found: &'a dyn Display,
+    },
+    FeatureOutOfRange {
+        feature: &'a dyn Display,
+        dimension: &'a dyn Display,
+    },
+
+    PcaTitle,
+    PcaHeader,
+    PrincipalComponent {
+        index: &'a dyn Display,
+        ratio: &'a dyn Display,
+        cumulative: &'a dyn Display,
+    },
+    PrincipalComponentAxis {
+        index: &'a dyn Display,
+        ratio: &'a dyn Display,
+    },
+    FeatureLabel {
+        index: &'a dyn Display,
+    },
+    FeatureScales {
+        dimension: &'a dyn Display,
+        scales: &'a dyn Display,
+    },
+    FeatureCentroid {
+        class: &'a dyn Display,
+        centroid: &'a dyn Display,
+    },
+    NoScaling,
+    MinMaxScaling,
+    ZScoreScaling,
+    RobustScaling,
+    WhiteningScaling,
+    ScalingPrompt {
+        default: &'a dyn Display,
+    },
+    ScalingChosen {
+        scaling: &'a dyn Display,
+    },
+
+    GmmTitle,
+    GmmHeader,
+    GmmIteration {
+        iteration: &'a dyn Display,
+        log_likelihood: &'a dyn Display,
+    },
+    GmmComponent {
+        class: &'a dyn Display,
+        weight: &'a dyn Display,
+        mean: &'a dyn Display,
+    },
+    GmmCriteria {
+        log_likelihood: &'a dyn Display,
+        bic: &'a dyn Display,
+        aic: &'a dyn Display,
+    },
+    FullCovariance,
+    DiagonalCovariance,
+    SphericalCovariance,
+    CovariancePrompt {
+        default: &'a dyn Display,
+    },
+    CovarianceChosen {
+        covariance: &'a dyn Display,
+    },
+    GmmSweepPrompt,
+    GmmSweepHeader,
+    GmmSweepRow {
+        k: &'a dyn Display,
+        log_likelihood: &'a dyn Display,
+        bic: &'a dyn Display,
+        aic: &'a dyn Display,
+    },
+    GmmSweepBest {
+        bic: &'a dyn Display,
+        aic: &'a dyn Display,
+    },
+
+    InvalidFuzzifier {
+        m: &'a dyn Display,
+    },
+    FuzzyTitle {
+        fuzzifier: &'a dyn Display,
+    },
+    FuzzyHeader {
+        fuzzifier: &'a dyn Display,
+        iterations: &'a dyn Display,
+    },
+    FuzzyCluster {
+        class: &'a dyn Display,
+        share: &'a dyn Display,
+        centroid: &'a dyn Display,
+    },
+    FuzzyPartition {
+        coefficient: &'a dyn Display,
+        entropy: &'a dyn Display,
+    },
+
+    MedoidsHeader {
+        swaps: &'a dyn Display,
+    },
+    MedoidCluster {
+        class: &'a dyn Display,
+        medoid: &'a dyn Display,
+        size: &'a dyn Display,
+    },
+    MedoidsCost {
+        cost: &'a dyn Display,
+    },
+    KMeanMedoid {
+        medoid: &'a dyn Display,
+        cost: &'a dyn Display,
+    },
+
+    MiniBatchHeader {
+        points: &'a dyn Display,
+        batches: &'a dyn Display,
+    },
+    MiniBatchDrift {
+        batch: &'a dyn Display,
+        drift: &'a dyn Display,
+    },
+    MiniBatchCentroid {
+        class: &'a dyn Display,
+        centroid: &'a dyn Display,
+        count: &'a dyn Display,
+    },
+    StreamingTitle,
+    StreamSample {
+        count: &'a dyn Display,
+    },
+    PointsNotDrawn {
+        count: &'a dyn Display,
+        total: &'a dyn Display,
+    },
+}
+
+impl Display for Message<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match language() {
+            Language::Russian => self.russian(f),
+            Language::English => self.english(f),
+        }
+    }
+}
+
+impl Message<'_> {
+    fn russian(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Message::TaskPrompt => write!(f, "Какое задание выполнить? (1-9, 0 для выхода)"),
+            Message::ProgramFinished => write!(f, "Работа программы завершена"),
+            Message::UnknownTask => write!(f, "Указанного задания не существует."),
+            Message::InvalidNumber => write!(f, "Введено неправильное число."),
+            Message::Error { error } => write!(f, "ОШИБКА: {}", error),
+            Message::Warning { warning } => write!(f, "ПРЕДУПРЕЖДЕНИЕ: {}", warning),
+            Message::UnknownLanguage { code } => write!(
+                f,
+                "ПРЕДУПРЕЖДЕНИЕ: язык {} не поддерживается (доступны ru и en)",
+                code
+            ),
+
+            Message::InvalidRectangle {
+                width,
+                height,
+                bottom_left,
+                top_right,
+            } => write!(
+                f,
+                "Попытка создать прямоугольник с отрицательной шириной ({}) или высотой ({}): Нижний левый угол - {}; Правый верхний угол - {}",
+                width, height, bottom_left, top_right
+            ),
+            Message::InvalidHexColor { hex, reason } => write!(
+                f,
+                "Не удалось распарсить {} как шестнадцатеричный цвет: {}",
+                hex, reason
+            ),
+            Message::HexWithoutHash => write!(f, "цвет не начинается с #"),
+            Message::AlphaOutOfRange { alpha } => {
+                write!(f, "Альфа-канал {} не лежит в пределах (0; 1)!", alpha)
+            }
+            Message::EmptyPointSet => write!(
+                f,
+                "Невозможно подобрать границы: нет ни одной точки с конечными координатами"
+            ),
+            Message::InvalidAspectRatio { ratio } => write!(
+                f,
+                "Соотношение сторон {} должно быть положительным числом",
+                ratio
+            ),
+            Message::FontLoadFailed { error } => write!(f, "Не удалось загрузить шрифт: {}", error),
+            Message::ImageSaveFailed { path, error } => write!(
+                f,
+                "Не удалось сохранить изображение по пути {}: {}",
+                path, error
+            ),
+            Message::ViewerFailed {
+                path,
+                command,
+                error,
+            } => write!(
+                f,
+                "Не удалось открыть изображение по пути {} с помощью команды {}: {}",
+                path, command, error
+            ),
+            Message::InputFailed { error } => {
+                write!(f, "Не удалось прочитать из стандартного ввода: {}", error)
+            }
+            Message::FontReadFailed { error } => {
+                write!(f, "не удалось прочитать файл шрифта: {}", error)
+            }
+            Message::FontUnknownFormat => {
+                write!(f, "неизвестный формат шрифта (поддерживаются BDF и PSF)")
+            }
+            Message::FontMalformed { reason } => {
+                write!(f, "повреждённый файл шрифта: {}", reason)
+            }
+
+            Message::PointOutOfField { point, rect } => write!(
+                f,
+                "Точка {} лежит за пределами поля {} и не отрисована",
+                point, rect
+            ),
+            Message::PointOverlap { point } => {
+                write!(f, "Точка {} накладывается на другую и не отрисована", point)
+            }
+            Message::NoMemberships { point } => write!(
+                f,
+                "У точки {} нет ни одной положительной принадлежности, её цвет не определён",
+                point
+            ),
+            Message::ImageSaved { path } => write!(f, "Изображение сохранено по пути {}", path),
+            Message::NotRasterImage { path } => write!(
+                f,
+                "Изображение {} не растровое, с него нельзя снять кадр",
+                path
+            ),
+            Message::NoFrames { path } => {
+                write!(f, "Нет ни одного кадра для анимации по пути {}", path)
+            }
+            Message::AnimationSaved { count, path } => {
+                write!(f, "Анимация ({} кадров) сохранена по пути {}", count, path)
+            }
+            Message::RectangleBounds {
+                bottom_left,
+                top_right,
+            } => write!(f, "От {} до {}", bottom_left, top_right),
+
+            Message::PointsCountPrompt { default } => {
+                write!(f, "Введите количество точек (По умолчанию: {}).", default)
+            }
+            Message::PointsCountChosen { count } => {
+                write!(f, "Выбранное количество точек: {}", count)
+            }
+            Message::CoresCountPrompt { default } => {
+                write!(f, "Введите количество ядер (По умолчанию: {}).", default)
+            }
+            Message::CoresCountChosen { count } => {
+                write!(f, "Выбранное количество ядер: {}", count)
+            }
+            Message::ClassesCountPrompt { default } => {
+                write!(f, "Введите количество классов (По умолчанию: {}).", default)
+            }
+            Message::ClassesCountChosen { count } => {
+                write!(f, "Выбранное количество классов: {}", count)
+            }
+            Message::YesNoPrompt { question } => {
+                write!(f, "{} (д/н, По умолчанию: н)", question)
+            }
+            Message::ElementsCountPrompt { default } => write!(
+                f,
+                "Введите количество элементов (По умолчанию: {})",
+                default
+            ),
+
+            Message::Boundaries { rect } => write!(f, "Границы: {}", rect),
+            Message::ClassLabel { class } => write!(f, "Класс {}", class),
+
+            Message::KMeanTitle => write!(f, "Центр класса"),
+            Message::KMeanPointsLabel => write!(f, "Точки"),
+            Message::KMeanHeader { rect, center } => {
+                write!(f, "Границы:{}\nЦентр - {}\nТочки:", rect, center)
+            }
+            Message::KMeanPoint {
+                index,
+                point,
+                distance,
+            } => write!(
+                f,
+                "{}: {} | Расстояние до центра: {}",
+                index, point, distance
+            ),
+            Message::KMeanResult { center, distance } => write!(
+                f,
+                "\nЦЕНТР КЛАССА - {} с расстоянием до центра {}",
+                center, distance
+            ),
+
+            Message::ClassificationTitle => write!(f, "Классификация по ближайшему ядру"),
+            Message::Cores => write!(f, "Ядра:"),
+            Message::ClassificationClass { class, core } => {
+                write!(f, "{} класс: {}", class, core)
+            }
+            Message::ClassificationPoint {
+                index,
+                point,
+                distance,
+            } => write!(
+                f,
+                "{}: {} | Расстояние до центра класса: {}",
+                index, point, distance
+            ),
+
+            Message::TwoClassesTitle => write!(f, "Разделяющая функция для двух классов"),
+            Message::FirstClass => write!(f, "I класс"),
+            Message::SecondClass => write!(f, "II класс"),
+            Message::DividingFunction { w_0, w_1, w_2 } => write!(
+                f,
+                "Разделяющая функция: f(x, y) = {} + {}x + {}y",
+                w_0, w_1, w_2
+            ),
+            Message::TrueDividingFunction { w_0, w_1, w_2 } => write!(
+                f,
+                "Истинная разделяющая функция (по ней размечены точки): g(x, y) = {} + {}x + {}y",
+                w_0, w_1, w_2
+            ),
+            Message::TwoClassesPoint {
+                index,
+                point,
+                value,
+                class,
+            } => write!(
+                f,
+                "{} точка: {} | Значение разделяющей функции: {} | ({})",
+                index, point, value, class
+            ),
+
+            Message::NClassesTitle => write!(f, "Разделяющие функции для N классов"),
+            Message::DividingFunctions => write!(f, "Разделяющие функции:"),
+            Message::TrueDividingFunctions => {
+                write!(f, "Истинные разделяющие функции (по ним размечены точки):")
+            }
+            Message::NClassesClass { class } => {
+                write!(f, "\nКЛАСС {} -------------------------", class)
+            }
+            Message::NClassesPoints => write!(f, "\nТОЧКИ:"),
+            Message::NClassesPoint { index, point } => write!(f, "№{} {}", index, point),
+            Message::Chosen => write!(f, " (ВЫБРАН)"),
+            Message::PerceptronPrompt => write!(
+                f,
+                "Обучить функции правилом перцептрона по истинным классам?"
+            ),
+            Message::PerceptronEpoch { epoch, errors } => {
+                write!(f, "Эпоха {}: ошибок классификации {}", epoch, errors)
+            }
+
+            Message::HierarchyTitle => write!(f, "Иерархическая группировка"),
+            Message::InitialDistances => write!(f, "Исходные расстояния:"),
+            Message::ResultingHierarchy => write!(f, "Получившаяся иерархия: "),
+            Message::HierarchyNode => write!(f, "УЗЕЛ"),
+            Message::HierarchyLeaf => write!(f, "ЛИСТ"),
+            Message::HierarchyElementsAxis => write!(f, "Элементы"),
+            Message::MergeDistanceAxis => write!(f, "Расстояние объединения"),
+            Message::HierarchyNodeEntry {
+                id,
+                distance,
+                first_kind,
+                first,
+                second_kind,
+                second,
+            } => write!(
+                f,
+                "УЗЕЛ: {} - Расстояние: {}; Составляющие: {} {} - {} {}",
+                id, distance, first_kind, first, second_kind, second
+            ),
+            Message::HierarchyCut { count } => {
+                write!(f, "Разрез иерархии, групп: {}", count)
+            }
+            Message::HierarchyCutGroup { class, elements } => {
+                write!(f, "Группа {}: элементы {}", class, elements)
+            }
+            Message::MedoidsAgreementHeader => {
+                write!(
+                    f,
+                    "Совпадение разбиения вокруг медоидов с разрезом иерархии:"
+                )
+            }
+            Message::ConfusionMatrixTitle => write!(f, "Матрица ошибок"),
+            Message::ConfusionMatrixHeader => write!(
+                f,
+                "Матрица ошибок (строки - истинные классы, столбцы - предсказанные):"
+            ),
+            Message::TrueClass => write!(f, "Истинный класс"),
+            Message::PredictedClass => write!(f, "Предсказанный класс"),
+            Message::Accuracy { value } => write!(f, "Доля верных ответов (accuracy): {}", value),
+            Message::ClassificationReportHeader => {
+                write!(f, "Класс | Точность | Полнота | F1 | Количество")
+            }
+            Message::MacroAverage => write!(f, "Макро-среднее"),
+            Message::MicroAverage => write!(f, "Микро-среднее"),
+            Message::MatchedClass { predicted, truth } => write!(
+                f,
+                "Предсказанный класс {} сопоставлен истинному классу {}",
+                predicted, truth
+            ),
+
+            Message::ValidityHeader => write!(f, "Качество разбиения:"),
+            Message::Undefined => write!(f, "не определено"),
+            Message::Wcss { value } => {
+                write!(f, "Внутрикластерная сумма квадратов (WCSS): {}", value)
+            }
+            Message::Silhouette { value } => {
+                write!(f, "Силуэт: {} (от -1 до 1, чем больше, тем лучше)", value)
+            }
+            Message::DaviesBouldin { value } => write!(
+                f,
+                "Индекс Дэвиса-Болдина: {} (чем меньше, тем лучше)",
+                value
+            ),
+            Message::CalinskiHarabasz { value } => write!(
+                f,
+                "Индекс Калински-Харабаза: {} (чем больше, тем лучше)",
+                value
+            ),
+            Message::Dunn { value } => {
+                write!(f, "Индекс Данна: {} (чем больше, тем лучше)", value)
+            }
+
+            Message::AgreementHeader => write!(f, "Совпадение с истинными классами:"),
+            Message::AdjustedRandIndex { value } => {
+                write!(f, "Скорректированный индекс Рэнда (ARI): {}", value)
+            }
+            Message::MutualInformation {
+                normalized,
+                adjusted,
+            } => write!(
+                f,
+                "Взаимная информация: нормированная (NMI) {}, скорректированная (AMI) {}",
+                normalized, adjusted
+            ),
+            Message::FowlkesMallows { value } => {
+                write!(f, "Индекс Фоулкса-Мэллоуза: {}", value)
+            }
+            Message::Purity { value } => write!(f, "Чистота: {}", value),
+            Message::VMeasure {
+                value,
+                homogeneity,
+                completeness,
+            } => write!(
+                f,
+                "V-мера: {} (однородность {}, полнота {})",
+                value, homogeneity, completeness
+            ),
+            Message::ModelSelectionTitle => write!(f, "Выбор числа кластеров"),
+            Message::ModelSelectionHeader => {
+                write!(f, "k | Инерция | Силуэт | Статистика разрыва")
+            }
+            Message::ModelSelectionPrompt => write!(
+                f,
+                "Подобрать количество ядер по локтю, силуэту и статистике разрыва? На больших наборах точек это долго."
+            ),
+            Message::ClustersCountAxis => write!(f, "Число кластеров"),
+            Message::NormalizedScoreAxis => write!(f, "Нормированное значение"),
+            Message::InertiaLabel => write!(f, "Инерция"),
+            Message::SilhouetteLabel => write!(f, "Силуэт"),
+            Message::GapLabel => write!(f, "Разрыв"),
+            Message::SuggestedClustersCount {
+                elbow,
+                silhouette,
+                gap,
+            } => write!(
+                f,
+                "Рекомендуемое число кластеров: {} по статистике разрыва ({} по локтю, {} по силуэту)",
+                gap, elbow, silhouette
+            ),
+            Message::SilhouettePlotTitle => write!(f, "Силуэты точек"),
+            Message::SilhouetteAxis => write!(f, "Коэффициент силуэта"),
+            Message::ClusterAxis => write!(f, "Кластер"),
+            Message::MeanSilhouette { value } => write!(f, "среднее: {}", value),
+
+            Message::InvalidMinkowskiOrder { p } => write!(
+                f,
+                "Порядок метрики Минковского должен быть не меньше 1, получено {}",
+                p
+            ),
+            Message::SingularCovariance => write!(
+                f,
+                "Ковариационная матрица вырождена, метрику Махаланобиса построить нельзя"
+            ),
+            Message::EuclideanMetric => write!(f, "Евклидова"),
+            Message::SquaredEuclideanMetric => write!(f, "Квадрат евклидовой"),
+            Message::ManhattanMetric => write!(f, "Манхэттенская"),
+            Message::ChebyshevMetric => write!(f, "Чебышёва"),
+            Message::MinkowskiMetric { p } => write!(f, "Минковского (p = {})", p),
+            Message::CosineMetric => write!(f, "Косинусная"),
+            Message::MahalanobisMetric => write!(f, "Махаланобиса"),
+            Message::MetricPrompt { default } => write!(
+                f,
+                "Выберите метрику расстояния (По умолчанию: {}):",
+                default
+            ),
+            Message::MetricOption { index, metric } => write!(f, "{}. {}", index, metric),
+            Message::MetricChosen { metric } => write!(f, "Выбранная метрика: {}", metric),
+            Message::RegionsTitle { metric } => {
+                write!(f, "Области ближайших ядер, метрика: {}", metric)
+            }
+            Message::RegionsPrompt => write!(
+                f,
+                "Сохранить области ближайших ядер по каждой из метрик (по картинке на метрику)?"
+            ),
+
+            Message::DimensionMismatch { expected, found } => write!(
+                f,
+                "Размерность {} не совпадает с ожидаемой ({})",
+                found, expected
+            ),
+            Message::FeatureOutOfRange { feature, dimension } => write!(
+                f,
+                "Признака с номером {} нет, всего признаков: {}",
+                feature, dimension
+            ),
+
+            Message::PcaTitle => write!(f, "Метод главных компонент"),
+            Message::PcaHeader => write!(f, "Доля объяснённой дисперсии по главным компонентам:"),
+            Message::PrincipalComponent {
+                index,
+                ratio,
+                cumulative,
+            } => write!(f, "ГК{}: {}% (накопленная {}%)", index, ratio, cumulative),
+            Message::PrincipalComponentAxis { index, ratio } => {
+                write!(f, "ГК{} ({}%)", index, ratio)
+            }
+            Message::FeatureLabel { index } => write!(f, "x{}", index),
+            Message::FeatureScales { dimension, scales } => write!(
+                f,
+                "Точки с {} признаками, масштабы признаков: {}",
+                dimension, scales
+            ),
+            Message::FeatureCentroid { class, centroid } => {
+                write!(f, "Центр {}: {}", class, centroid)
+            }
+            Message::NoScaling => write!(f, "Без масштабирования"),
+            Message::MinMaxScaling => write!(f, "Мин-макс (к отрезку от 0 до 1)"),
+            Message::ZScoreScaling => write!(f, "Стандартизация (z-оценки)"),
+            Message::RobustScaling => write!(f, "Робастная (медиана и межквартильный размах)"),
+            Message::WhiteningScaling => write!(f, "Отбеливание (декорреляция признаков)"),
+            Message::ScalingPrompt { default } => write!(
+                f,
+                "Выберите масштабирование признаков (По умолчанию: {}):",
+                default
+            ),
+            Message::ScalingChosen { scaling } => {
+                write!(f, "Выбранное масштабирование: {}", scaling)
+            }
+
+            Message::GmmTitle => write!(f, "Смесь нормальных распределений (EM)"),
+            Message::GmmHeader => write!(
+                f,
+                "Смесь нормальных распределений, логарифм правдоподобия по итерациям:"
+            ),
+            Message::GmmIteration {
+                iteration,
+                log_likelihood,
+            } => write!(f, "{}: {}", iteration, log_likelihood),
+            Message::GmmComponent {
+                class,
+                weight,
+                mean,
+            } => write!(f, "Компонента {}: вес {}, среднее {}", class, weight, mean),
+            Message::GmmCriteria {
+                log_likelihood,
+                bic,
+                aic,
+            } => write!(
+                f,
+                "Логарифм правдоподобия: {}, BIC: {}, AIC: {} (чем меньше, тем лучше)",
+                log_likelihood, bic, aic
+            ),
+            Message::FullCovariance => write!(f, "Полная (эллипсы любого наклона)"),
+            Message::DiagonalCovariance => write!(f, "Диагональная (эллипсы вдоль осей)"),
+            Message::SphericalCovariance => write!(f, "Сферическая (круги)"),
+            Message::CovariancePrompt { default } => write!(
+                f,
+                "Выберите вид ковариационных матриц компонент (По умолчанию: {}):",
+                default
+            ),
+            Message::CovarianceChosen { covariance } => {
+                write!(f, "Выбранный вид ковариации: {}", covariance)
+            }
+            Message::GmmSweepPrompt => write!(
+                f,
+                "Подобрать количество компонент по BIC и AIC? Смесь строится для каждого количества."
+            ),
+            Message::GmmSweepHeader => write!(
+                f,
+                "Количество компонент: логарифм правдоподобия, BIC, AIC (чем меньше, тем лучше)"
+            ),
+            Message::GmmSweepRow {
+                k,
+                log_likelihood,
+                bic,
+                aic,
+            } => write!(f, "{}: {}, {}, {}", k, log_likelihood, bic, aic),
+            Message::GmmSweepBest { bic, aic } => write!(
+                f,
+                "Лучшее количество компонент по BIC: {}, по AIC: {}",
+                bic, aic
+            ),
+
+            Message::InvalidFuzzifier { m } => write!(
+                f,
+                "Степень размытости нечётких c-средних должна быть больше 1, указана {}",
+                m
+            ),
+            Message::FuzzyTitle { fuzzifier } => {
+                write!(f, "Нечёткие c-средние (m = {})", fuzzifier)
+            }
+            Message::FuzzyHeader {
+                fuzzifier,
+                iterations,
+            } => write!(
+                f,
+                "Нечёткие c-средние, степень размытости {}, итераций: {}",
+                fuzzifier, iterations
+            ),
+            Message::FuzzyCluster {
+                class,
+                share,
+                centroid,
+            } => write!(
+                f,
+                "Кластер {}: доля принадлежности {}, центр {}",
+                class, share, centroid
+            ),
+            Message::FuzzyPartition {
+                coefficient,
+                entropy,
+            } => write!(
+                f,
+                "Коэффициент разбиения: {} (1 для чёткого), энтропия разбиения: {} (0 для чёткого)",
+                coefficient, entropy
+            ),
+
+            Message::MedoidsHeader { swaps } => {
+                write!(f, "Разбиение вокруг медоидов (PAM), обменов: {}", swaps)
+            }
+            Message::MedoidCluster {
+                class,
+                medoid,
+                size,
+            } => write!(
+                f,
+                "Кластер {}: медоид - объект {}, объектов: {}",
+                class, medoid, size
+            ),
+            Message::MedoidsCost { cost } => {
+                write!(f, "Сумма расстояний до медоидов: {}", cost)
+            }
+            Message::KMeanMedoid { medoid, cost } => write!(
+                f,
+                "МЕДОИД - {} с суммой расстояний до остальных точек {}",
+                medoid, cost
+            ),
+
+            Message::MiniBatchHeader { points, batches } => write!(
+                f,
+                "K-средние по пакетам: {} точек в {} пакетах, смещение центров по пакетам:",
+                points, batches
+            ),
+            Message::MiniBatchDrift { batch, drift } => write!(f, "{}: {}", batch, drift),
+            Message::MiniBatchCentroid {
+                class,
+                centroid,
+                count,
+            } => write!(f, "Центр {}: {}, получил точек: {}", class, centroid, count),
+            Message::StreamingTitle => write!(f, "K-средние по потоку точек"),
+            Message::StreamSample { count } => write!(
+                f,
+                "Качество разбиения и совпадение с истинными классами по выборке из {} точек:",
+                count
+            ),
+            Message::PointsNotDrawn { count, total } => write!(
+                f,
+                "{} из {} точек попали на занятые пиксели и не отрисованы",
+                count, total
+            ),
+        }
+    }
+
+    fn english(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Message::TaskPrompt => write!(f, "Which task should be run? (1-9, 0 to quit)"),
+            Message::ProgramFinished => write!(f, "The program has finished"),
+            Message::UnknownTask => write!(f, "There is no such task."),
+            Message::InvalidNumber => write!(f, "The number is invalid."),
+            Message::Error { error } => write!(f, "ERROR: {}", error),
+            Message::Warning { warning } => write!(f, "WARNING: {}", warning),
+            Message::UnknownLanguage { code } => write!(
+                f,
+                "WARNING: language {} is not supported (ru and en are available)",
+                code
+            ),
+
+            Message::InvalidRectangle {
+                width,
+                height,
+                bottom_left,
+                top_right,
+            } => write!(
+                f,
+                "Attempt to create a rectangle with a negative width ({}) or height ({}): Bottom left corner - {}; Top right corner - {}",
+                width, height, bottom_left, top_right
+            ),
+            Message::InvalidHexColor { hex, reason } => write!(
+                f,
+                "Could not parse {} as a hexadecimal color: {}",
+                hex, reason
+            ),
+            Message::HexWithoutHash => write!(f, "the color does not start with #"),
+            Message::AlphaOutOfRange { alpha } => {
+                write!(f, "Alpha channel {} is not within (0; 1)!", alpha)
+            }
+            Message::EmptyPointSet => write!(
+                f,
+                "Could not fit the bounds: there are no points with finite coordinates"
+            ),
+            Message::InvalidAspectRatio { ratio } => {
+                write!(f, "Aspect ratio {} must be a positive number", ratio)
+            }
+            Message::FontLoadFailed { error } => write!(f, "Could not load the font: {}", error),
+            Message::ImageSaveFailed { path, error } => {
+                write!(f, "Could not save the image to {}: {}", path, error)
+            }
+            Message::ViewerFailed {
+                path,
+                command,
+                error,
+            } => write!(
+                f,
+                "Could not open the image {} with the command {}: {}",
+                path, command, error
+            ),
+            Message::InputFailed { error } => {
+                write!(f, "Could not read from the standard input: {}", error)
+            }
+            Message::FontReadFailed { error } => {
+                write!(f, "could not read the font file: {}", error)
+            }
+            Message::FontUnknownFormat => {
+                write!(f, "unknown font format (BDF and PSF are supported)")
+            }
+            Message::FontMalformed { reason } => write!(f, "malformed font file: {}", reason),
+
+            Message::PointOutOfField { point, rect } => write!(
+                f,
+                "The point {} lies outside of the field {} and is not drawn",
+                point, rect
+            ),
+            Message::PointOverlap { point } => {
+                write!(
+                    f,
+                    "The point {} overlaps another one and is not drawn",
+                    point
+                )
+            }
+            Message::NoMemberships { point } => write!(
+                f,
+                "The point {} has no positive membership to pick its color by",
+                point
+            ),
+            Message::ImageSaved { path } => write!(f, "The image is saved to {}", path),
+            Message::NotRasterImage { path } => write!(
+                f,
+                "The image {} is not a raster one, no frame can be taken from it",
+                path
+            ),
+            Message::NoFrames { path } => {
+                write!(f, "There are no frames for the animation {}", path)
+            }
+            Message::AnimationSaved { count, path } => {
+                write!(f, "The animation ({} frames) is saved to {}", count, path)
+            }
+            Message::RectangleBounds {
+                bottom_left,
+                top_right,
+            } => write!(f, "From {} to {}", bottom_left, top_right),
+
+            Message::PointsCountPrompt { default } => {
+                write!(f, "Enter the number of points (Default: {}).", default)
+            }
+            Message::PointsCountChosen { count } => {
+                write!(f, "Chosen number of points: {}", count)
+            }
+            Message::CoresCountPrompt { default } => {
+                write!(f, "Enter the number of cores (Default: {}).", default)
+            }
+            Message::CoresCountChosen { count } => write!(f, "Chosen number of cores: {}", count),
+            Message::ClassesCountPrompt { default } => {
+                write!(f, "Enter the number of classes (Default: {}).", default)
+            }
+            Message::ClassesCountChosen { count } => {
+                write!(f, "Chosen number of classes: {}", count)
+            }
+            Message::YesNoPrompt { question } => write!(f, "{} (y/n, Default: n)", question),
+            Message::ElementsCountPrompt { default } => {
+                write!(f, "Enter the number of elements (Default: {})", default)
+            }
+
+            Message::Boundaries { rect } => write!(f, "Boundaries: {}", rect),
+            Message::ClassLabel { class } => write!(f, "Class {}", class),
+
+            Message::KMeanTitle => write!(f, "Class center"),
+            Message::KMeanPointsLabel => write!(f, "Points"),
+            Message::KMeanHeader { rect, center } => {
+                write!(f, "Boundaries:{}\nCenter - {}\nPoints:", rect, center)
+            }
+            Message::KMeanPoint {
+                index,
+                point,
+                distance,
+            } => write!(
+                f,
+                "{}: {} | Distance to the center: {}",
+                index, point, distance
+            ),
+            Message::KMeanResult { center, distance } => write!(
+                f,
+                "\nCLASS CENTER - {} with the distance to the center {}",
+                center, distance
+            ),
+
+            Message::ClassificationTitle => write!(f, "Classification by the nearest core"),
+            Message::Cores => write!(f, "Cores:"),
+            Message::ClassificationClass { class, core } => {
+                write!(f, "Class {}: {}", class, core)
+            }
+            Message::ClassificationPoint {
+                index,
+                point,
+                distance,
+            } => write!(
+                f,
+                "{}: {} | Distance to the class center: {}",
+                index, point, distance
+            ),
+
+            Message::TwoClassesTitle => write!(f, "Dividing function for two classes"),
+            Message::FirstClass => write!(f, "Class I"),
+            Message::SecondClass => write!(f, "Class II"),
+            Message::DividingFunction { w_0, w_1, w_2 } => write!(
+                f,
+                "Dividing function: f(x, y) = {} + {}x + {}y",
+                w_0, w_1, w_2
+            ),
+            Message::TrueDividingFunction { w_0, w_1, w_2 } => write!(
+                f,
+                "True dividing function (the points are labeled by it): g(x, y) = {} + {}x + {}y",
+                w_0, w_1, w_2
+            ),
+            Message::TwoClassesPoint {
+                index,
+                point,
+                value,
+                class,
+            } => write!(
+                f,
+                "Point {}: {} | Dividing function value: {} | ({})",
+                index, point, value, class
+            ),
+
+            Message::NClassesTitle => write!(f, "Dividing functions for N classes"),
+            Message::DividingFunctions => write!(f, "Dividing functions:"),
+            Message::TrueDividingFunctions => {
+                write!(
+                    f,
+                    "True dividing functions (the points are labeled by them):"
+                )
+            }
+            Message::NClassesClass { class } => {
+                write!(f, "\nCLASS {} -------------------------", class)
+            }
+            Message::NClassesPoints => write!(f, "\nPOINTS:"),
+            Message::NClassesPoint { index, point } => write!(f, "#{} {}", index, point),
+            Message::Chosen => write!(f, " (CHOSEN)"),
+            Message::PerceptronPrompt => write!(
+                f,
+                "Train the functions by the perceptron rule on the true classes?"
+            ),
+            Message::PerceptronEpoch { epoch, errors } => {
+                write!(f, "Epoch {}: {} misclassified points", epoch, errors)
+            }
+
+            Message::HierarchyTitle => write!(f, "Hierarchical grouping"),
+            Message::InitialDistances => write!(f, "Initial distances:"),
+            Message::ResultingHierarchy => write!(f, "Resulting hierarchy: "),
+            Message::HierarchyNode => write!(f, "NODE"),
+            Message::HierarchyLeaf => write!(f, "LEAF"),
+            Message::HierarchyElementsAxis => write!(f, "Elements"),
+            Message::MergeDistanceAxis => write!(f, "Merge distance"),
+            Message::HierarchyNodeEntry {
+                id,
+                distance,
+                first_kind,
+                first,
+                second_kind,
+                second,
+            } => write!(
+                f,
+                "NODE: {} - Distance: {}; Members: {} {} - {} {}",
+                id, distance, first_kind, first, second_kind, second
+            ),
+            Message::HierarchyCut { count } => {
+                write!(f, "Hierarchy cut into {} groups:", count)
+            }
+            Message::HierarchyCutGroup { class, elements } => {
+                write!(f, "Group {}: elements {}", class, elements)
+            }
+            Message::MedoidsAgreementHeader => {
+                write!(f, "Agreement of the medoids with the hierarchy cut:")
+            }
+            Message::ConfusionMatrixTitle => write!(f, "Confusion matrix"),
+            Message::ConfusionMatrixHeader => write!(
+                f,
+                "Confusion matrix (rows are true classes, columns are predicted ones):"
+            ),
+            Message::TrueClass => write!(f, "True class"),
+            Message::PredictedClass => write!(f, "Predicted class"),
+            Message::Accuracy { value } => write!(f, "Accuracy: {}", value),
+            Message::ClassificationReportHeader => {
+                write!(f, "Class | Precision | Recall | F1 | Support")
+            }
+            Message::MacroAverage => write!(f, "Macro average"),
+            Message::MicroAverage => write!(f, "Micro average"),
+            Message::MatchedClass { predicted, truth } => write!(
+                f,
+                "Predicted class {} is matched to true class {}",
+                predicted, truth
+            ),
+
+            Message::ValidityHeader => write!(f, "Partition quality:"),
+            Message::Undefined => write!(f, "undefined"),
+            Message::Wcss { value } => {
+                write!(f, "Within-cluster sum of squares (WCSS): {}", value)
+            }
+            Message::Silhouette { value } => {
+                write!(f, "Silhouette: {} (from -1 to 1, higher is better)", value)
+            }
+            Message::DaviesBouldin { value } => {
+                write!(f, "Davies-Bouldin index: {} (lower is better)", value)
+            }
+            Message::CalinskiHarabasz { value } => {
+                write!(f, "Calinski-Harabasz index: {} (higher is better)", value)
+            }
+            Message::Dunn { value } => write!(f, "Dunn index: {} (higher is better)", value),
+
+            Message::AgreementHeader => write!(f, "Agreement with the true classes:"),
+            Message::AdjustedRandIndex { value } => {
+                write!(f, "Adjusted Rand index (ARI): {}", value)
+            }
+            Message::MutualInformation {
+                normalized,
+                adjusted,
+            } => write!(
+                f,
+                "Mutual information: normalized (NMI) {}, adjusted (AMI) {}",
+                normalized, adjusted
+            ),
+            Message::FowlkesMallows { value } => write!(f, "Fowlkes-Mallows index: {}", value),
+            Message::Purity { value } => write!(f, "Purity: {}", value),
+            Message::VMeasure {
+                value,
+                homogeneity,
+                completeness,
+            } => write!(
+                f,
+                "V-measure: {} (homogeneity {}, completeness {})",
+                value, homogeneity, completeness
+            ),
+            Message::ModelSelectionTitle => write!(f, "Choosing the number of clusters"),
+            Message::ModelSelectionHeader => write!(f, "k | Inertia | Silhouette | Gap statistic"),
+            Message::ModelSelectionPrompt => write!(
+                f,
+                "Suggest the number of cores by the elbow, the silhouette and the gap statistic? It is slow on large point sets."
+            ),
+            Message::ClustersCountAxis => write!(f, "Number of clusters"),
+            Message::NormalizedScoreAxis => write!(f, "Normalized value"),
+            Message::InertiaLabel => write!(f, "Inertia"),
+            Message::SilhouetteLabel => write!(f, "Silhouette"),
+            Message::GapLabel => write!(f, "Gap"),
+            Message::SuggestedClustersCount {
+                elbow,
+                silhouette,
+                gap,
+            } => write!(
+                f,
+                "Suggested number of clusters: {} by the gap statistic ({} by the elbow, {} by the silhouette)",
+                gap, elbow, silhouette
+            ),
+            Message::SilhouettePlotTitle => write!(f, "Silhouettes of the points"),
+            Message::SilhouetteAxis => write!(f, "Silhouette coefficient"),
+            Message::ClusterAxis => write!(f, "Cluster"),
+            Message::MeanSilhouette { value } => write!(f, "mean: {}", value),
+
+            Message::InvalidMinkowskiOrder { p } => write!(
+                f,
+                "Order of the Minkowski metric must be at least 1, got {}",
+                p
+            ),
+            Message::SingularCovariance => write!(
+                f,
+                "Covariance matrix is singular, the Mahalanobis metric can not be built"
+            ),
+            Message::EuclideanMetric => write!(f, "Euclidean"),
+            Message::SquaredEuclideanMetric => write!(f, "Squared Euclidean"),
+            Message::ManhattanMetric => write!(f, "Manhattan"),
+            Message::ChebyshevMetric => write!(f, "Chebyshev"),
+            Message::MinkowskiMetric { p } => write!(f, "Minkowski (p = {})", p),
+            Message::CosineMetric => write!(f, "Cosine"),
+            Message::MahalanobisMetric => write!(f, "Mahalanobis"),
+            Message::MetricPrompt { default } => {
+                write!(f, "Choose the distance metric (Default: {}):", default)
+            }
+            Message::MetricOption { index, metric } => write!(f, "{}. {}", index, metric),
+            Message::MetricChosen { metric } => write!(f, "Chosen metric: {}", metric),
+            Message::RegionsTitle { metric } => {
+                write!(f, "Regions of the nearest cores, metric: {}", metric)
+            }
+            Message::RegionsPrompt => write!(
+                f,
+                "Save the regions of the nearest cores under every metric (a picture per metric)?"
+            ),
+
+            Message::DimensionMismatch { expected, found } => write!(
+                f,
+                "Dimension {} does not match the expected one ({})",
+                found, expected
+            ),
+            Message::FeatureOutOfRange { feature, dimension } => write!(
+                f,
+                "There is no feature number {}, the dimension is {}",
+                feature, dimension
+            ),
+
+            Message::PcaTitle => write!(f, "Principal component analysis"),
+            Message::PcaHeader => write!(f, "Explained variance by principal components:"),
+            Message::PrincipalComponent {
+                index,
+                ratio,
+                cumulative,
+            } => write!(f, "PC{}: {}% (cumulative {}%)", index, ratio, cumulative),
+            Message::PrincipalComponentAxis { index, ratio } => {
+                write!(f, "PC{} ({}%)", index, ratio)
+            }
+            Message::FeatureLabel { index } => write!(f, "x{}", index),
+            Message::FeatureScales { dimension, scales } => write!(
+                f,
+                "Points with {} features, scales of the features: {}",
+                dimension, scales
+            ),
+            Message::FeatureCentroid { class, centroid } => {
+                write!(f, "Centroid {}: {}", class, centroid)
+            }
+            Message::NoScaling => write!(f, "No scaling"),
+            Message::MinMaxScaling => write!(f, "Min-max (to the range from 0 to 1)"),
+            Message::ZScoreScaling => write!(f, "Standardization (z-scores)"),
+            Message::RobustScaling => write!(f, "Robust (median and interquartile range)"),
+            Message::WhiteningScaling => write!(f, "Whitening (decorrelated features)"),
+            Message::ScalingPrompt { default } => {
+                write!(f, "Choose the feature scaling (Default: {}):", default)
+            }
+            Message::ScalingChosen { scaling } => write!(f, "Chosen scaling: {}", scaling),
+
+            Message::GmmTitle => write!(f, "Gaussian mixture (EM)"),
+            Message::GmmHeader => write!(f, "Gaussian mixture, log-likelihood by iteration:"),
+            Message::GmmIteration {
+                iteration,
+                log_likelihood,
+            } => write!(f, "{}: {}", iteration, log_likelihood),
+            Message::GmmComponent {
+                class,
+                weight,
+                mean,
+            } => write!(f, "Component {}: weight {}, mean {}", class, weight, mean),
+            Message::GmmCriteria {
+                log_likelihood,
+                bic,
+                aic,
+            } => write!(
+                f,
+                "Log-likelihood: {}, BIC: {}, AIC: {} (the smaller the better)",
+                log_likelihood, bic, aic
+            ),
+            Message::FullCovariance => write!(f, "Full (ellipses at any angle)"),
+            Message::DiagonalCovariance => write!(f, "Diagonal (ellipses along the axes)"),
+            Message::SphericalCovariance => write!(f, "Spherical (circles)"),
+            Message::CovariancePrompt { default } => write!(
+                f,
+                "Choose the shape of the covariance matrices of the components (Default: {}):",
+                default
+            ),
+            Message::CovarianceChosen { covariance } => {
+                write!(f, "Chosen covariance: {}", covariance)
+            }
+            Message::GmmSweepPrompt => write!(
+                f,
+                "Choose the number of components by BIC and AIC? A mixture is fitted for every number."
+            ),
+            Message::GmmSweepHeader => write!(
+                f,
+                "Number of components: log-likelihood, BIC, AIC (the smaller the better)"
+            ),
+            Message::GmmSweepRow {
+                k,
+                log_likelihood,
+                bic,
+                aic,
+            } => write!(f, "{}: {}, {}, {}", k, log_likelihood, bic, aic),
+            Message::GmmSweepBest { bic, aic } => write!(
+                f,
+                "Best number of components by BIC: {}, by AIC: {}",
+                bic, aic
+            ),
+
+            Message::InvalidFuzzifier { m } => write!(
+                f,
+                "The fuzzifier of fuzzy c-means must be above 1, {} was given",
+                m
+            ),
+            Message::FuzzyTitle { fuzzifier } => write!(f, "Fuzzy c-means (m = {})", fuzzifier),
+            Message::FuzzyHeader {
+                fuzzifier,
+                iterations,
+            } => write!(
+                f,
+                "Fuzzy c-means, fuzzifier {}, iterations: {}",
+                fuzzifier, iterations
+            ),
+            Message::FuzzyCluster {
+                class,
+                share,
+                centroid,
+            } => write!(
+                f,
+                "Cluster {}: membership share {}, center {}",
+                class, share, centroid
+            ),
+            Message::FuzzyPartition {
+                coefficient,
+                entropy,
+            } => write!(
+                f,
+                "Partition coefficient: {} (1 when crisp), partition entropy: {} (0 when crisp)",
+                coefficient, entropy
+            ),
+
+            Message::MedoidsHeader { swaps } => {
+                write!(f, "Partitioning around medoids (PAM), swaps: {}", swaps)
+            }
+            Message::MedoidCluster {
+                class,
+                medoid,
+                size,
+            } => write!(
+                f,
+                "Cluster {}: medoid - object {}, objects: {}",
+                class, medoid, size
+            ),
+            Message::MedoidsCost { cost } => {
+                write!(f, "Sum of the distances to the medoids: {}", cost)
+            }
+            Message::KMeanMedoid { medoid, cost } => write!(
+                f,
+                "MEDOID - {} with the sum of the distances to the other points {}",
+                medoid, cost
+            ),
+
+            Message::MiniBatchHeader { points, batches } => write!(
+                f,
+                "Mini-batch k-means: {} points in {} batches, centroid drift by batch:",
+                points, batches
+            ),
+            Message::MiniBatchDrift { batch, drift } => write!(f, "{}: {}", batch, drift),
+            Message::MiniBatchCentroid {
+                class,
+                centroid,
+                count,
+            } => write!(
+                f,
+                "Centroid {}: {}, points received: {}",
+                class, centroid, count
+            ),
+            Message::StreamingTitle => write!(f, "K-means over a stream of points"),
+            Message::StreamSample { count } => write!(
+                f,
+                "Partition quality and agreement with the true classes on a sample of {} points:",
+                count
+            ),
+            Message::PointsNotDrawn { count, total } => write!(
+                f,
+                "{} of {} points fell on taken pixels and were not drawn",
+                count, total
+            ),
+        }
+    }
+}
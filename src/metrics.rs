@@ -0,0 +1,7 @@
+pub mod confusion;
+pub mod external;
+pub mod internal;
+
+pub use confusion::{ConfusionMatrix, Scores, match_labels};
+pub use external::Agreement;
+pub use internal::Validity;
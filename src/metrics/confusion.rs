@@ -0,0 +1,407 @@
+use std::{fmt::Display, path::Path};
+
+use crate::{messages::Message, utils::RoundToDecimalPlaces, visual::heatmap::Heatmap};
+
+/// Precision, recall and their harmonic mean, each in (0; 1)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Scores {
+    pub precision: f32,
+    pub recall: f32,
+    pub f1: f32,
+}
+
+impl Scores {
+    fn new(precision: f32, recall: f32) -> Self {
+        let f1 = if precision + recall > 0.0 {
+            2.0 * precision * recall / (precision + recall)
+        } else {
+            0.0
+        };
+        Self {
+            precision,
+            recall,
+            f1,
+        }
+    }
+}
+
+/// Counts of (true class; predicted class) pairs. Rows are the true classes,
+/// columns are the predicted ones, both sorted by the class number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfusionMatrix {
+    labels: Vec<usize>,
+    counts: Vec<Vec<usize>>,
+}
+
+impl ConfusionMatrix {
+    /// Pairs up the labels by index, extra labels of the longer slice are ignored.
+    /// Classes that occur in either slice get a row and a column.
+    pub fn new(truth: &[usize], predicted: &[usize]) -> Self {
+        let mut labels: Vec<usize> = truth.iter().chain(predicted).copied().collect();
+        labels.sort_unstable();
+        labels.dedup();
+
+        let mut counts = vec![vec![0; labels.len()]; labels.len()];
+        for (truth, predicted) in truth.iter().zip(predicted) {
+            // Both are present, the labels were collected from these slices
+            let row = labels.binary_search(truth).unwrap();
+            let column = labels.binary_search(predicted).unwrap();
+            counts[row][column] += 1;
+        }
+
+        Self { labels, counts }
+    }
+
+    pub fn labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    pub fn count(&self, truth: usize, predicted: usize) -> usize {
+        match (self.index(truth), self.index(predicted)) {
+            (Some(row), Some(column)) => self.counts[row][column],
+            _ => 0,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// Share of the correct predictions, 0 for an empty matrix
+    pub fn accuracy(&self) -> f32 {
+        let correct: usize = (0..self.labels.len()).map(|i| self.counts[i][i]).sum();
+        ratio(correct, self.total())
+    }
+
+    /// Number of points that truly belong to the class
+    pub fn support(&self, label: usize) -> usize {
+        self.index(label)
+            .map_or(0, |row| self.counts[row].iter().sum())
+    }
+
+    /// Share of the points predicted as the class that truly belong to it
+    pub fn precision(&self, label: usize) -> f32 {
+        let Some(column) = self.index(label) else {
+            return 0.0;
+        };
+        let predicted: usize = self.counts.iter().map(|row| row[column]).sum();
+        ratio(self.counts[column][column], predicted)
+    }
+
+    /// Share of the points of the class that were predicted as it
+    pub fn recall(&self, label: usize) -> f32 {
+        let Some(row) = self.index(label) else {
+            return 0.0;
+        };
+        ratio(self.counts[row][row], self.support(label))
+    }
+
+    pub fn scores(&self, label: usize) -> Scores {
+        Scores::new(self.precision(label), self.recall(label))
+    }
+
+    /// Unweighted mean of the scores of the classes, small classes count as much as large ones
+    pub fn macro_average(&self) -> Scores {
+        if self.labels.is_empty() {
+            return Scores::default();
+        }
+        let count = self.labels.len() as f32;
+        let (precision, recall, f1) = self.labels.iter().fold((0.0, 0.0, 0.0), |sum, &label| {
+            let scores = self.scores(label);
+            (
+                sum.0 + scores.precision,
+                sum.1 + scores.recall,
+                sum.2 + scores.f1,
+            )
+        });
+        Scores {
+            precision: precision / count,
+            recall: recall / count,
+            f1: f1 / count,
+        }
+    }
+
+    /// Scores of the pooled predictions. With every point in exactly one class
+    /// all three are equal to the accuracy.
+    pub fn micro_average(&self) -> Scores {
+        let accuracy = self.accuracy();
+        Scores::new(accuracy, accuracy)
+    }
+
+    /// Picture of the matrix, the brighter the cell the more points it has
+    pub fn heatmap<T: AsRef<Path>>(&self, path: T) -> Heatmap<T> {
+        let values = self
+            .counts
+            .iter()
+            .map(|row| row.iter().map(|&count| count as f32).collect())
+            .collect();
+        let labels: Vec<String> = self.labels.iter().map(|label| label.to_string()).collect();
+
+        let mut heatmap = Heatmap::new(path, values);
+        heatmap.set_row_labels(labels.clone());
+        heatmap.set_column_labels(labels);
+        heatmap.set_title(Message::ConfusionMatrixTitle.to_string());
+        heatmap.set_row_title(Message::TrueClass.to_string());
+        heatmap.set_column_title(Message::PredictedClass.to_string());
+        heatmap
+    }
+
+    fn index(&self, label: usize) -> Option<usize> {
+        self.labels.binary_search(&label).ok()
+    }
+}
+
+/// Matrix followed by the per-class report and the averages
+impl Display for ConfusionMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .counts
+            .iter()
+            .flatten()
+            .map(|count| count.to_string().len())
+            .chain(self.labels.iter().map(|label| label.to_string().len()))
+            .max()
+            .unwrap_or(1);
+
+        writeln!(f, "{}", Message::ConfusionMatrixHeader)?;
+        write!(f, "{:>width$} |", "")?;
+        for label in &self.labels {
+            write!(f, " {:>width$}", label)?;
+        }
+        writeln!(f)?;
+        for (label, row) in self.labels.iter().zip(&self.counts) {
+            write!(f, "{:>width$} |", label)?;
+            for count in row {
+                write!(f, " {:>width$}", count)?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(
+            f,
+            "\n{}",
+            Message::Accuracy {
+                value: &self.accuracy().round_to_dp(3)
+            }
+        )?;
+        writeln!(f, "{}", Message::ClassificationReportHeader)?;
+        for &label in &self.labels {
+            let scores = self.scores(label);
+            writeln!(
+                f,
+                "{} | {} | {} | {} | {}",
+                label,
+                scores.precision.round_to_dp(3),
+                scores.recall.round_to_dp(3),
+                scores.f1.round_to_dp(3),
+                self.support(label)
+            )?;
+        }
+
+        for (name, scores) in [
+            (Message::MacroAverage, self.macro_average()),
+            (Message::MicroAverage, self.micro_average()),
+        ] {
+            writeln!(
+                f,
+                "{} | {} | {} | {} | {}",
+                name,
+                scores.precision.round_to_dp(3),
+                scores.recall.round_to_dp(3),
+                scores.f1.round_to_dp(3),
+                self.total()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// One-to-one matching of the predicted classes to the true ones that puts the
+/// most points on the diagonal (Hungarian method, `O(n³)` in the number of
+/// classes). Needed when the numbers of the predicted classes mean nothing by
+/// themselves, e.g. for clusters. Returns (predicted; true) pairs sorted by the
+/// predicted class, predicted classes left over get new numbers above the true ones.
+pub fn match_labels(truth: &[usize], predicted: &[usize]) -> Vec<(usize, usize)> {
+    let distinct = |labels: &[usize]| {
+        let mut labels = labels.to_vec();
+        labels.sort_unstable();
+        labels.dedup();
+        labels
+    };
+    let true_labels = distinct(truth);
+    let predicted_labels = distinct(predicted);
+    let size = true_labels.len().max(predicted_labels.len());
+
+    // Rows are the predicted classes, columns are the true ones, missing ones cost nothing
+    let mut cost = vec![vec![0_i64; size]; size];
+    for (truth, predicted) in truth.iter().zip(predicted) {
+        let row = predicted_labels.binary_search(predicted).unwrap();
+        let column = true_labels.binary_search(truth).unwrap();
+        cost[row][column] -= 1;
+    }
+
+    // Potentials of the rows and the columns, both with a dummy zero index
+    let mut row_potential = vec![0_i64; size + 1];
+    let mut column_potential = vec![0_i64; size + 1];
+    let mut row_of_column = vec![0_usize; size + 1];
+    let mut way = vec![0_usize; size + 1];
+    for row in 1..=size {
+        row_of_column[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![i64::MAX; size + 1];
+        let mut used = vec![false; size + 1];
+        loop {
+            used[column] = true;
+            let current_row = row_of_column[column];
+            let mut delta = i64::MAX;
+            let mut next = 0;
+            for candidate in 1..=size {
+                if used[candidate] {
+                    continue;
+                }
+                let slack = cost[current_row - 1][candidate - 1]
+                    - row_potential[current_row]
+                    - column_potential[candidate];
+                if slack < min_slack[candidate] {
+                    min_slack[candidate] = slack;
+                    way[candidate] = column;
+                }
+                if min_slack[candidate] < delta {
+                    delta = min_slack[candidate];
+                    next = candidate;
+                }
+            }
+            for candidate in 0..=size {
+                if used[candidate] {
+                    row_potential[row_of_column[candidate]] += delta;
+                    column_potential[candidate] -= delta;
+                } else {
+                    min_slack[candidate] -= delta;
+                }
+            }
+            column = next;
+            if row_of_column[column] == 0 {
+                break;
+            }
+        }
+        while column != 0 {
+            let previous = way[column];
+            row_of_column[column] = row_of_column[previous];
+            column = previous;
+        }
+    }
+
+    let mut column_of_row = vec![0; size];
+    for column in 1..=size {
+        column_of_row[row_of_column[column] - 1] = column - 1;
+    }
+    let mut spare = true_labels.last().map_or(1, |last| last + 1);
+    predicted_labels
+        .iter()
+        .zip(column_of_row)
+        .map(|(&predicted, column)| match true_labels.get(column) {
+            Some(&truth) => (predicted, truth),
+            None => {
+                spare += 1;
+                (predicted, spare - 1)
+            }
+        })
+        .collect()
+}
+
+fn ratio(part: usize, whole: usize) -> f32 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f32 / whole as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::confusion::{ConfusionMatrix, match_labels};
+
+    #[test]
+    fn scores_of_a_known_matrix() {
+        let truth = [1, 1, 1, 2, 2, 3];
+        let predicted = [1, 1, 2, 2, 2, 1];
+        let confusion = ConfusionMatrix::new(&truth, &predicted);
+
+        assert_eq!(confusion.count(1, 1), 2);
+        assert_eq!(confusion.count(3, 1), 1);
+        assert_eq!(confusion.total(), 6);
+        assert!((confusion.accuracy() - 4.0 / 6.0).abs() < 1e-6);
+        assert!((confusion.precision(2) - 2.0 / 3.0).abs() < 1e-6);
+        assert!((confusion.recall(1) - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(confusion.recall(3), 0.0);
+    }
+
+    #[test]
+    fn matching_undoes_a_permutation() {
+        let truth = [1, 1, 2, 2, 2, 3, 3, 3, 3];
+        let predicted = [3, 3, 1, 1, 2, 2, 2, 2, 2];
+        assert_eq!(
+            match_labels(&truth, &predicted),
+            vec![(1, 2), (2, 3), (3, 1)]
+        );
+    }
+
+    #[test]
+    fn matching_is_the_best_permutation() {
+        fn permutations(items: Vec<usize>) -> Vec<Vec<usize>> {
+            if items.len() <= 1 {
+                return vec![items];
+            }
+            let mut result = Vec::new();
+            for (index, &item) in items.iter().enumerate() {
+                let mut rest = items.clone();
+                rest.remove(index);
+                for mut tail in permutations(rest) {
+                    tail.insert(0, item);
+                    result.push(tail);
+                }
+            }
+            result
+        }
+
+        let mut state = 12345_u64;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as usize % 4 + 1
+        };
+        for _ in 0..20 {
+            let truth: Vec<usize> = (0..60).map(|_| next()).collect();
+            let predicted: Vec<usize> = (0..60).map(|_| next()).collect();
+            let agreement = |mapping: &dyn Fn(usize) -> usize| {
+                truth
+                    .iter()
+                    .zip(&predicted)
+                    .filter(|&(&truth, &predicted)| mapping(predicted) == truth)
+                    .count()
+            };
+
+            let matching = match_labels(&truth, &predicted);
+            let found = agreement(&|class| matching[class - 1].1);
+            let best = permutations(vec![1, 2, 3, 4])
+                .into_iter()
+                .map(|permutation| agreement(&|class| permutation[class - 1]))
+                .max()
+                .unwrap();
+            assert_eq!(found, best);
+        }
+    }
+
+    #[test]
+    fn matching_with_more_predicted_classes() {
+        let truth = [1, 1, 2, 2];
+        let predicted = [5, 5, 7, 9];
+        let matching = match_labels(&truth, &predicted);
+
+        assert_eq!(matching[0], (5, 1));
+        // Either of the two halves of the second class may be matched to it
+        let truths: Vec<usize> = matching[1..].iter().map(|&(_, truth)| truth).collect();
+        assert!(truths == [2, 3] || truths == [3, 2]);
+    }
+}
@@ -0,0 +1,347 @@
+use std::fmt::Display;
+
+use crate::{messages::Message, utils::RoundToDecimalPlaces};
+
+/// Counts of points for every pair of (true class; found cluster), with the
+/// sums of the rows and the columns. Labels are paired up by index.
+struct Contingency {
+    counts: Vec<Vec<usize>>,
+    truth_sizes: Vec<usize>,
+    found_sizes: Vec<usize>,
+    total: usize,
+}
+
+impl Contingency {
+    fn new(truth: &[usize], found: &[usize]) -> Self {
+        let index = |labels: &[usize]| {
+            let mut unique = labels.to_vec();
+            unique.sort_unstable();
+            unique.dedup();
+            unique
+        };
+        let count = truth.len().min(found.len());
+        let truth_labels = index(&truth[..count]);
+        let found_labels = index(&found[..count]);
+
+        let mut counts = vec![vec![0; found_labels.len()]; truth_labels.len()];
+        for (truth, found) in truth.iter().zip(found) {
+            let row = truth_labels.binary_search(truth).unwrap();
+            let column = found_labels.binary_search(found).unwrap();
+            counts[row][column] += 1;
+        }
+        let truth_sizes = counts.iter().map(|row| row.iter().sum()).collect();
+        let found_sizes = (0..found_labels.len())
+            .map(|column| counts.iter().map(|row| row[column]).sum())
+            .collect();
+
+        Self {
+            counts,
+            truth_sizes,
+            found_sizes,
+            total: count,
+        }
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.counts.iter().enumerate().flat_map(|(row, counts)| {
+            counts
+                .iter()
+                .enumerate()
+                .filter(|(_, count)| **count > 0)
+                .map(move |(column, &count)| (row, column, count))
+        })
+    }
+
+    fn mutual_information(&self) -> f64 {
+        let total = self.total as f64;
+        self.cells()
+            .map(|(row, column, count)| {
+                let count = count as f64;
+                let expected = self.truth_sizes[row] as f64 * self.found_sizes[column] as f64;
+                count / total * (total * count / expected).ln()
+            })
+            .sum()
+    }
+
+    /// Mutual information of two random labelings with the same cluster sizes
+    fn expected_mutual_information(&self) -> f64 {
+        let total = self.total;
+        let ln_factorial: Vec<f64> = (0..=total)
+            .scan(0.0, |sum, k| {
+                if k > 0 {
+                    *sum += (k as f64).ln();
+                }
+                Some(*sum)
+            })
+            .collect();
+
+        let mut expected = 0.0;
+        for &a in &self.truth_sizes {
+            for &b in &self.found_sizes {
+                let first = (a + b).saturating_sub(total).max(1);
+                for count in first..=a.min(b) {
+                    let term = count as f64 / total as f64
+                        * (total as f64 * count as f64 / (a as f64 * b as f64)).ln();
+                    let ln_probability = ln_factorial[a]
+                        + ln_factorial[b]
+                        + ln_factorial[total - a]
+                        + ln_factorial[total - b]
+                        - ln_factorial[total]
+                        - ln_factorial[count]
+                        - ln_factorial[a - count]
+                        - ln_factorial[b - count]
+                        - ln_factorial[total + count - a - b];
+                    expected += term * ln_probability.exp();
+                }
+            }
+        }
+        expected
+    }
+}
+
+fn entropy(sizes: &[usize], total: usize) -> f64 {
+    sizes
+        .iter()
+        .filter(|size| **size > 0)
+        .map(|&size| {
+            let share = size as f64 / total as f64;
+            -share * share.ln()
+        })
+        .sum()
+}
+
+fn pairs(count: usize) -> f64 {
+    count as f64 * count.saturating_sub(1) as f64 / 2.0
+}
+
+/// Adjusted Rand index: agreement of the pairs of points (both together or both
+/// apart) corrected for chance. 1 for equal partitions, about 0 for random ones.
+pub fn adjusted_rand_index(truth: &[usize], found: &[usize]) -> f32 {
+    let table = Contingency::new(truth, found);
+    let index: f64 = table.cells().map(|(_, _, count)| pairs(count)).sum();
+    let truth_pairs: f64 = table.truth_sizes.iter().map(|&size| pairs(size)).sum();
+    let found_pairs: f64 = table.found_sizes.iter().map(|&size| pairs(size)).sum();
+
+    let expected = truth_pairs * found_pairs / pairs(table.total).max(1.0);
+    let max = (truth_pairs + found_pairs) / 2.0;
+    if max == expected {
+        // Both partitions are trivial (one cluster or only single points)
+        return 1.0;
+    }
+    ((index - expected) / (max - expected)) as f32
+}
+
+/// Mutual information divided by the mean entropy of the partitions, in (0; 1)
+pub fn normalized_mutual_information(truth: &[usize], found: &[usize]) -> f32 {
+    let table = Contingency::new(truth, found);
+    let mean_entropy =
+        (entropy(&table.truth_sizes, table.total) + entropy(&table.found_sizes, table.total)) / 2.0;
+    if mean_entropy == 0.0 {
+        return 1.0;
+    }
+    (table.mutual_information() / mean_entropy) as f32
+}
+
+/// Mutual information corrected for chance, like [`adjusted_rand_index`] it is
+/// 1 for equal partitions and about 0 for random ones
+pub fn adjusted_mutual_information(truth: &[usize], found: &[usize]) -> f32 {
+    let table = Contingency::new(truth, found);
+    let mean_entropy =
+        (entropy(&table.truth_sizes, table.total) + entropy(&table.found_sizes, table.total)) / 2.0;
+    if mean_entropy == 0.0 {
+        return 1.0;
+    }
+    let expected = table.expected_mutual_information();
+    let denominator = mean_entropy - expected;
+    // Keeps the sign of the denominator when it is almost 0
+    let denominator = if denominator < 0.0 {
+        denominator.min(-f64::EPSILON)
+    } else {
+        denominator.max(f64::EPSILON)
+    };
+    ((table.mutual_information() - expected) / denominator) as f32
+}
+
+/// Geometric mean of the pairwise precision and recall, in (0; 1)
+pub fn fowlkes_mallows(truth: &[usize], found: &[usize]) -> f32 {
+    let table = Contingency::new(truth, found);
+    let together: f64 = table.cells().map(|(_, _, count)| pairs(count)).sum();
+    let truth_pairs: f64 = table.truth_sizes.iter().map(|&size| pairs(size)).sum();
+    let found_pairs: f64 = table.found_sizes.iter().map(|&size| pairs(size)).sum();
+    if truth_pairs == 0.0 || found_pairs == 0.0 {
+        return 0.0;
+    }
+    (together / (truth_pairs * found_pairs).sqrt()) as f32
+}
+
+/// Share of the points that belong to the most common true class of their
+/// cluster. 1 when every point is a cluster of its own, so it favours many clusters.
+pub fn purity(truth: &[usize], found: &[usize]) -> f32 {
+    let table = Contingency::new(truth, found);
+    if table.total == 0 {
+        return 0.0;
+    }
+    let majority: usize = (0..table.found_sizes.len())
+        .map(|column| {
+            table
+                .counts
+                .iter()
+                .map(|row| row[column])
+                .max()
+                .unwrap_or(0)
+        })
+        .sum();
+    majority as f32 / table.total as f32
+}
+
+/// Homogeneity (every cluster holds a single class), completeness (every class
+/// is in a single cluster) and their harmonic mean, the V-measure
+pub fn v_measure(truth: &[usize], found: &[usize]) -> (f32, f32, f32) {
+    let table = Contingency::new(truth, found);
+    let mutual_information = table.mutual_information();
+    let share = |entropy: f64| {
+        if entropy == 0.0 {
+            1.0
+        } else {
+            mutual_information / entropy
+        }
+    };
+    let homogeneity = share(entropy(&table.truth_sizes, table.total));
+    let completeness = share(entropy(&table.found_sizes, table.total));
+    let v_measure = if homogeneity + completeness == 0.0 {
+        0.0
+    } else {
+        2.0 * homogeneity * completeness / (homogeneity + completeness)
+    };
+    (homogeneity as f32, completeness as f32, v_measure as f32)
+}
+
+/// All of the external indices comparing found clusters with the true classes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Agreement {
+    pub adjusted_rand_index: f32,
+    pub normalized_mutual_information: f32,
+    pub adjusted_mutual_information: f32,
+    pub fowlkes_mallows: f32,
+    pub purity: f32,
+    pub homogeneity: f32,
+    pub completeness: f32,
+    pub v_measure: f32,
+}
+
+impl Agreement {
+    pub fn new(truth: &[usize], found: &[usize]) -> Self {
+        let (homogeneity, completeness, v_measure) = v_measure(truth, found);
+        Self {
+            adjusted_rand_index: adjusted_rand_index(truth, found),
+            normalized_mutual_information: normalized_mutual_information(truth, found),
+            adjusted_mutual_information: adjusted_mutual_information(truth, found),
+            fowlkes_mallows: fowlkes_mallows(truth, found),
+            purity: purity(truth, found),
+            homogeneity,
+            completeness,
+            v_measure,
+        }
+    }
+}
+
+impl Display for Agreement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", Message::AgreementHeader)?;
+        writeln!(
+            f,
+            "{}",
+            Message::AdjustedRandIndex {
+                value: &self.adjusted_rand_index.round_to_dp(3)
+            }
+        )?;
+        writeln!(
+            f,
+            "{}",
+            Message::MutualInformation {
+                normalized: &self.normalized_mutual_information.round_to_dp(3),
+                adjusted: &self.adjusted_mutual_information.round_to_dp(3)
+            }
+        )?;
+        writeln!(
+            f,
+            "{}",
+            Message::FowlkesMallows {
+                value: &self.fowlkes_mallows.round_to_dp(3)
+            }
+        )?;
+        writeln!(
+            f,
+            "{}",
+            Message::Purity {
+                value: &self.purity.round_to_dp(3)
+            }
+        )?;
+        write!(
+            f,
+            "{}",
+            Message::VMeasure {
+                value: &self.v_measure.round_to_dp(3),
+                homogeneity: &self.homogeneity.round_to_dp(3),
+                completeness: &self.completeness.round_to_dp(3)
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::external::{
+        Agreement, adjusted_mutual_information, adjusted_rand_index, normalized_mutual_information,
+    };
+
+    const TRUTH: [usize; 9] = [1, 1, 1, 2, 2, 2, 3, 3, 3];
+    const FOUND: [usize; 9] = [1, 1, 2, 2, 2, 3, 3, 3, 3];
+
+    /// The same partition with the clusters renumbered
+    fn permuted(labels: &[usize]) -> Vec<usize> {
+        labels.iter().map(|label| [7, 4, 9][label - 1]).collect()
+    }
+
+    fn assert_close(first: f32, second: f32) {
+        assert!((first - second).abs() < 1e-5, "{} != {}", first, second);
+    }
+
+    #[test]
+    fn identical_labelings_agree_fully() {
+        for found in [TRUTH.to_vec(), permuted(&TRUTH)] {
+            assert_close(adjusted_rand_index(&TRUTH, &found), 1.0);
+            assert_close(normalized_mutual_information(&TRUTH, &found), 1.0);
+            assert_close(adjusted_mutual_information(&TRUTH, &found), 1.0);
+        }
+    }
+
+    #[test]
+    fn renumbering_changes_nothing() {
+        let agreement = Agreement::new(&TRUTH, &FOUND);
+        let renumbered = Agreement::new(&permuted(&TRUTH), &permuted(&FOUND));
+        let found_renumbered = Agreement::new(&TRUTH, &permuted(&FOUND));
+
+        for other in [renumbered, found_renumbered] {
+            assert_close(agreement.adjusted_rand_index, other.adjusted_rand_index);
+            assert_close(
+                agreement.normalized_mutual_information,
+                other.normalized_mutual_information,
+            );
+            assert_close(
+                agreement.adjusted_mutual_information,
+                other.adjusted_mutual_information,
+            );
+            assert_close(agreement.v_measure, other.v_measure);
+        }
+        assert!(agreement.adjusted_rand_index < 1.0);
+    }
+
+    #[test]
+    fn adjusted_rand_index_of_known_labelings() {
+        assert_close(
+            adjusted_rand_index(&[1, 1, 1, 2, 2, 2], &[1, 1, 2, 2, 3, 3]),
+            0.242_424_24,
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use rayon::prelude::*;
+
+use crate::{
+    distance::Metric,
+    messages::Message,
+    utils::RoundToDecimalPlaces,
+    vector::{Features, mean},
+};
+
+/// Mean point of every cluster, whatever metric the indices use.
+/// Points and labels are paired up by index.
+pub fn centroids<P: Features>(points: &[P], labels: &[usize]) -> BTreeMap<usize, P> {
+    let mut clusters: BTreeMap<usize, Vec<&P>> = BTreeMap::new();
+    for (point, &label) in points.iter().zip(labels) {
+        clusters.entry(label).or_default().push(point);
+    }
+    clusters
+        .into_iter()
+        .filter_map(|(label, members)| Some((label, mean(members)?)))
+        .collect()
+}
+
+/// Within-cluster sum of squares (inertia): squared distances of the points to
+/// the centroids of their clusters. The smaller the tighter the clusters.
+pub fn wcss<P: Features>(points: &[P], labels: &[usize], metric: &dyn Metric) -> f32 {
+    let centroids = centroids(points, labels);
+    points
+        .iter()
+        .zip(labels)
+        .map(|(point, label)| metric.between(point, &centroids[label]).powi(2))
+        .sum()
+}
+
+/// Silhouette of every point in (-1; 1): how much closer it is to its own cluster
+/// than to the nearest other one. Points of single point clusters get 0.
+pub fn silhouette_samples<P: Features>(
+    points: &[P],
+    labels: &[usize],
+    metric: &dyn Metric,
+) -> Vec<f32> {
+    let points = &points[..points.len().min(labels.len())];
+    let sizes = cluster_sizes(labels);
+    if sizes.len() < 2 {
+        return vec![0.0; points.len()];
+    }
+
+    points
+        .par_iter()
+        .zip(labels)
+        .map(|(point, label)| {
+            if sizes[label] < 2 {
+                return 0.0;
+            }
+            let mut sums: BTreeMap<usize, f32> = BTreeMap::new();
+            for (other, other_label) in points.iter().zip(labels) {
+                *sums.entry(*other_label).or_insert(0.0) += metric.between(point, other);
+            }
+
+            // The point itself adds 0 to the sum but is not counted
+            let own = sums[label] / (sizes[label] - 1) as f32;
+            let nearest = sums
+                .iter()
+                .filter(|(other_label, _)| *other_label != label)
+                .map(|(other_label, sum)| sum / sizes[other_label] as f32)
+                .fold(f32::INFINITY, f32::min);
+
+            let largest = own.max(nearest);
+            if largest > 0.0 {
+                (nearest - own) / largest
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Mean silhouette of the points, the closer to 1 the better.
+/// `None` for less than 2 clusters.
+pub fn silhouette<P: Features>(points: &[P], labels: &[usize], metric: &dyn Metric) -> Option<f32> {
+    if cluster_sizes(labels).len() < 2 {
+        return None;
+    }
+    let samples = silhouette_samples(points, labels, metric);
+    Some(samples.iter().sum::<f32>() / samples.len() as f32)
+}
+
+/// Davies–Bouldin index: mean over clusters of the worst ratio of the spreads
+/// of two clusters to the distance between their centroids. The smaller the better,
+/// `None` for less than 2 clusters.
+pub fn davies_bouldin<P: Features>(
+    points: &[P],
+    labels: &[usize],
+    metric: &dyn Metric,
+) -> Option<f32> {
+    let centroids = centroids(points, labels);
+    if centroids.len() < 2 {
+        return None;
+    }
+
+    let mut spreads: BTreeMap<usize, (f32, usize)> = BTreeMap::new();
+    for (point, label) in points.iter().zip(labels) {
+        let spread = spreads.entry(*label).or_insert((0.0, 0));
+        spread.0 += metric.between(point, &centroids[label]);
+        spread.1 += 1;
+    }
+    let spreads: BTreeMap<usize, f32> = spreads
+        .into_iter()
+        .map(|(label, (sum, count))| (label, sum / count as f32))
+        .collect();
+
+    let worst_ratios = centroids.iter().map(|(label, centroid)| {
+        centroids
+            .iter()
+            .filter(|(other_label, _)| *other_label != label)
+            .map(|(other_label, other_centroid)| {
+                let distance = metric.between(centroid, other_centroid);
+                let spread = spreads[label] + spreads[other_label];
+                if distance > 0.0 {
+                    spread / distance
+                } else {
+                    f32::INFINITY
+                }
+            })
+            .fold(0.0, f32::max)
+    });
+    Some(worst_ratios.sum::<f32>() / centroids.len() as f32)
+}
+
+/// Calinski–Harabasz index: dispersion between clusters over dispersion within
+/// them, scaled by the degrees of freedom. The larger the better, `None` for
+/// less than 2 clusters or as many clusters as points.
+pub fn calinski_harabasz<P: Features>(
+    points: &[P],
+    labels: &[usize],
+    metric: &dyn Metric,
+) -> Option<f32> {
+    let sizes = cluster_sizes(labels);
+    let count = points.len().min(labels.len());
+    if sizes.len() < 2 || sizes.len() >= count {
+        return None;
+    }
+
+    let mean = mean(&points[..count])?;
+    let between: f32 = centroids(points, labels)
+        .iter()
+        .map(|(label, centroid)| sizes[label] as f32 * metric.between(centroid, &mean).powi(2))
+        .sum();
+    let within = wcss(points, labels, metric);
+
+    if within == 0.0 {
+        return Some(f32::INFINITY);
+    }
+    let clusters = sizes.len() as f32;
+    Some(between / within * (count as f32 - clusters) / (clusters - 1.0))
+}
+
+/// Dunn index: the smallest distance between points of different clusters over
+/// the largest diameter of a cluster. The larger the better, `None` for less
+/// than 2 clusters.
+pub fn dunn<P: Features>(points: &[P], labels: &[usize], metric: &dyn Metric) -> Option<f32> {
+    if cluster_sizes(labels).len() < 2 {
+        return None;
+    }
+
+    let points = &points[..points.len().min(labels.len())];
+    let (separation, diameter) = points
+        .par_iter()
+        .enumerate()
+        .map(|(i, point)| {
+            let mut separation = f32::INFINITY;
+            let mut diameter: f32 = 0.0;
+            for (other, other_label) in points.iter().zip(labels).skip(i + 1) {
+                let distance = metric.between(point, other);
+                if *other_label == labels[i] {
+                    diameter = diameter.max(distance);
+                } else {
+                    separation = separation.min(distance);
+                }
+            }
+            (separation, diameter)
+        })
+        .reduce(
+            || (f32::INFINITY, 0.0),
+            |first, second| (first.0.min(second.0), first.1.max(second.1)),
+        );
+
+    if diameter == 0.0 {
+        return Some(f32::INFINITY);
+    }
+    Some(separation / diameter)
+}
+
+fn cluster_sizes(labels: &[usize]) -> BTreeMap<usize, usize> {
+    let mut sizes = BTreeMap::new();
+    for &label in labels {
+        *sizes.entry(label).or_insert(0) += 1;
+    }
+    sizes
+}
+
+/// All of the internal indices of a partition, `None` where an index is not
+/// defined for it (e.g. a single cluster)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Validity {
+    pub wcss: f32,
+    pub silhouette: Option<f32>,
+    pub davies_bouldin: Option<f32>,
+    pub calinski_harabasz: Option<f32>,
+    pub dunn: Option<f32>,
+}
+
+impl Validity {
+    pub fn new<P: Features>(points: &[P], labels: &[usize], metric: &dyn Metric) -> Self {
+        Self {
+            wcss: wcss(points, labels, metric),
+            silhouette: silhouette(points, labels, metric),
+            davies_bouldin: davies_bouldin(points, labels, metric),
+            calinski_harabasz: calinski_harabasz(points, labels, metric),
+            dunn: dunn(points, labels, metric),
+        }
+    }
+}
+
+impl Display for Validity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |value: Option<f32>| match value {
+            Some(value) => value.round_to_dp(3).to_string(),
+            None => Message::Undefined.to_string(),
+        };
+
+        writeln!(f, "{}", Message::ValidityHeader)?;
+        writeln!(
+            f,
+            "{}",
+            Message::Wcss {
+                value: &self.wcss.round_to_dp(2)
+            }
+        )?;
+        writeln!(
+            f,
+            "{}",
+            Message::Silhouette {
+                value: &show(self.silhouette)
+            }
+        )?;
+        writeln!(
+            f,
+            "{}",
+            Message::DaviesBouldin {
+                value: &show(self.davies_bouldin)
+            }
+        )?;
+        writeln!(
+            f,
+            "{}",
+            Message::CalinskiHarabasz {
+                value: &show(self.calinski_harabasz)
+            }
+        )?;
+        write!(
+            f,
+            "{}",
+            Message::Dunn {
+                value: &show(self.dunn)
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        distance::Euclidean,
+        geometry::Point,
+        metrics::internal::{Validity, davies_bouldin, silhouette, wcss},
+    };
+
+    /// Two squares of side 2 whose centers are 100 apart
+    fn separated() -> (Vec<Point>, Vec<usize>) {
+        let corners = [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)];
+        corners
+            .iter()
+            .map(|&(x, y)| (Point::new(x, y), 1))
+            .chain(corners.iter().map(|&(x, y)| (Point::new(x + 100.0, y), 2)))
+            .unzip()
+    }
+
+    #[test]
+    fn well_separated_clusters() {
+        let (points, labels) = separated();
+
+        assert!((wcss(&points, &labels, &Euclidean) - 16.0).abs() < 1e-4);
+        assert!(silhouette(&points, &labels, &Euclidean).unwrap() > 0.97);
+        // Mean spread of √2 in both, 100 between the centroids
+        let expected = 2.0 * 2.0_f32.sqrt() / 100.0;
+        assert!((davies_bouldin(&points, &labels, &Euclidean).unwrap() - expected).abs() < 1e-4);
+    }
+
+    #[test]
+    fn mixed_clusters_score_worse() {
+        let (points, _) = separated();
+        let mixed = [1, 2, 1, 2, 1, 2, 1, 2];
+
+        assert!(silhouette(&points, &mixed, &Euclidean).unwrap() < 0.0);
+        assert!(davies_bouldin(&points, &mixed, &Euclidean).unwrap() > 1.0);
+    }
+
+    #[test]
+    fn single_cluster_has_only_wcss() {
+        let (points, _) = separated();
+        let validity = Validity::new(&points, &[1; 8], &Euclidean);
+
+        assert!(validity.wcss > 0.0);
+        assert_eq!(validity.silhouette, None);
+        assert_eq!(validity.davies_bouldin, None);
+        assert_eq!(validity.calinski_harabasz, None);
+        assert_eq!(validity.dunn, None);
+    }
+}
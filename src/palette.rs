@@ -0,0 +1,243 @@
+use crate::visual::{Color, Lab};
+
+/// Points are only a few pixels wide, so they need more contrast with the
+/// background than large areas do (WCAG ratio, 1 means no contrast at all)
+pub const MIN_BACKGROUND_CONTRAST: f32 = 2.0;
+
+/// Levels per sRGB channel of the grid that distinct colors are picked from
+const CANDIDATE_LEVELS: u8 = 16;
+/// Share of the way to black (or white) a color is moved per contrast step
+const CONTRAST_STEP: f32 = 0.05;
+
+const TABLEAU_10: [u32; 10] = [
+    0x4e79a7, 0xf28e2b, 0xe15759, 0x76b7b2, 0x59a14f, 0xedc948, 0xb07aa1, 0xff9da7, 0x9c755f,
+    0xbab0ac,
+];
+/// Okabe & Ito, "Color Universal Design", black moved to the end
+const OKABE_ITO: [u32; 8] = [
+    0xe69f00, 0x56b4e9, 0x009e73, 0xf0e442, 0x0072b2, 0xd55e00, 0xcc79a7, 0x000000,
+];
+const SET_1: [u32; 9] = [
+    0xe41a1c, 0x377eb8, 0x4daf4a, 0x984ea3, 0xff7f00, 0xffff33, 0xa65628, 0xf781bf, 0x999999,
+];
+const SET_2: [u32; 8] = [
+    0x66c2a5, 0xfc8d62, 0x8da0cb, 0xe78ac3, 0xa6d854, 0xffd92f, 0xe5c494, 0xb3b3b3,
+];
+const SET_3: [u32; 12] = [
+    0x8dd3c7, 0xffffb3, 0xbebada, 0xfb8072, 0x80b1d3, 0xfdb462, 0xb3de69, 0xfccde5, 0xd9d9d9,
+    0xbc80bd, 0xccebc5, 0xffed6f,
+];
+const DARK_2: [u32; 8] = [
+    0x1b9e77, 0xd95f02, 0x7570b3, 0xe7298a, 0x66a61e, 0xe6ab02, 0xa6761d, 0x666666,
+];
+
+/// matplotlib viridis sampled at ninths
+const VIRIDIS: [u32; 10] = [
+    0x440154, 0x482878, 0x3e4989, 0x31688e, 0x26828e, 0x1f9e89, 0x35b779, 0x6ece58, 0xb5de2b,
+    0xfde725,
+];
+/// matplotlib magma sampled at ninths
+const MAGMA: [u32; 10] = [
+    0x000004, 0x180f3d, 0x440f76, 0x721f81, 0x9e2f7f, 0xcd4071, 0xf1605d, 0xfd9668, 0xfeca8d,
+    0xfcfdbf,
+];
+/// ColorBrewer RdBu from blue to red
+const BLUE_RED: [u32; 11] = [
+    0x053061, 0x2166ac, 0x4393c3, 0x92c5de, 0xd1e5f0, 0xf7f7f7, 0xfddbc7, 0xf4a582, 0xd6604d,
+    0xb2182b, 0x67001f,
+];
+
+/// Named qualitative palettes for class colors.
+/// Classes beyond the palette get colors from [`distinct_colors`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Palette {
+    #[default]
+    Tableau10,
+    /// Distinguishable with every common kind of color blindness
+    OkabeIto,
+    /// ColorBrewer Set1
+    Set1,
+    /// ColorBrewer Set2
+    Set2,
+    /// ColorBrewer Set3
+    Set3,
+    /// ColorBrewer Dark2
+    Dark2,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 6] = [
+        Palette::Tableau10,
+        Palette::OkabeIto,
+        Palette::Set1,
+        Palette::Set2,
+        Palette::Set3,
+        Palette::Dark2,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Tableau10 => "tableau10",
+            Palette::OkabeIto => "okabe-ito",
+            Palette::Set1 => "set1",
+            Palette::Set2 => "set2",
+            Palette::Set3 => "set3",
+            Palette::Dark2 => "dark2",
+        }
+    }
+
+    /// Case insensitive, see [`Palette::name`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|palette| palette.name().eq_ignore_ascii_case(name))
+    }
+
+    fn hex(self) -> &'static [u32] {
+        match self {
+            Palette::Tableau10 => &TABLEAU_10,
+            Palette::OkabeIto => &OKABE_ITO,
+            Palette::Set1 => &SET_1,
+            Palette::Set2 => &SET_2,
+            Palette::Set3 => &SET_3,
+            Palette::Dark2 => &DARK_2,
+        }
+    }
+
+    pub fn len(self) -> usize {
+        self.hex().len()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.hex().is_empty()
+    }
+
+    /// Colors as they are listed by the authors
+    pub fn colors(self) -> Vec<Color> {
+        self.hex().iter().map(|&rgb| Color::opaque(rgb)).collect()
+    }
+
+    /// Color of a class, counted from 1, with enough contrast with the background.
+    /// `None` once the palette is exhausted.
+    pub fn class_color(self, class: usize, background: Color) -> Option<Color> {
+        let rgb = *self.hex().get(class.checked_sub(1)?)?;
+        Some(with_contrast(
+            Color::opaque(rgb),
+            background,
+            MIN_BACKGROUND_CONTRAST,
+        ))
+    }
+}
+
+/// `count` colors that are as far as possible (in CIELAB) from each other, from
+/// the `taken` ones and from the background, and contrast with the background.
+/// Picked greedily, so the first colors are the most distinct ones.
+pub fn distinct_colors(count: usize, background: Color, taken: &[Color]) -> Vec<Color> {
+    let step = 255 / (CANDIDATE_LEVELS - 1);
+    let levels = || (0..CANDIDATE_LEVELS).map(move |level| level * step);
+    let mut candidates: Vec<(Color, Lab)> = levels()
+        .flat_map(|r| levels().flat_map(move |g| levels().map(move |b| Color::rgba(r, g, b, 255))))
+        .filter(|color| color.contrast_ratio(background) >= MIN_BACKGROUND_CONTRAST)
+        .map(|color| (color, color.to_lab()))
+        .collect();
+
+    // Distance from every candidate to the closest already used color
+    let mut closest: Vec<f32> = candidates
+        .iter()
+        .map(|(_, candidate)| {
+            taken
+                .iter()
+                .chain([&background])
+                .map(|color| candidate.delta_e(color.to_lab()))
+                .fold(f32::INFINITY, f32::min)
+        })
+        .collect();
+
+    let mut chosen = Vec::with_capacity(count);
+    while chosen.len() < count && !candidates.is_empty() {
+        let best = (0..candidates.len())
+            .max_by(|&a, &b| closest[a].total_cmp(&closest[b]))
+            .unwrap();
+        let (color, picked) = candidates.swap_remove(best);
+        closest.swap_remove(best);
+        for ((_, candidate), distance) in candidates.iter().zip(&mut closest) {
+            *distance = distance.min(candidate.delta_e(picked));
+        }
+        chosen.push(color);
+    }
+    chosen
+}
+
+/// Moves the color towards black (or white on a dark background) until its
+/// contrast ratio with the background reaches `min_contrast`
+pub fn with_contrast(color: Color, background: Color, min_contrast: f32) -> Color {
+    let target = if background.relative_luminance() > 0.5 {
+        Color::rgba(0, 0, 0, 255)
+    } else {
+        Color::rgba(255, 255, 255, 255)
+    };
+
+    let mut share = 0.0;
+    let mut result = color;
+    while result.contrast_ratio(background) < min_contrast && share < 1.0 {
+        share += CONTRAST_STEP;
+        result = color.mix(target, share);
+    }
+    result
+}
+
+/// Continuous colormaps for scalar values such as distances, densities or
+/// membership degrees. Stops are interpolated in CIELAB.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Colormap {
+    /// Dark blue to yellow, lightness grows evenly
+    #[default]
+    Viridis,
+    /// Black to pale yellow through purple and orange
+    Magma,
+    /// Blue to red through white, for values on both sides of a center
+    Diverging,
+}
+
+impl Colormap {
+    fn stops(self) -> &'static [u32] {
+        match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Magma => &MAGMA,
+            Colormap::Diverging => &BLUE_RED,
+        }
+    }
+
+    /// Color at `t` in (0; 1), values outside are clamped and NaN is the start
+    pub fn at(self, t: f32) -> Color {
+        let stops = self.stops();
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let position = t * (stops.len() - 1) as f32;
+        let index = (position.floor() as usize).min(stops.len() - 2);
+
+        let from = Color::opaque(stops[index]).to_lab();
+        let to = Color::opaque(stops[index + 1]).to_lab();
+        Color::from_lab(from.lerp(to, position - index as f32), 1.0)
+    }
+
+    /// Color of the value in the range, for [`Colormap::Diverging`] the middle of
+    /// the range is white. An empty range maps everything to the middle.
+    pub fn map(self, value: f32, min: f32, max: f32) -> Color {
+        if max > min {
+            self.at((value - min) / (max - min))
+        } else {
+            self.at(0.5)
+        }
+    }
+
+    /// `count` evenly spaced colors from the start to the end of the map
+    pub fn sample(self, count: usize) -> Vec<Color> {
+        match count {
+            0 => Vec::new(),
+            1 => vec![self.at(0.5)],
+            _ => (0..count)
+                .map(|i| self.at(i as f32 / (count - 1) as f32))
+                .collect(),
+        }
+    }
+}
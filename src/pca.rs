@@ -0,0 +1,281 @@
+use std::{fmt::Display, path::Path};
+
+use crate::{
+    error::{Error, Result},
+    font::{HorizontalAlign, TextStyle, VerticalAlign},
+    geometry::{FixedPosition, Point},
+    messages::Message,
+    utils::RoundToDecimalPlaces,
+    vector::{Features, Matrix, Projection, Vector, mean},
+    visual::{Color, Image},
+};
+
+/// Longest biplot arrow as a share of the distance from the origin to the nearest edge
+const BIPLOT_REACH: f32 = 0.8;
+const BIPLOT_COLOR: Color = Color::rgba(0xb9, 0x00, 0x00, 200);
+
+/// Principal component analysis: directions of the largest variance of the data,
+/// found by the eigendecomposition of its covariance matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pca {
+    mean: Vector,
+    /// Unit vectors, from the largest variance
+    components: Vec<Vector>,
+    /// Variance along every component
+    variances: Vec<f32>,
+}
+
+impl Pca {
+    pub fn fit<P: Features>(points: &[P]) -> Result<Self> {
+        let mean = mean(points).ok_or(Error::EmptyPointSet)?;
+        let mean = Vector::new(mean.coordinates().as_ref().to_vec());
+        let covariance = Matrix::covariance(points)?;
+
+        let (variances, components) = covariance
+            .symmetric_eigen()
+            .into_iter()
+            .map(|(variance, component)| {
+                // Signs of the eigenvectors are arbitrary, the largest coordinate is made positive
+                let largest = component
+                    .as_slice()
+                    .iter()
+                    .copied()
+                    .max_by(|first, second| first.abs().total_cmp(&second.abs()))
+                    .unwrap_or(1.0);
+                let sign = if largest < 0.0 { -1.0 } else { 1.0 };
+                let component =
+                    Vector::new(component.as_slice().iter().map(|c| c * sign).collect());
+                (variance.max(0.0), component)
+            })
+            .unzip();
+
+        Ok(Self {
+            mean,
+            components,
+            variances,
+        })
+    }
+
+    pub fn components(&self) -> &[Vector] {
+        &self.components
+    }
+
+    pub fn explained_variance(&self) -> &[f32] {
+        &self.variances
+    }
+
+    /// Share of the total variance along every component, they sum up to 1
+    pub fn explained_variance_ratio(&self) -> Vec<f32> {
+        let total: f32 = self.variances.iter().sum();
+        self.variances
+            .iter()
+            .map(|variance| if total > 0.0 { variance / total } else { 0.0 })
+            .collect()
+    }
+
+    /// Coordinates of the point along the first `count` components
+    pub fn transform<P: Features>(&self, point: &P, count: usize) -> Vector {
+        let centered = self.center(point);
+        Vector::new(
+            self.components
+                .iter()
+                .take(count)
+                .map(|component| centered.dot(component))
+                .collect(),
+        )
+    }
+
+    /// Point of the feature space with the given coordinates along the components
+    pub fn inverse_transform(&self, coordinates: &Vector) -> Vector {
+        let mut point = self.mean.as_slice().to_vec();
+        for (component, weight) in self.components.iter().zip(coordinates.as_slice()) {
+            for (coordinate, value) in point.iter_mut().zip(component.as_slice()) {
+                *coordinate += weight * value;
+            }
+        }
+        Vector::new(point)
+    }
+
+    /// Projection onto the first two components, the mean goes to the origin
+    pub fn projection(&self) -> Projection {
+        let dimension = self.mean.dimension();
+        let axis = |index: usize| {
+            self.components
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| Vector::zeros(dimension))
+        };
+        Projection::new(self.mean.clone(), [axis(0), axis(1)])
+            .expect("components have the dimension of the mean")
+    }
+
+    /// Projected points drawn in the colors of their classes (all in the first
+    /// one without labels), the axes show the shares of the variance.
+    pub fn plot<T: AsRef<Path>, P: Features>(
+        &self,
+        path: T,
+        points: &[P],
+        labels: Option<&[usize]>,
+    ) -> Result<Image<T>> {
+        let projected = self.projection().apply_all(points);
+        let mut drawing = Image::fitted(path, &projected, None, true, None, None)?;
+        drawing.set_title(Message::PcaTitle.to_string());
+
+        let ratios = self.explained_variance_ratio();
+        let axis_title = |index: usize| {
+            Message::PrincipalComponentAxis {
+                index: &(index + 1),
+                ratio: &(ratios.get(index).copied().unwrap_or(0.0) * 100.0).round_to_dp(1),
+            }
+            .to_string()
+        };
+        drawing.set_x_axis_title(axis_title(0));
+        drawing.set_y_axis_title(axis_title(1));
+
+        for (index, point) in projected.into_iter().enumerate() {
+            let class = labels.and_then(|labels| labels.get(index)).copied();
+            let _ = drawing.draw_point_with_class(point, class.unwrap_or(1), false);
+        }
+        if let Some(labels) = labels {
+            let mut classes = labels.to_vec();
+            classes.sort_unstable();
+            classes.dedup();
+            for class in classes {
+                drawing.set_class_label(class, Message::ClassLabel { class: &class }.to_string());
+            }
+            drawing.set_legend(Some(FixedPosition::TopRight(-2.0, -2.0)));
+        }
+        Ok(drawing)
+    }
+
+    /// Biplot arrows: every original feature axis projected onto the first two
+    /// components, from the origin. Arrows are scaled together to fit the picture,
+    /// features are named `x1`, `x2`... without names.
+    pub fn draw_biplot<T: AsRef<Path>>(&self, drawing: &mut Image<T>, names: Option<&[String]>) {
+        let dimension = self.mean.dimension();
+        let loading = |component: usize, feature: usize| {
+            self.components
+                .get(component)
+                .map_or(0.0, |component| component[feature])
+        };
+        let ends: Vec<Point> = (0..dimension)
+            .map(|feature| Point::new(loading(0, feature), loading(1, feature)))
+            .collect();
+
+        let rect = drawing.rect();
+        let room = [
+            -rect.bottom_left.x,
+            rect.top_right.x,
+            -rect.bottom_left.y,
+            rect.top_right.y,
+        ]
+        .into_iter()
+        .fold(f32::INFINITY, f32::min)
+        .max(0.0);
+        let longest = ends
+            .iter()
+            .map(|end| end.x.hypot(end.y))
+            .fold(0.0, f32::max);
+        if longest == 0.0 || room == 0.0 {
+            return;
+        }
+        let scale = BIPLOT_REACH * room / longest;
+
+        let origin = Point::new(0.0, 0.0);
+        for (feature, end) in ends.into_iter().enumerate() {
+            let end = Point::new(end.x * scale, end.y * scale);
+            drawing.draw_arrow(origin, end, Some(BIPLOT_COLOR));
+
+            // Names lie beyond the heads, on the side the arrow points to
+            let style = TextStyle {
+                horizontal_align: if end.x < 0.0 {
+                    HorizontalAlign::Right
+                } else {
+                    HorizontalAlign::Left
+                },
+                vertical_align: if end.y < 0.0 {
+                    VerticalAlign::Top
+                } else {
+                    VerticalAlign::Bottom
+                },
+                ..drawing.text_style()
+            };
+            let name = match names.and_then(|names| names.get(feature)) {
+                Some(name) => name.clone(),
+                None => Message::FeatureLabel {
+                    index: &(feature + 1),
+                }
+                .to_string(),
+            };
+            drawing.write_styled(end, name, Some(BIPLOT_COLOR), &style);
+        }
+    }
+
+    fn center<P: Features>(&self, point: &P) -> Vector {
+        Vector::new(
+            point
+                .coordinates()
+                .as_ref()
+                .iter()
+                .zip(self.mean.as_slice())
+                .map(|(coordinate, mean)| coordinate - mean)
+                .collect(),
+        )
+    }
+}
+
+impl Display for Pca {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Message::PcaHeader)?;
+        let mut cumulative = 0.0;
+        for (index, ratio) in (1..).zip(self.explained_variance_ratio()) {
+            cumulative += ratio;
+            write!(
+                f,
+                "\n{}",
+                Message::PrincipalComponent {
+                    index: &index,
+                    ratio: &(ratio * 100.0).round_to_dp(1),
+                    cumulative: &(cumulative * 100.0).round_to_dp(1)
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pca::Pca, vector::Vector};
+
+    fn points() -> Vec<Vector> {
+        (0..50)
+            .map(|i| {
+                let t = i as f32;
+                Vector::new(vec![t, 2.0 * t + (t * 0.7).sin(), (t * 1.3).cos(), 5.0])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn explained_variance_sums_to_one() {
+        let pca = Pca::fit(&points()).unwrap();
+        let ratios = pca.explained_variance_ratio();
+
+        assert_eq!(ratios.len(), 4);
+        assert!((ratios.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        assert!(ratios.windows(2).all(|pair| pair[0] >= pair[1]));
+        // The points lie close to a line
+        assert!(ratios[0] > 0.99);
+    }
+
+    #[test]
+    fn all_components_restore_the_point() {
+        let points = points();
+        let pca = Pca::fit(&points).unwrap();
+        for point in &points {
+            let restored = pca.inverse_transform(&pca.transform(point, 4));
+            assert!(restored.distance_to(point) < 1e-3);
+        }
+    }
+}
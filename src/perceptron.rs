@@ -0,0 +1,175 @@
+use crate::{clustering::Observer, geometry::Point};
+
+const DEFAULT_LEARNING_RATE: f32 = 0.01;
+const DEFAULT_MAX_EPOCHS: usize = 50;
+
+/// Linear classifier of points on the plane. One function
+/// `f(x, y) = w_0 + w_1 * x + w_2 * y` splits them into two classes by its
+/// sign (1 where it is not negative, 2 elsewhere), several functions give the
+/// class of the largest of them. Classes are counted from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Perceptron {
+    /// (w_0; w_1; w_2) of every function
+    pub weights: Vec<(f32, f32, f32)>,
+    /// Misclassified points after every epoch of the training
+    pub errors: Vec<usize>,
+}
+
+impl Perceptron {
+    pub fn new(weights: Vec<(f32, f32, f32)>) -> Self {
+        Self {
+            weights,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn predict(&self, point: Point) -> usize {
+        let value = |(w_0, w_1, w_2): &(f32, f32, f32)| w_0 + w_1 * point.x + w_2 * point.y;
+        match self.weights.as_slice() {
+            [function] => {
+                if value(function) >= 0.0 {
+                    1
+                } else {
+                    2
+                }
+            }
+            functions => (1..)
+                .zip(functions)
+                .map(|(class, function)| (class, value(function)))
+                .max_by(|first, second| first.1.total_cmp(&second.1))
+                .map_or(1, |(class, _)| class),
+        }
+    }
+
+    /// Perceptron rule from the current weights: every misclassified point
+    /// moves the function of its class towards itself and, with several
+    /// functions, the chosen one away from it. Stops after an epoch without
+    /// errors or `max_epochs` (50) of them and keeps the weights of the epoch
+    /// with the fewest errors, as the data may not be linearly separable.
+    /// The free coefficient is learned as the weight of an input as large as
+    /// the points, so that it moves as fast as the others.
+    /// `on_epoch` gets the perceptron after every pass over the points.
+    pub fn train(
+        &mut self,
+        points: &[Point],
+        truth: &[usize],
+        learning_rate: Option<f32>,
+        max_epochs: Option<usize>,
+        mut on_epoch: Option<Observer<Self>>,
+    ) {
+        let rate = learning_rate.unwrap_or(DEFAULT_LEARNING_RATE);
+        let max_epochs = max_epochs.unwrap_or(DEFAULT_MAX_EPOCHS);
+        let scale = points
+            .iter()
+            .map(|point| point.x.abs().max(point.y.abs()))
+            .fold(1.0, f32::max);
+        let step = |weights: &mut (f32, f32, f32), point: Point, sign: f32| {
+            weights.0 += sign * rate * scale * scale;
+            weights.1 += sign * rate * point.x;
+            weights.2 += sign * rate * point.y;
+        };
+
+        let mut best = (self.count_errors(points, truth), self.weights.clone());
+        for _ in 0..max_epochs {
+            for (&point, &class) in points.iter().zip(truth) {
+                let predicted = self.predict(point);
+                if predicted == class {
+                    continue;
+                }
+                if let [function] = self.weights.as_mut_slice() {
+                    let sign = if class == 1 { 1.0 } else { -1.0 };
+                    step(function, point, sign);
+                } else {
+                    step(&mut self.weights[class - 1], point, 1.0);
+                    step(&mut self.weights[predicted - 1], point, -1.0);
+                }
+            }
+
+            let errors = self.count_errors(points, truth);
+            self.errors.push(errors);
+            if let Some(on_epoch) = on_epoch.as_mut() {
+                on_epoch(self);
+            }
+            if errors < best.0 {
+                best = (errors, self.weights.clone());
+            }
+            if errors == 0 {
+                break;
+            }
+        }
+        self.weights = best.1;
+    }
+
+    fn count_errors(&self, points: &[Point], truth: &[usize]) -> usize {
+        points
+            .iter()
+            .zip(truth)
+            .filter(|&(&point, &class)| self.predict(point) != class)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{geometry::Point, perceptron::Perceptron};
+
+    fn grid() -> Vec<Point> {
+        (0..400)
+            .map(|i| Point::new((i % 20) as f32 * 10.0 - 95.0, (i / 20) as f32 * 10.0 - 95.0))
+            .collect()
+    }
+
+    #[test]
+    fn two_classes_are_separated() {
+        let points = grid();
+        let truth: Vec<usize> = points
+            .iter()
+            .map(|point| {
+                if 20.0 + point.x - 2.0 * point.y >= 0.0 {
+                    1
+                } else {
+                    2
+                }
+            })
+            .collect();
+
+        // The opposite of the true function misclassifies every point at first
+        let mut perceptron = Perceptron::new(vec![(-20.0, -1.0, 2.0)]);
+        let mut epochs = 0;
+        perceptron.train(
+            &points,
+            &truth,
+            None,
+            Some(1000),
+            Some(&mut |_| epochs += 1),
+        );
+
+        assert_eq!(perceptron.errors.last(), Some(&0));
+        assert_eq!(epochs, perceptron.errors.len());
+        for (&point, &class) in points.iter().zip(&truth) {
+            assert_eq!(perceptron.predict(point), class);
+        }
+    }
+
+    #[test]
+    fn several_classes_get_fewer_errors() {
+        let points = grid();
+        // Left, right bottom and right top parts of the field
+        let truth: Vec<usize> = points
+            .iter()
+            .map(|point| match (point.x < 0.0, point.y < 0.0) {
+                (true, _) => 1,
+                (false, true) => 2,
+                (false, false) => 3,
+            })
+            .collect();
+
+        let mut perceptron = Perceptron::new(vec![(0.0, 0.0, 0.0); 3]);
+        let before = perceptron.count_errors(&points, &truth);
+        perceptron.train(&points, &truth, None, Some(200), None);
+
+        let after = perceptron.count_errors(&points, &truth);
+        assert!(after < before / 10, "{} errors after {}", after, before);
+        assert_eq!(Some(&after), perceptron.errors.iter().min());
+    }
+}
@@ -0,0 +1,270 @@
+use crate::{
+    error::{Error, Result},
+    messages::Message,
+    vector::{Features, Matrix, mean},
+};
+
+/// Variances below this are taken as a constant feature, which is left unscaled
+const MIN_VARIANCE: f32 = 1e-12;
+
+/// Ways to bring the features to comparable scales
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scaling {
+    /// Every feature to (0; 1)
+    MinMax,
+    /// Every feature to zero mean and unit standard deviation
+    #[default]
+    ZScore,
+    /// Median to 0 and the interquartile range to 1, outliers barely affect it
+    Robust,
+    /// Decorrelated features of unit variance (PCA whitening), the covariance
+    /// becomes the identity
+    Whitening,
+}
+
+impl Scaling {
+    pub const ALL: [Scaling; 4] = [
+        Scaling::MinMax,
+        Scaling::ZScore,
+        Scaling::Robust,
+        Scaling::Whitening,
+    ];
+
+    pub fn name(&self) -> Message<'static> {
+        match self {
+            Scaling::MinMax => Message::MinMaxScaling,
+            Scaling::ZScore => Message::ZScoreScaling,
+            Scaling::Robust => Message::RobustScaling,
+            Scaling::Whitening => Message::WhiteningScaling,
+        }
+    }
+}
+
+/// Scaling fitted to a data set. Every scaling is an affine map
+/// `x' = A * (x - center)`, so transformed results (e.g. centroids) can be mapped
+/// back into the original coordinates for drawing and reporting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scaler {
+    scaling: Scaling,
+    center: Vec<f32>,
+    forward: Matrix,
+    backward: Matrix,
+}
+
+impl Scaler {
+    pub fn fit<P: Features>(scaling: Scaling, points: &[P]) -> Result<Self> {
+        let dimension = points.first().ok_or(Error::EmptyPointSet)?.dimension();
+        let feature = |axis: usize| -> Vec<f32> {
+            points
+                .iter()
+                .map(|point| {
+                    point
+                        .coordinates()
+                        .as_ref()
+                        .get(axis)
+                        .copied()
+                        .unwrap_or(0.0)
+                })
+                .collect()
+        };
+
+        let (center, scales): (Vec<f32>, Vec<f32>) = match scaling {
+            Scaling::MinMax => (0..dimension)
+                .map(|axis| {
+                    let values = feature(axis);
+                    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+                    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                    (min, max - min)
+                })
+                .unzip(),
+            Scaling::ZScore => {
+                let mean = mean(points).ok_or(Error::EmptyPointSet)?;
+                let mean = mean.coordinates().as_ref().to_vec();
+                let covariance = Matrix::covariance(points)?;
+                let deviations = (0..dimension)
+                    .map(|axis| covariance[(axis, axis)].max(0.0).sqrt())
+                    .collect();
+                (mean, deviations)
+            }
+            Scaling::Robust => (0..dimension)
+                .map(|axis| {
+                    let mut values = feature(axis);
+                    values.sort_by(f32::total_cmp);
+                    let median = quantile(&values, 0.5);
+                    (median, quantile(&values, 0.75) - quantile(&values, 0.25))
+                })
+                .unzip(),
+            Scaling::Whitening => return Self::whitening(points),
+        };
+
+        let mut forward = Matrix::identity(dimension);
+        let mut backward = Matrix::identity(dimension);
+        for (axis, scale) in scales.into_iter().enumerate() {
+            if scale.abs() > MIN_VARIANCE.sqrt() {
+                forward[(axis, axis)] = 1.0 / scale;
+                backward[(axis, axis)] = scale;
+            }
+        }
+        Ok(Self {
+            scaling,
+            center,
+            forward,
+            backward,
+        })
+    }
+
+    /// Rows of the forward map are the principal axes divided by their standard deviations
+    fn whitening<P: Features>(points: &[P]) -> Result<Self> {
+        let mean = mean(points).ok_or(Error::EmptyPointSet)?;
+        let center = mean.coordinates().as_ref().to_vec();
+        let dimension = center.len();
+        let mut forward = Matrix::zeros(dimension);
+        let mut backward = Matrix::zeros(dimension);
+
+        for (row, (variance, axis)) in Matrix::covariance(points)?
+            .symmetric_eigen()
+            .into_iter()
+            .enumerate()
+        {
+            let deviation = if variance > MIN_VARIANCE {
+                variance.sqrt()
+            } else {
+                1.0
+            };
+            for column in 0..dimension {
+                forward[(row, column)] = axis[column] / deviation;
+                backward[(column, row)] = axis[column] * deviation;
+            }
+        }
+        Ok(Self {
+            scaling: Scaling::Whitening,
+            center,
+            forward,
+            backward,
+        })
+    }
+
+    pub fn scaling(&self) -> Scaling {
+        self.scaling
+    }
+
+    pub fn transform<P: Features>(&self, point: &P) -> P {
+        let shifted: Vec<f32> = point
+            .coordinates()
+            .as_ref()
+            .iter()
+            .zip(&self.center)
+            .map(|(coordinate, center)| coordinate - center)
+            .collect();
+        P::from_coordinates(&self.forward.multiply_vector(&shifted))
+    }
+
+    pub fn transform_all<P: Features>(&self, points: &[P]) -> Vec<P> {
+        points.iter().map(|point| self.transform(point)).collect()
+    }
+
+    /// Point of the original coordinates that transforms into the given one
+    pub fn inverse_transform<P: Features>(&self, point: &P) -> P {
+        let coordinates: Vec<f32> = self
+            .backward
+            .multiply_vector(point.coordinates().as_ref())
+            .iter()
+            .zip(&self.center)
+            .map(|(coordinate, center)| coordinate + center)
+            .collect();
+        P::from_coordinates(&coordinates)
+    }
+
+    pub fn inverse_transform_all<P: Features>(&self, points: &[P]) -> Vec<P> {
+        points
+            .iter()
+            .map(|point| self.inverse_transform(point))
+            .collect()
+    }
+}
+
+/// Quantile of sorted values with linear interpolation between the neighbours
+fn quantile(sorted: &[f32], share: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let position = share.clamp(0.0, 1.0) * (sorted.len() - 1) as f32;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        preprocessing::{Scaler, Scaling},
+        vector::{Features, Matrix, Vector},
+    };
+
+    fn points() -> Vec<Vector> {
+        (0..40)
+            .map(|i| {
+                let t = i as f32;
+                Vector::new(vec![
+                    t * 100.0,
+                    (t * 0.9).sin() + t * 0.05,
+                    3.0 + (t * 1.7).cos() * 0.01,
+                ])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn inverse_transform_restores_the_points() {
+        let points = points();
+        for scaling in Scaling::ALL {
+            let scaler = Scaler::fit(scaling, &points).unwrap();
+            for point in &points {
+                let restored = scaler.inverse_transform(&scaler.transform(point));
+                assert!(
+                    restored.distance_to(point) < 1e-2,
+                    "{:?}: {} != {}",
+                    scaling,
+                    restored,
+                    point
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scaled_features_are_comparable() {
+        let points = points();
+        let feature = |points: &[Vector], axis: usize| -> Vec<f32> {
+            points.iter().map(|point| point[axis]).collect()
+        };
+
+        let min_max = Scaler::fit(Scaling::MinMax, &points)
+            .unwrap()
+            .transform_all(&points);
+        for axis in 0..3 {
+            let values = feature(&min_max, axis);
+            let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+            let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            assert!(min.abs() < 1e-5 && (max - 1.0).abs() < 1e-5);
+        }
+
+        let z_scores = Scaler::fit(Scaling::ZScore, &points)
+            .unwrap()
+            .transform_all(&points);
+        let covariance = Matrix::covariance(&z_scores).unwrap();
+        for axis in 0..z_scores[0].dimension() {
+            assert!((covariance[(axis, axis)] - 1.0).abs() < 1e-3);
+        }
+
+        let whitened = Scaler::fit(Scaling::Whitening, &points)
+            .unwrap()
+            .transform_all(&points);
+        let covariance = Matrix::covariance(&whitened).unwrap();
+        for row in 0..3 {
+            for column in 0..3 {
+                let expected = if row == column { 1.0 } else { 0.0 };
+                assert!((covariance[(row, column)] - expected).abs() < 1e-2);
+            }
+        }
+    }
+}
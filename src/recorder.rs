@@ -0,0 +1,324 @@
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use image::{
+    Delay, Frame, Rgba, RgbaImage,
+    codecs::gif::{GifEncoder, Repeat},
+    imageops::overlay,
+};
+
+use crate::{
+    error::{Error, Result},
+    messages::Message,
+    visual::Image,
+};
+
+const DEFAULT_FRAME_DELAY_MS: u32 = 200;
+/// Frames kept in memory, long runs keep every second, fourth, ... iteration
+const DEFAULT_MAX_FRAMES: usize = 64;
+
+/// How the captured frames are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// Looped animated GIF
+    Gif,
+    /// `name_001.png`, `name_002.png`, ... next to the given path
+    PngSequence,
+}
+
+/// Collects snapshots of an [`Image`] after each iteration of an algorithm and
+/// writes them as an animation. Once there are more than the maximum of frames,
+/// every second one is dropped and only every second capture is kept from then
+/// on; the latest capture is always shown last.
+#[derive(Clone)]
+pub struct FrameRecorder<T: AsRef<Path>> {
+    path: T,
+    format: RecordingFormat,
+    delay_ms: u32,
+    max_frames: usize,
+    frames: Vec<RgbaImage>,
+    /// Latest capture if it was not kept in the frames
+    latest: Option<RgbaImage>,
+    captures: usize,
+    /// Captures per kept frame
+    stride: usize,
+}
+
+impl<T: AsRef<Path>> FrameRecorder<T> {
+    /// The format is chosen by the extension: `.gif` gives an animation, anything
+    /// else gives a numbered PNG sequence.
+    pub fn new(path: T, delay_ms: Option<u32>) -> Self {
+        let is_gif = path
+            .as_ref()
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+        let format = if is_gif {
+            RecordingFormat::Gif
+        } else {
+            RecordingFormat::PngSequence
+        };
+
+        Self {
+            path,
+            format,
+            delay_ms: delay_ms.unwrap_or(DEFAULT_FRAME_DELAY_MS),
+            max_frames: DEFAULT_MAX_FRAMES,
+            frames: Vec::new(),
+            latest: None,
+            captures: 0,
+            stride: 1,
+        }
+    }
+
+    pub fn format(&self) -> RecordingFormat {
+        self.format
+    }
+
+    /// Frames that will be written
+    pub fn frames_count(&self) -> usize {
+        self.frames.len() + usize::from(self.latest.is_some())
+    }
+
+    /// Time each frame is shown in the animation
+    pub fn set_delay(&mut self, delay_ms: u32) {
+        self.delay_ms = delay_ms;
+    }
+
+    /// At least 2, 64 by default
+    pub fn set_max_frames(&mut self, max_frames: usize) {
+        self.max_frames = max_frames.max(2);
+    }
+
+    /// Fails for pictures without pixels, e.g. SVG ones
+    pub fn capture<K: AsRef<Path>>(&mut self, image: &Image<K>) -> Result<()> {
+        let frame = image
+            .snapshot()
+            .ok_or_else(|| Error::NotRasterImage(image.path().to_path_buf()))?;
+
+        if self.captures.is_multiple_of(self.stride) {
+            self.frames.push(frame);
+            self.latest = None;
+            if self.frames.len() > self.max_frames {
+                let mut index = 0;
+                self.frames.retain(|_| {
+                    index += 1;
+                    index % 2 == 1
+                });
+                self.stride *= 2;
+            }
+        } else {
+            self.latest = Some(frame);
+        }
+        self.captures += 1;
+        Ok(())
+    }
+
+    /// Fails if nothing was captured, so that a missing animation is never taken for a saved one
+    pub fn save(&self) -> Result<()> {
+        if self.frames_count() == 0 {
+            return Err(Error::NoFrames(self.path.as_ref().to_path_buf()));
+        }
+
+        let result = match self.format {
+            RecordingFormat::Gif => self.save_gif(),
+            RecordingFormat::PngSequence => self.save_png_sequence(),
+        };
+        result.map_err(|err| Error::ImageIo {
+            path: self.path.as_ref().to_path_buf(),
+            source: std::io::Error::other(err),
+        })?;
+
+        println!(
+            "{}",
+            Message::AnimationSaved {
+                count: &self.frames_count(),
+                path: &self.path.as_ref().to_string_lossy()
+            }
+        );
+        Ok(())
+    }
+
+    fn save_gif(&self) -> image::ImageResult<()> {
+        let file = BufWriter::new(File::create(self.path.as_ref())?);
+        let mut encoder = GifEncoder::new(file);
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        let (width, height) = self.canvas_size();
+        let delay = Delay::from_numer_denom_ms(self.delay_ms, 1);
+        for frame in self.frames() {
+            let frame = fit_to_canvas(frame, width, height);
+            encoder.encode_frame(Frame::from_parts(frame, 0, 0, delay))?;
+        }
+        Ok(())
+    }
+
+    fn save_png_sequence(&self) -> image::ImageResult<()> {
+        let (width, height) = self.canvas_size();
+        for (index, frame) in (1..).zip(self.frames()) {
+            fit_to_canvas(frame, width, height).save(self.frame_path(index))?;
+        }
+        Ok(())
+    }
+
+    /// `dir/name.png` -> `dir/name_007.png`
+    fn frame_path(&self, index: usize) -> PathBuf {
+        let path = self.path.as_ref();
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let digits = self.frames_count().to_string().len().max(3);
+        path.with_file_name(format!("{}_{:0digits$}.png", stem, index))
+    }
+
+    fn frames(&self) -> impl Iterator<Item = &RgbaImage> {
+        self.frames.iter().chain(&self.latest)
+    }
+
+    /// Frames may grow when the layout changes, all of them are padded to the largest one
+    fn canvas_size(&self) -> (u32, u32) {
+        self.frames().fold((0, 0), |(width, height), frame| {
+            (width.max(frame.width()), height.max(frame.height()))
+        })
+    }
+}
+
+fn fit_to_canvas(frame: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+    if frame.width() == width && frame.height() == height {
+        return frame.clone();
+    }
+    let mut canvas = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 0]));
+    overlay(&mut canvas, frame, 0, 0);
+    canvas
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, fs::File, io::BufReader, path::PathBuf};
+
+    use image::{AnimationDecoder, Rgba, RgbaImage, codecs::gif::GifDecoder};
+
+    use crate::{
+        error::Error,
+        geometry::{Point, Rectangle},
+        recorder::{FrameRecorder, RecordingFormat, fit_to_canvas},
+        visual::Image,
+    };
+
+    /// Empty directory of the test in the temporary one
+    fn directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("ai_k_mean_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// Small picture with a point, wider pictures for larger `width`
+    fn image(width: u32, point: Point) -> Image<&'static str> {
+        let mut image = Image::new(
+            "frame.png",
+            Rectangle::default(),
+            true,
+            Some(width),
+            Some(30),
+        );
+        image
+            .draw_point_with_color(point, crate::visual::Color::rgba(0, 0, 0, 255), false)
+            .unwrap();
+        image
+    }
+
+    #[test]
+    fn gif_has_every_frame_at_the_largest_size() {
+        let directory = directory("gif");
+        let path = directory.join("animation.gif");
+        let mut recorder = FrameRecorder::new(&path, Some(50));
+        assert_eq!(recorder.format(), RecordingFormat::Gif);
+
+        let images = [40, 60, 50].map(|width| image(width, Point::new(0.0, 0.0)));
+        for image in &images {
+            recorder.capture(image).unwrap();
+        }
+        recorder.save().unwrap();
+
+        let width = images
+            .iter()
+            .map(|image| image.snapshot().unwrap().width())
+            .max()
+            .unwrap();
+        let decoder = GifDecoder::new(BufReader::new(File::open(&path).unwrap())).unwrap();
+        let frames = decoder.into_frames().collect_frames().unwrap();
+        assert_eq!(frames.len(), 3);
+        for frame in &frames {
+            assert_eq!(frame.buffer().width(), width);
+            assert_eq!(frame.delay().numer_denom_ms(), (50, 1));
+        }
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn png_sequence_is_numbered() {
+        let directory = directory("png");
+        let mut recorder = FrameRecorder::new(directory.join("steps.png"), None);
+        assert_eq!(recorder.format(), RecordingFormat::PngSequence);
+
+        for x in [-50.0, 0.0, 50.0] {
+            recorder.capture(&image(40, Point::new(x, 0.0))).unwrap();
+        }
+        recorder.save().unwrap();
+
+        let mut names: Vec<String> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["steps_001.png", "steps_002.png", "steps_003.png"]);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn frames_are_padded_to_the_canvas() {
+        let frame = RgbaImage::from_pixel(2, 3, Rgba([255, 0, 0, 255]));
+        let canvas = fit_to_canvas(&frame, 4, 5);
+
+        assert_eq!(canvas.dimensions(), (4, 5));
+        assert_eq!(canvas.get_pixel(1, 2), &Rgba([255, 0, 0, 255]));
+        assert_eq!(canvas.get_pixel(3, 4), &Rgba([0, 0, 0, 0]));
+        assert_eq!(fit_to_canvas(&frame, 2, 3), frame);
+    }
+
+    #[test]
+    fn long_runs_are_thinned_out_to_the_latest_capture() {
+        let mut recorder = FrameRecorder::new("thinned.gif", None);
+        recorder.set_max_frames(4);
+        let images: Vec<Image<&str>> = (0..10)
+            .map(|step| image(40, Point::new(step as f32 * 10.0 - 50.0, 0.0)))
+            .collect();
+        for image in &images {
+            recorder.capture(image).unwrap();
+        }
+
+        assert!(recorder.frames_count() <= 5);
+        let frames: Vec<&RgbaImage> = recorder.frames().collect();
+        assert_eq!(frames[0], &images[0].snapshot().unwrap());
+        assert_eq!(frames.last().copied(), images[9].snapshot().as_ref());
+    }
+
+    #[test]
+    fn nothing_to_record_is_an_error() {
+        let mut recorder = FrameRecorder::new("empty.gif", None);
+        assert!(matches!(recorder.save(), Err(Error::NoFrames(_))));
+
+        let vector = Image::new("frame.svg", Rectangle::default(), true, None, None);
+        assert!(matches!(
+            recorder.capture(&vector),
+            Err(Error::NotRasterImage(path)) if path.ends_with("frame.svg")
+        ));
+        assert_eq!(recorder.frames_count(), 0);
+        assert!(matches!(recorder.save(), Err(Error::NoFrames(_))));
+    }
+}
@@ -1,5 +1,159 @@
 pub mod classification;
+pub mod features_clustering;
+pub mod fuzzy_clustering;
+pub mod gaussian_mixture;
 pub mod hierarchy_grouping;
 pub mod k_mean;
 pub mod n_classes_functions;
+pub mod streaming_k_mean;
 pub mod two_classes_function;
+
+use std::io::stdin;
+
+use crate::{
+    distance::{
+        Chebyshev, Cosine, Euclidean, Mahalanobis, Manhattan, Metric, Minkowski, SquaredEuclidean,
+    },
+    error::{Error, Result},
+    geometry::{Point, Rectangle},
+    messages::Message,
+    utils::rand_isize_in_range,
+};
+
+const MINKOWSKI_ORDER: f32 = 3.0;
+
+const MIN_TRUE_CLASSES_COUNT: usize = 2;
+const MAX_TRUE_CLASSES_COUNT: usize = 6;
+
+/// Random number of groups the points of a task come in, the groups are the
+/// true classes the found clusters are compared with
+pub fn rand_true_classes_count() -> usize {
+    rand_isize_in_range(
+        MIN_TRUE_CLASSES_COUNT as isize,
+        MAX_TRUE_CLASSES_COUNT as isize,
+    ) as usize
+}
+
+/// `count` points in the field in [`rand_true_classes_count`] groups and the
+/// true class of every point
+pub fn rand_labeled_points(boundary: &Rectangle, count: usize) -> (Vec<Point>, Vec<usize>) {
+    boundary
+        .create_rand_clusters(count, rand_true_classes_count(), None)
+        .into_iter()
+        .unzip()
+}
+
+/// Every metric the tasks offer, Euclidean first. Mahalanobis follows the
+/// covariance of the points and is left out when it can not be fitted to them.
+pub fn available_metrics(points: &[Point]) -> Vec<Box<dyn Metric>> {
+    let mut metrics: Vec<Box<dyn Metric>> = vec![
+        Box::new(Euclidean),
+        Box::new(SquaredEuclidean),
+        Box::new(Manhattan),
+        Box::new(Chebyshev),
+        Box::new(Minkowski::new(MINKOWSKI_ORDER).unwrap()),
+        Box::new(Cosine),
+    ];
+    if let Ok(mahalanobis) = Mahalanobis::fit(points) {
+        metrics.push(Box::new(mahalanobis));
+    }
+    metrics
+}
+
+/// Asks the question, anything but yes is no
+pub fn yes_no_dialogue(question: Message) -> Result<bool> {
+    let mut buf = String::new();
+
+    println!(
+        "{}",
+        Message::YesNoPrompt {
+            question: &question
+        }
+    );
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
+    let answer = buf.trim().to_lowercase();
+
+    Ok(answer.starts_with('y') || answer.starts_with('д'))
+}
+
+/// Asks for one of the [`available_metrics`], Euclidean by default
+pub fn metric_dialogue(points: &[Point]) -> Result<Box<dyn Metric>> {
+    let mut metrics = available_metrics(points);
+    let mut buf = String::new();
+
+    println!("{}", Message::MetricPrompt { default: &1 });
+    for (index, metric) in (1..).zip(&metrics) {
+        println!(
+            "{}",
+            Message::MetricOption {
+                index: &index,
+                metric: &metric.name()
+            }
+        );
+    }
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
+    let index = match buf.trim().parse::<usize>() {
+        Ok(index) if (1..=metrics.len()).contains(&index) => index - 1,
+        _ => 0,
+    };
+
+    let metric = metrics.swap_remove(index);
+    println!(
+        "{}\n",
+        Message::MetricChosen {
+            metric: &metric.name()
+        }
+    );
+    Ok(metric)
+}
+
+/// Asks for the number of points, `default` for an empty or zero answer
+pub fn points_count_dialogue(default: usize) -> Result<usize> {
+    let mut buf = String::new();
+
+    println!("{}", Message::PointsCountPrompt { default: &default });
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
+    let mut points_count = buf.trim().parse::<usize>().unwrap_or(default);
+    if points_count == 0 {
+        points_count = default;
+    }
+    println!(
+        "{}",
+        Message::PointsCountChosen {
+            count: &points_count
+        }
+    );
+
+    Ok(points_count)
+}
+
+/// Asks for the number of classes to split the points into, `default` for an
+/// empty or zero answer
+pub fn classes_count_dialogue(default: usize) -> Result<usize> {
+    let mut buf = String::new();
+
+    println!("{}", Message::ClassesCountPrompt { default: &default });
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
+    let mut classes_count = buf.trim().parse::<usize>().unwrap_or(default);
+    if classes_count == 0 {
+        classes_count = default;
+    }
+    println!(
+        "{}",
+        Message::ClassesCountChosen {
+            count: &classes_count
+        }
+    );
+
+    Ok(classes_count)
+}
+
+/// [`points_count_dialogue`] and then [`classes_count_dialogue`]
+pub fn points_and_classes_dialogue(
+    default_points_count: usize,
+    default_classes_count: usize,
+) -> Result<(usize, usize)> {
+    let points_count = points_count_dialogue(default_points_count)?;
+    let classes_count = classes_count_dialogue(default_classes_count)?;
+    Ok((points_count, classes_count))
+}
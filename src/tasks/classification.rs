@@ -1,102 +1,218 @@
 use std::{collections::BTreeMap, io::stdin};
 
 use crate::{
-    geometry::{Point, Rectangle},
+    clustering::ModelSelection,
+    error::{Error, Result},
+    geometry::{FixedPosition, Point, Rectangle},
+    messages::Message,
+    metrics::{Agreement, Validity},
+    tasks::{
+        available_metrics, metric_dialogue, points_count_dialogue, rand_labeled_points,
+        yes_no_dialogue,
+    },
     utils::RoundToDecimalPlaces,
-    visual::Image,
+    visual::{Image, silhouette::silhouette_plot},
 };
 
 const DEFAULT_POINTS_COUNT: usize = 1000;
 const DEFAULT_CORES_COUNT: usize = 10;
+/// Largest number of cores the model selection tries
+const MAX_SUGGESTED_CORES_COUNT: usize = 10;
 
-pub fn execute() {
-    let (points_count, cores_count) = dialogue();
+pub fn execute() -> Result<()> {
+    let points_count = points_count_dialogue(DEFAULT_POINTS_COUNT)?;
 
     let boundary = Rectangle::default();
-    let mut drawing = Image::new(
+    // Points come in groups, the groups are the true classes
+    let (points, true_labels) = rand_labeled_points(&boundary, points_count);
+    let metric = metric_dialogue(&points)?;
+
+    // Every candidate count is clustered and scored over all the points, so it is optional
+    let suggested = if yes_no_dialogue(Message::ModelSelectionPrompt)? {
+        let selection = ModelSelection::new(
+            &points,
+            MAX_SUGGESTED_CORES_COUNT,
+            metric.as_ref(),
+            Some((boundary.bottom_left, boundary.top_right)),
+            None,
+        )?;
+        println!("{}", selection);
+        let mut selection_plot =
+            selection.plot("/home/vlad0s/Изображения/Misc/labs/classification_k.png")?;
+        selection_plot.save()?;
+        selection.suggested()
+    } else {
+        DEFAULT_CORES_COUNT
+    };
+
+    let cores_count = cores_dialogue(suggested, points_count)?;
+    let compare_metrics = yes_no_dialogue(Message::RegionsPrompt)?;
+    let cores: Vec<Point> = (0..cores_count)
+        .map(|_| boundary.create_rand_point())
+        .collect();
+
+    let mut drawing = Image::fitted(
         "/home/vlad0s/Изображения/Misc/labs/classification.png",
-        boundary.clone(),
+        &[cores.as_slice(), points.as_slice()].concat(),
+        None,
         true,
         None,
         None,
-    );
+    )?;
+    drawing.set_title(Message::ClassificationTitle.to_string());
+    drawing.set_legend(Some(FixedPosition::TopRight(-2.0, -2.0)));
 
-    println!("Границы: {} \nЯдра:", boundary);
+    println!(
+        "{} \n{}",
+        Message::Boundaries { rect: &boundary },
+        Message::Cores
+    );
 
-    let mut classes: BTreeMap<Point, Vec<Point>> = BTreeMap::new();
+    let mut classes: BTreeMap<Point, Vec<(Point, usize)>> = BTreeMap::new();
 
-    for _ in 0..cores_count {
-        let core = boundary.create_rand_point();
+    for core in cores {
         classes.insert(core, Vec::new());
     }
 
-    for _ in 0..points_count {
-        let point = boundary.create_rand_point();
+    for (&point, &true_class) in points.iter().zip(&true_labels) {
         let mut min_distance = f32::MAX;
         let mut class: Point = Point::new(f32::MAX, f32::MAX);
         for i in &mut classes {
-            let distance = point.distance_to(*i.0);
+            let distance = metric.between(&point, i.0);
             if distance < min_distance {
                 class = *i.0;
                 min_distance = distance;
             }
         }
-        classes.get_mut(&class).unwrap().push(point);
+        classes.get_mut(&class).unwrap().push((point, true_class));
+    }
+
+    let numbered_cores: Vec<(Point, usize)> = classes.keys().copied().zip(1..).collect();
+    drawing.draw_regions(&numbered_cores, metric.as_ref());
+    if compare_metrics {
+        save_regions(&numbered_cores, &points, &boundary)?;
     }
 
-    let mut class_num: usize = 1;
-    for class in &classes {
+    let mut clustered_points = Vec::with_capacity(points_count);
+    let mut labels = Vec::with_capacity(points_count);
+    let mut truth = Vec::with_capacity(points_count);
+    let mut not_drawn = 0;
+    for (class_num, class) in (1..).zip(&classes) {
         println!("---------------------------------------");
-        println!("{} класс: {}", class_num, class.0);
-        let mut point_num = 1;
-        drawing.draw_point_with_class(*class.0, class_num, true, false);
-        for point in class.1 {
-            drawing.draw_point_with_class(*point, class_num, false, false);
+        println!(
+            "{}",
+            Message::ClassificationClass {
+                class: &class_num,
+                core: class.0
+            }
+        );
+        drawing.set_class_label(
+            class_num,
+            Message::ClassLabel { class: &class_num }.to_string(),
+        );
+        if let Err(error) = drawing.draw_point_with_class(*class.0, class_num, true) {
+            eprintln!("{}", Message::Warning { warning: &error });
+        }
+        for (point_num, (point, true_class)) in (1..).zip(class.1) {
+            if drawing
+                .draw_point_with_class(*point, class_num, false)
+                .is_err()
+            {
+                not_drawn += 1;
+            }
+            clustered_points.push(*point);
+            labels.push(class_num);
+            truth.push(*true_class);
             println!(
-                "{}: {} | Расстояние до центра класса: {}",
-                point_num,
-                point,
-                point.distance_to(*class.0).round_to_dp(2)
+                "{}",
+                Message::ClassificationPoint {
+                    index: &point_num,
+                    point,
+                    distance: &metric.between(point, class.0).round_to_dp(2)
+                }
             );
-            point_num += 1;
         }
-        class_num += 1;
     }
 
-    drawing.save();
-    drawing.show("gimp");
-}
-
-fn dialogue() -> (usize, usize) {
-    let mut buf = String::new();
-
+    println!("---------------------------------------");
     println!(
-        "Введите количество точек (По умолчанию: {}).",
-        DEFAULT_POINTS_COUNT
+        "{}",
+        Message::PointsNotDrawn {
+            count: &not_drawn,
+            total: &clustered_points.len()
+        }
     );
-    stdin()
-        .read_line(&mut buf)
-        .expect("Не удалось прочитать из стандартного ввода.");
-    let mut points_count = buf.trim().parse::<usize>().unwrap_or(DEFAULT_POINTS_COUNT);
-    if points_count == 0 {
-        points_count = DEFAULT_POINTS_COUNT;
+    println!(
+        "{}",
+        Validity::new(&clustered_points, &labels, metric.as_ref())
+    );
+    println!("{}", Agreement::new(&truth, &labels));
+    let mut silhouettes = silhouette_plot(
+        "/home/vlad0s/Изображения/Misc/labs/classification_silhouette.png",
+        &clustered_points,
+        &labels,
+        metric.as_ref(),
+    )?;
+
+    drawing.save()?;
+    silhouettes.save()?;
+    if let Err(error) = drawing.show("gimp") {
+        eprintln!("{}", Message::Error { error: &error });
     }
-    println!("Выбранное количество точек: {}", points_count);
-    buf.clear();
+
+    Ok(())
+}
+
+/// Regions of the same cores under every metric, one picture per metric
+fn save_regions(cores: &[(Point, usize)], points: &[Point], boundary: &Rectangle) -> Result<()> {
+    for (index, metric) in (1..).zip(available_metrics(points)) {
+        let mut drawing = Image::new(
+            format!(
+                "/home/vlad0s/Изображения/Misc/labs/classification_regions_{}.png",
+                index
+            ),
+            boundary.clone(),
+            true,
+            None,
+            None,
+        );
+        drawing.set_title(
+            Message::RegionsTitle {
+                metric: &metric.name(),
+            }
+            .to_string(),
+        );
+        drawing.draw_regions(cores, metric.as_ref());
+        for &(core, class) in cores {
+            let _ = drawing.draw_point_with_class(core, class, true);
+        }
+        drawing.save()?;
+    }
+    Ok(())
+}
+
+/// `suggested` is the default, the count found by the model selection if it was made
+fn cores_dialogue(suggested: usize, points_count: usize) -> Result<usize> {
+    let mut buf = String::new();
 
     println!(
-        "Введите количество ядер (По умолчанию: {}).",
-        DEFAULT_CORES_COUNT
+        "{}",
+        Message::CoresCountPrompt {
+            default: &suggested
+        }
     );
-    stdin()
-        .read_line(&mut buf)
-        .expect("Не удалось прочитать из стандартного ввода.");
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
 
-    let mut cores_count = buf.trim().parse::<usize>().unwrap_or(DEFAULT_CORES_COUNT);
+    let mut cores_count = buf.trim().parse::<usize>().unwrap_or(suggested);
     if cores_count == 0 || cores_count >= points_count {
-        cores_count = DEFAULT_CORES_COUNT;
+        cores_count = suggested;
     }
-    println!("Выбранное количество ядер: {}\n", cores_count);
+    println!(
+        "{}\n",
+        Message::CoresCountChosen {
+            count: &cores_count
+        }
+    );
 
-    (points_count, cores_count)
+    Ok(cores_count)
 }
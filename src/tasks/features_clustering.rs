@@ -0,0 +1,166 @@
+use std::io::stdin;
+
+use crate::{
+    clustering::{Clustering, k_means},
+    distance::Euclidean,
+    error::{Error, Result},
+    messages::Message,
+    metrics::Agreement,
+    pca::Pca,
+    preprocessing::{Scaler, Scaling},
+    recorder::FrameRecorder,
+    tasks::{points_and_classes_dialogue, rand_true_classes_count},
+    utils::{rand_f32_in_range, rand_normal},
+    vector::Vector,
+};
+
+const PLOT_PATH: &str = "/home/vlad0s/Изображения/Misc/labs/features_clustering.png";
+
+const DEFAULT_POINTS_COUNT: usize = 1000;
+const DEFAULT_CLUSTERS_COUNT: usize = 3;
+
+/// Every feature is measured in its own units, like the columns of a real data set
+const FEATURE_SCALES: [f32; 5] = [1.0, 1000.0, 0.01, 10.0, 100.0];
+/// Coordinates of the centers of the groups before the scaling
+const MAX_CENTER_COORDINATE: f32 = 5.0;
+/// Standard deviation of a group before the scaling
+const SPREAD: f32 = 1.0;
+
+pub fn execute() -> Result<()> {
+    let (points_count, clusters_count) =
+        points_and_classes_dialogue(DEFAULT_POINTS_COUNT, DEFAULT_CLUSTERS_COUNT)?;
+
+    // Points come in groups, the groups are the true classes
+    let true_classes_count = rand_true_classes_count();
+    let (points, true_labels) = create_rand_vectors(points_count, true_classes_count);
+    println!(
+        "{}",
+        Message::FeatureScales {
+            dimension: &FEATURE_SCALES.len(),
+            scales: &Vector::new(FEATURE_SCALES.to_vec())
+        }
+    );
+
+    // Without scaling the feature of the largest units decides every distance
+    let scaler = scaling_dialogue()?
+        .map(|scaling| Scaler::fit(scaling, &points))
+        .transpose()?;
+    let points = match &scaler {
+        Some(scaler) => scaler.transform_all(&points),
+        None => points,
+    };
+
+    // The points can only be drawn on the plane of their two main directions,
+    // every move of the centroids is a frame there
+    let pca = Pca::fit(&points)?;
+    let mut recorder = FrameRecorder::new(
+        "/home/vlad0s/Изображения/Misc/labs/features_clustering.gif",
+        None,
+    );
+    let mut recording = Ok(());
+    let mut record = |clustering: &Clustering<Vector>| {
+        if recording.is_ok() {
+            recording = pca
+                .plot(PLOT_PATH, &points, Some(&clustering.labels))
+                .and_then(|frame| recorder.capture(&frame));
+        }
+    };
+    let clustering = k_means(
+        &points,
+        clusters_count,
+        &Euclidean,
+        None,
+        None,
+        Some(&mut record),
+    );
+    recording?;
+    // Centroids are reported in the units of the features
+    let centroids = match &scaler {
+        Some(scaler) => scaler.inverse_transform_all(&clustering.centroids),
+        None => clustering.centroids.clone(),
+    };
+    for (class, centroid) in (1..).zip(&centroids) {
+        println!(
+            "{}",
+            Message::FeatureCentroid {
+                class: &class,
+                centroid
+            }
+        );
+    }
+    println!("{}", Agreement::new(&true_labels, &clustering.labels));
+
+    println!("{}", pca);
+    let mut drawing = pca.plot(PLOT_PATH, &points, Some(&clustering.labels))?;
+    pca.draw_biplot(&mut drawing, None);
+
+    drawing.save()?;
+    recorder.save()?;
+    if let Err(error) = drawing.show("gimp") {
+        eprintln!("{}", Message::Error { error: &error });
+    }
+
+    Ok(())
+}
+
+/// `count` points split evenly between `classes` normally distributed groups,
+/// every feature multiplied by its scale, with their true classes (from 1)
+fn create_rand_vectors(count: usize, classes: usize) -> (Vec<Vector>, Vec<usize>) {
+    let centers: Vec<Vec<f32>> = (0..classes)
+        .map(|_| {
+            FEATURE_SCALES
+                .iter()
+                .map(|_| rand_f32_in_range(-MAX_CENTER_COORDINATE, MAX_CENTER_COORDINATE, 2))
+                .collect()
+        })
+        .collect();
+
+    (0..count)
+        .map(|i| {
+            let coordinates = centers[i % classes]
+                .iter()
+                .zip(FEATURE_SCALES)
+                .map(|(center, scale)| rand_normal(*center, SPREAD) * scale)
+                .collect();
+            (Vector::new(coordinates), i % classes + 1)
+        })
+        .unzip()
+}
+
+/// Scalings are numbered from 1, 0 is no scaling. Z-scores by default.
+fn scaling_dialogue() -> Result<Option<Scaling>> {
+    let mut buf = String::new();
+    let default = Scaling::ALL
+        .iter()
+        .position(|&scaling| scaling == Scaling::default())
+        .unwrap_or(0)
+        + 1;
+
+    println!("{}", Message::ScalingPrompt { default: &default });
+    println!(
+        "{}",
+        Message::MetricOption {
+            index: &0,
+            metric: &Message::NoScaling
+        }
+    );
+    for (index, scaling) in (1..).zip(Scaling::ALL) {
+        println!(
+            "{}",
+            Message::MetricOption {
+                index: &index,
+                metric: &scaling.name()
+            }
+        );
+    }
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
+    let scaling = match buf.trim().parse::<usize>() {
+        Ok(0) => None,
+        Ok(index) if index <= Scaling::ALL.len() => Some(Scaling::ALL[index - 1]),
+        _ => Some(Scaling::ALL[default - 1]),
+    };
+
+    let name = scaling.map_or(Message::NoScaling, |scaling| scaling.name());
+    println!("{}\n", Message::ScalingChosen { scaling: &name });
+    Ok(scaling)
+}
@@ -0,0 +1,80 @@
+use crate::{
+    clustering::{FuzzyClustering, fuzzy_c_means},
+    error::Result,
+    geometry::{FixedPosition, Rectangle},
+    messages::Message,
+    metrics::Agreement,
+    recorder::FrameRecorder,
+    tasks::{metric_dialogue, points_and_classes_dialogue, rand_labeled_points},
+    visual::Image,
+};
+
+const DEFAULT_POINTS_COUNT: usize = 1000;
+const DEFAULT_CLUSTERS_COUNT: usize = 3;
+const DEFAULT_FUZZIFIER: f32 = 2.0;
+
+pub fn execute() -> Result<()> {
+    let (points_count, clusters_count) =
+        points_and_classes_dialogue(DEFAULT_POINTS_COUNT, DEFAULT_CLUSTERS_COUNT)?;
+
+    let boundary = Rectangle::default();
+    // Points come in groups, the groups are the true classes
+    let (points, true_labels) = rand_labeled_points(&boundary, points_count);
+    let metric = metric_dialogue(&points)?;
+
+    let mut drawing = Image::fitted(
+        "/home/vlad0s/Изображения/Misc/labs/fuzzy_clustering.png",
+        &points,
+        None,
+        true,
+        None,
+        None,
+    )?;
+    drawing.set_title(
+        Message::FuzzyTitle {
+            fuzzifier: &DEFAULT_FUZZIFIER,
+        }
+        .to_string(),
+    );
+    for class in 1..=clusters_count.min(points.len()) {
+        drawing.set_class_label(class, Message::ClassLabel { class: &class }.to_string());
+    }
+    drawing.set_legend(Some(FixedPosition::TopRight(-2.0, -2.0)));
+
+    // Every update of the memberships is a frame
+    let mut recorder = FrameRecorder::new(
+        "/home/vlad0s/Изображения/Misc/labs/fuzzy_clustering.gif",
+        None,
+    );
+    let mut recording = Ok(());
+    let mut record = |fuzzy: &FuzzyClustering| {
+        let mut frame = drawing.clone();
+        fuzzy.draw(&mut frame, &points);
+        if recording.is_ok() {
+            recording = recorder.capture(&frame);
+        }
+    };
+
+    // Overlapping clusters show up as points of blended colors
+    let fuzzy = fuzzy_c_means(
+        &points,
+        clusters_count,
+        Some(DEFAULT_FUZZIFIER),
+        metric.as_ref(),
+        None,
+        None,
+        Some(&mut record),
+    )?;
+    recording?;
+    println!("{}", fuzzy);
+    println!("{}", Agreement::new(&true_labels, &fuzzy.labels()));
+    fuzzy.draw(&mut drawing, &points);
+
+    drawing.save()?;
+    recorder.save()?;
+    if let Err(error) = drawing.show("gimp") {
+        eprintln!("{}", Message::Error { error: &error });
+    }
+
+    Ok(())
+}
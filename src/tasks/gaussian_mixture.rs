@@ -0,0 +1,156 @@
+use std::io::stdin;
+
+use crate::{
+    clustering::{CovarianceType, GaussianMixture},
+    error::{Error, Result},
+    geometry::{FixedPosition, Point, Rectangle},
+    messages::Message,
+    metrics::Agreement,
+    recorder::FrameRecorder,
+    tasks::{classes_count_dialogue, points_count_dialogue, rand_labeled_points, yes_no_dialogue},
+    utils::RoundToDecimalPlaces,
+    visual::Image,
+};
+
+const DEFAULT_POINTS_COUNT: usize = 1000;
+const DEFAULT_COMPONENTS_COUNT: usize = 3;
+/// Largest number of components the sweep over BIC and AIC tries
+const MAX_SWEEP_COMPONENTS_COUNT: usize = 8;
+
+pub fn execute() -> Result<()> {
+    let points_count = points_count_dialogue(DEFAULT_POINTS_COUNT)?;
+
+    let boundary = Rectangle::default();
+    // Points come in groups, the groups are the true classes
+    let (points, true_labels) = rand_labeled_points(&boundary, points_count);
+    let covariance_type = covariance_dialogue()?;
+
+    // A mixture is fitted for every number of components, so it is optional
+    let suggested = if yes_no_dialogue(Message::GmmSweepPrompt)? {
+        let mixtures =
+            GaussianMixture::sweep(&points, MAX_SWEEP_COMPONENTS_COUNT, Some(covariance_type))?;
+        println!("{}", Message::GmmSweepHeader);
+        for mixture in &mixtures {
+            println!(
+                "{}",
+                Message::GmmSweepRow {
+                    k: &mixture.components.len(),
+                    log_likelihood: &mixture.log_likelihood().round_to_dp(3),
+                    bic: &mixture.bic().round_to_dp(3),
+                    aic: &mixture.aic().round_to_dp(3)
+                }
+            );
+        }
+        let best = |criterion: fn(&GaussianMixture) -> f64| {
+            mixtures
+                .iter()
+                .min_by(|first, second| criterion(first).total_cmp(&criterion(second)))
+                .map_or(DEFAULT_COMPONENTS_COUNT, |mixture| mixture.components.len())
+        };
+        let (by_bic, by_aic) = (best(GaussianMixture::bic), best(GaussianMixture::aic));
+        println!(
+            "{}",
+            Message::GmmSweepBest {
+                bic: &by_bic,
+                aic: &by_aic
+            }
+        );
+        by_bic
+    } else {
+        DEFAULT_COMPONENTS_COUNT
+    };
+    let components_count = classes_count_dialogue(suggested)?;
+
+    let mut drawing = Image::fitted(
+        "/home/vlad0s/Изображения/Misc/labs/gaussian_mixture.png",
+        &points,
+        None,
+        true,
+        None,
+        None,
+    )?;
+    drawing.set_title(Message::GmmTitle.to_string());
+    for class in 1..=components_count.min(points.len()) {
+        drawing.set_class_label(class, Message::ClassLabel { class: &class }.to_string());
+    }
+    drawing.set_legend(Some(FixedPosition::TopRight(-2.0, -2.0)));
+
+    // Every EM step is a frame with the points in their most probable components
+    let mut recorder = FrameRecorder::new(
+        "/home/vlad0s/Изображения/Misc/labs/gaussian_mixture.gif",
+        None,
+    );
+    let mut recording = Ok(());
+    let mut record = |mixture: &GaussianMixture| {
+        let mut frame = drawing.clone();
+        draw_mixture(&mut frame, &points, mixture);
+        if recording.is_ok() {
+            recording = recorder.capture(&frame);
+        }
+    };
+
+    // Components of any shape, where the cores of k-means only give equal round ones
+    let mixture = GaussianMixture::fit(
+        &points,
+        components_count,
+        Some(covariance_type),
+        None,
+        None,
+        Some(&mut record),
+    )?;
+    recording?;
+    println!("{}", mixture);
+    println!("{}", Agreement::new(&true_labels, &mixture.labels(&points)));
+    draw_mixture(&mut drawing, &points, &mixture);
+
+    drawing.save()?;
+    recorder.save()?;
+    if let Err(error) = drawing.show("gimp") {
+        eprintln!("{}", Message::Error { error: &error });
+    }
+
+    Ok(())
+}
+
+fn draw_mixture(drawing: &mut Image<&str>, points: &[Point], mixture: &GaussianMixture) {
+    for (point, label) in points.iter().zip(mixture.labels(points)) {
+        let _ = drawing.draw_point_with_class(*point, label, false);
+    }
+    mixture.draw_ellipses(drawing);
+}
+
+/// Asks for one of the [`CovarianceType`]s, full by default
+fn covariance_dialogue() -> Result<CovarianceType> {
+    let mut buf = String::new();
+    let default = CovarianceType::ALL
+        .iter()
+        .position(|&covariance_type| covariance_type == CovarianceType::default())
+        .unwrap_or(0)
+        + 1;
+
+    println!("{}", Message::CovariancePrompt { default: &default });
+    for (index, covariance_type) in (1..).zip(CovarianceType::ALL) {
+        println!(
+            "{}",
+            Message::MetricOption {
+                index: &index,
+                metric: &covariance_type.name()
+            }
+        );
+    }
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
+    let covariance_type = match buf.trim().parse::<usize>() {
+        Ok(index) if (1..=CovarianceType::ALL.len()).contains(&index) => {
+            CovarianceType::ALL[index - 1]
+        }
+        _ => CovarianceType::ALL[default - 1],
+    };
+
+    println!(
+        "{}\n",
+        Message::CovarianceChosen {
+            covariance: &covariance_type.name()
+        }
+    );
+    Ok(covariance_type)
+}
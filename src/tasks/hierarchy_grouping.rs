@@ -5,14 +5,24 @@ use std::{
 };
 
 use crate::{
-    geometry::{Axis, Point, Rectangle},
-    utils::{rand_f32_in_range, rand_f32_in_range_with_distance},
+    clustering::{DistanceMatrix, Observer, pam},
+    error::{Error, Result},
+    font::{HorizontalAlign, TextStyle, VerticalAlign},
+    geometry::{Axis, Point},
+    messages::Message,
+    metrics::Agreement,
+    recorder::FrameRecorder,
+    utils::{RoundToDecimalPlaces, rand_f32_in_range_with_distance},
     visual::Image,
 };
 
 type ElementId = usize;
+/// Ids of the merged objects and the distance between them
+type Merge = (ElementId, ElementId, f32);
 
 const DEFAULT_ELEMENTS_COUNT: usize = 5;
+/// Groups the hierarchy is cut into and the elements are split into around medoids
+const DEFAULT_GROUPS_COUNT: usize = 2;
 
 const MIN_POINTS_DISTANCE: f32 = 0.5;
 const DISTANCE_BETWEEN_VALUES: f32 = 0.5;
@@ -34,7 +44,7 @@ impl Display for Hierarchy {
                 write!(f, "{}   ", id)?;
             }
         }
-        write!(f, "\n")?;
+        writeln!(f)?;
         for (id, object) in &self.objects {
             if id / 10 < 1 {
                 write!(f, "{}  ", id)?;
@@ -48,7 +58,7 @@ impl Display for Hierarchy {
                     write!(f, "{}  ", distance)?;
                 }
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
@@ -70,7 +80,7 @@ impl Hierarchy {
     }
 
     fn init_rand_distances(&mut self) {
-        let keys: Vec<ElementId> = self.objects.keys().map(|id_ref| *id_ref).collect();
+        let keys: Vec<ElementId> = self.objects.keys().copied().collect();
         let mut cached_distances: HashMap<(ElementId, ElementId), f32> = HashMap::new();
 
         for i in 1..=self.objects.len() {
@@ -79,7 +89,54 @@ impl Hierarchy {
         }
     }
 
-    fn assemble(&mut self) {
+    /// Distances between the objects in the order of their ids
+    fn distance_matrix(&self) -> Result<DistanceMatrix> {
+        let rows: Vec<Vec<f32>> = self
+            .objects
+            .values()
+            .map(|object| object.distances.values().copied().collect())
+            .collect();
+        DistanceMatrix::from_rows(&rows)
+    }
+
+    /// Groups of element ids left after all but the last `count - 1` merges,
+    /// numbered by their smallest element. The hierarchy must be assembled.
+    fn cut(&self, count: usize) -> Vec<Vec<ElementId>> {
+        let mut groups: Vec<&HierarchyObject> = self.objects.values().collect();
+        // Nodes get their ids in the order of the merges, the latest is undone first
+        while groups.len() < count {
+            let Some((position, pair)) = groups
+                .iter()
+                .enumerate()
+                .filter_map(|(position, object)| match &object.inner {
+                    InnerHierarchyObject::Node(pair) => Some((position, object.id, pair)),
+                    InnerHierarchyObject::Leaf => None,
+                })
+                .max_by_key(|(_, id, _)| *id)
+                .map(|(position, _, pair)| (position, pair))
+            else {
+                break;
+            };
+            let (first, second) = (&pair.0, &pair.1);
+            groups[position] = first;
+            groups.push(second);
+        }
+
+        let mut groups: Vec<Vec<ElementId>> = groups
+            .into_iter()
+            .map(|object| {
+                let mut leaves = object.leaves();
+                leaves.sort();
+                leaves
+            })
+            .collect();
+        groups.sort();
+        groups
+    }
+
+    /// Single linkage: merges the two closest objects until one is left.
+    /// `on_merge` gets every new node right after it is made.
+    fn assemble(&mut self, mut on_merge: Option<Observer<HierarchyObject>>) {
         while self.objects.len() > 1 {
             let mut min_distance = f32::MAX;
             let mut current_pair: (ElementId, ElementId) = (0, 0);
@@ -105,6 +162,9 @@ impl Hierarchy {
                 object.distances.remove(&current_pair.1);
             }
             new_node.calculate_all_distances(&mut self.objects);
+            if let Some(on_merge) = on_merge.as_mut() {
+                on_merge(&new_node);
+            }
 
             self.objects.insert(self.element_count, new_node);
         }
@@ -177,25 +237,63 @@ impl HierarchyObject {
         }
     }
 
+    fn leaves(&self) -> Vec<ElementId> {
+        match &self.inner {
+            InnerHierarchyObject::Node(pair) => {
+                let mut leaves = pair.0.leaves();
+                leaves.extend(pair.1.leaves());
+                leaves
+            }
+            InnerHierarchyObject::Leaf => vec![self.id],
+        }
+    }
+
+    /// Position of every object in the dendrogram: the leaves lie in a row at
+    /// height 0 in the order of the tree, every node above the middle of its
+    /// members at the distance between them
+    fn dendrogram(&self) -> BTreeMap<ElementId, Point> {
+        let mut positions: BTreeMap<ElementId, Point> = (1..)
+            .zip(self.leaves())
+            .map(|(x, id)| (id, Point::new(x as f32, 0.0)))
+            .collect();
+        self.place(&mut positions);
+        positions
+    }
+
+    fn place(&self, positions: &mut BTreeMap<ElementId, Point>) -> Point {
+        match &self.inner {
+            InnerHierarchyObject::Node(pair) => {
+                let first = pair.0.place(positions);
+                let second = pair.1.place(positions);
+                let position = Point::new((first.x + second.x) / 2.0, pair.2);
+                positions.insert(self.id, position);
+                position
+            }
+            InnerHierarchyObject::Leaf => positions[&self.id],
+        }
+    }
+
     fn tree_display(&self) {
         match &self.inner {
             InnerHierarchyObject::Node(node) => {
                 let first_member_prefix = match node.0.inner {
-                    InnerHierarchyObject::Node(_) => "УЗЕЛ",
-                    InnerHierarchyObject::Leaf => "ЛИСТ",
+                    InnerHierarchyObject::Node(_) => Message::HierarchyNode,
+                    InnerHierarchyObject::Leaf => Message::HierarchyLeaf,
                 };
                 let second_member_prefix = match node.1.inner {
-                    InnerHierarchyObject::Node(_) => "УЗЕЛ",
-                    InnerHierarchyObject::Leaf => "ЛИСТ",
+                    InnerHierarchyObject::Node(_) => Message::HierarchyNode,
+                    InnerHierarchyObject::Leaf => Message::HierarchyLeaf,
                 };
                 println!(
-                    "УЗЕЛ: {} - Расстояние: {}; Составляющие: {} {} - {} {}",
-                    self.id,
-                    node.2,
-                    first_member_prefix,
-                    node.0.id,
-                    second_member_prefix,
-                    node.1.id
+                    "{}",
+                    Message::HierarchyNodeEntry {
+                        id: &self.id,
+                        distance: &node.2,
+                        first_kind: &first_member_prefix,
+                        first: &node.0.id,
+                        second_kind: &second_member_prefix,
+                        second: &node.1.id
+                    }
                 );
                 node.0.tree_display();
                 node.1.tree_display();
@@ -205,60 +303,160 @@ impl HierarchyObject {
     }
 }
 
+/// Merge as a bracket from the members up to the distance between them
+fn draw_merge(
+    drawing: &mut Image<&str>,
+    positions: &BTreeMap<ElementId, Point>,
+    (first, second, distance): Merge,
+) {
+    let (first, second) = (positions[&first], positions[&second]);
+    drawing.draw_polyline(
+        &[
+            first,
+            Point::new(first.x, distance),
+            Point::new(second.x, distance),
+            second,
+        ],
+        None,
+    );
+}
+
 #[derive(Clone, Debug)]
 enum InnerHierarchyObject {
     Node(Box<(HierarchyObject, HierarchyObject, f32)>),
     Leaf,
 }
 
-pub fn execute() {
-    let elements_count = dialogue();
+pub fn execute() -> Result<()> {
+    let (elements_count, groups_count) = dialogue()?;
+
+    let mut hierarchy = Hierarchy::new();
+    hierarchy.populate(elements_count);
+    hierarchy.init_rand_distances();
+
+    println!("{}", Message::InitialDistances);
+    println!("{}", hierarchy);
+
+    let medoids = pam(&hierarchy.distance_matrix()?, groups_count, None)?;
+    println!("{}", medoids);
 
-    let boundary = Rectangle::new(Point::new(-10.0, -10.0), Point::new(190.0, 190.0));
-    let mut drawing = Image::new(
+    let mut merges: Vec<Merge> = Vec::new();
+    hierarchy.assemble(Some(&mut |node| {
+        if let InnerHierarchyObject::Node(pair) = &node.inner {
+            merges.push((pair.0.id, pair.1.id, pair.2));
+        }
+    }));
+
+    println!("{}", Message::ResultingHierarchy);
+    let root = hierarchy.objects.values().next().unwrap();
+    root.tree_display();
+
+    // The picture is fitted to the dendrogram, the distance axis lies at zero
+    let positions = root.dendrogram();
+    let mut drawing = Image::fitted(
         "/home/vlad0s/Изображения/Misc/labs/hierarchy_grouping.png",
-        boundary.clone(),
+        &positions
+            .values()
+            .copied()
+            .chain([Point::new(0.0, 0.0)])
+            .collect::<Vec<_>>(),
+        None,
         true,
         None,
         None,
-    );
-
+    )?;
+    drawing.set_title(Message::HierarchyTitle.to_string());
+    drawing.set_x_axis_title(Message::HierarchyElementsAxis.to_string());
+    drawing.set_y_axis_title(Message::MergeDistanceAxis.to_string());
     drawing.draw_axis(Axis::X, None, None);
     drawing.draw_axis(Axis::Y, None, None);
+    println!(
+        "\n{}",
+        Message::Boundaries {
+            rect: drawing.rect()
+        }
+    );
 
-    println!("Границы: {} \n\n:", boundary);
-
-    let mut hierarchy = Hierarchy::new();
-    hierarchy.populate(elements_count);
-    hierarchy.init_rand_distances();
-
-    println!("Исходные расстояния:");
-    println!("{}", hierarchy);
-
-    hierarchy.assemble();
+    let label_style = TextStyle {
+        horizontal_align: HorizontalAlign::Center,
+        vertical_align: VerticalAlign::Top,
+        offset: (0, -3),
+        ..drawing.text_style()
+    };
+    for id in root.leaves() {
+        drawing.write_styled(positions[&id], id.to_string(), None, &label_style);
+    }
+    // The dendrogram grows by a merge a frame
+    let mut recorder = FrameRecorder::new(
+        "/home/vlad0s/Изображения/Misc/labs/hierarchy_grouping.gif",
+        None,
+    );
+    recorder.capture(&drawing)?;
+    for merge in merges {
+        draw_merge(&mut drawing, &positions, merge);
+        recorder.capture(&drawing)?;
+    }
 
-    println!("Получившаяся иерархия: ");
-    hierarchy
-        .objects
-        .first_entry()
-        .unwrap()
-        .get()
-        .tree_display();
+    // Both split the elements by the same distances, single linkage by the
+    // closest pairs and PAM by the sums to the medoids
+    let groups = hierarchy.cut(groups_count);
+    println!(
+        "\n{}",
+        Message::HierarchyCut {
+            count: &groups.len()
+        }
+    );
+    let mut cut_labels = vec![0; elements_count];
+    for (class, group) in (1..).zip(&groups) {
+        for id in group {
+            cut_labels[id - 1] = class;
+        }
+        let elements = group
+            .iter()
+            .map(ElementId::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "{}",
+            Message::HierarchyCutGroup {
+                class: &class,
+                elements: &elements
+            }
+        );
+    }
+    let agreement = Agreement::new(&cut_labels, &medoids.labels);
+    println!("{}", Message::MedoidsAgreementHeader);
+    println!(
+        "{}",
+        Message::AdjustedRandIndex {
+            value: &agreement.adjusted_rand_index.round_to_dp(3)
+        }
+    );
+    println!(
+        "{}",
+        Message::MutualInformation {
+            normalized: &agreement.normalized_mutual_information.round_to_dp(3),
+            adjusted: &agreement.adjusted_mutual_information.round_to_dp(3)
+        }
+    );
 
-    drawing.save();
+    drawing.save()?;
+    recorder.save()?;
     //drawing.show("gimp");
+
+    Ok(())
 }
 
-fn dialogue() -> usize {
+fn dialogue() -> Result<(usize, usize)> {
     let mut buf = String::new();
 
     println!(
-        "Введите количество элементов (По умолчанию: {})",
-        DEFAULT_ELEMENTS_COUNT
+        "{}",
+        Message::ElementsCountPrompt {
+            default: &DEFAULT_ELEMENTS_COUNT
+        }
     );
-    stdin()
-        .read_line(&mut buf)
-        .expect("Не удалось прочитать из стандартного ввода.");
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
     let mut count = buf
         .trim()
         .parse::<usize>()
@@ -266,6 +464,94 @@ fn dialogue() -> usize {
     if count == 0 {
         count = DEFAULT_ELEMENTS_COUNT;
     }
+    buf.clear();
+
+    let default_groups_count = DEFAULT_GROUPS_COUNT.min(count);
+    println!(
+        "{}",
+        Message::ClassesCountPrompt {
+            default: &default_groups_count
+        }
+    );
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
+    let mut groups_count = buf.trim().parse::<usize>().unwrap_or(default_groups_count);
+    if groups_count == 0 {
+        groups_count = default_groups_count;
+    }
+    // Neither the hierarchy nor the medoids split the elements into more groups
+    groups_count = groups_count.min(count);
+    println!(
+        "{}",
+        Message::ClassesCountChosen {
+            count: &groups_count
+        }
+    );
+
+    Ok((count, groups_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tasks::hierarchy_grouping::{Hierarchy, HierarchyObject};
+
+    /// Hierarchy of the elements from 1 with the given distances between them
+    fn hierarchy(rows: &[[f32; 5]]) -> Hierarchy {
+        let mut hierarchy = Hierarchy::new();
+        hierarchy.populate(rows.len());
+        for (id, row) in (1..).zip(rows) {
+            let object: &mut HierarchyObject = hierarchy.objects.get_mut(&id).unwrap();
+            object.distances = (1..).zip(row.iter().copied()).collect();
+        }
+        hierarchy
+    }
 
-    count
+    #[test]
+    fn cut_undoes_the_latest_merges() {
+        // Merges: 6 = (1, 2), 7 = (6, 5), 8 = (3, 4), 9 = (7, 8). Node 7 has
+        // a child of a larger id than any child of node 8, but was made earlier.
+        let mut hierarchy = hierarchy(&[
+            [0.0, 1.0, 5.0, 5.0, 2.0],
+            [1.0, 0.0, 5.0, 5.0, 2.5],
+            [5.0, 5.0, 0.0, 3.0, 5.0],
+            [5.0, 5.0, 3.0, 0.0, 5.0],
+            [2.0, 2.5, 5.0, 5.0, 0.0],
+        ]);
+        hierarchy.assemble(None);
+
+        assert_eq!(hierarchy.cut(1), vec![vec![1, 2, 3, 4, 5]]);
+        assert_eq!(hierarchy.cut(2), vec![vec![1, 2, 5], vec![3, 4]]);
+        assert_eq!(hierarchy.cut(3), vec![vec![1, 2, 5], vec![3], vec![4]]);
+        assert_eq!(
+            hierarchy.cut(4),
+            vec![vec![1, 2], vec![3], vec![4], vec![5]]
+        );
+        assert_eq!(hierarchy.cut(10).len(), 5);
+    }
+
+    #[test]
+    fn every_merge_is_observed_in_order() {
+        let mut hierarchy = hierarchy(&[
+            [0.0, 1.0, 5.0, 5.0, 2.0],
+            [1.0, 0.0, 5.0, 5.0, 2.5],
+            [5.0, 5.0, 0.0, 3.0, 5.0],
+            [5.0, 5.0, 3.0, 0.0, 5.0],
+            [2.0, 2.5, 5.0, 5.0, 0.0],
+        ]);
+        let mut merges = Vec::new();
+        hierarchy.assemble(Some(&mut |node: &HierarchyObject| {
+            let mut leaves = node.leaves();
+            leaves.sort();
+            merges.push((node.id, leaves));
+        }));
+
+        assert_eq!(
+            merges,
+            vec![
+                (6, vec![1, 2]),
+                (7, vec![1, 2, 5]),
+                (8, vec![3, 4]),
+                (9, vec![1, 2, 3, 4, 5]),
+            ]
+        );
+    }
 }
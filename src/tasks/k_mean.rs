@@ -1,80 +1,137 @@
 use std::io::stdin;
 
 use crate::{
-    geometry::{Point, Rectangle},
+    clustering::clara,
+    error::{Error, Result},
+    geometry::{FixedPosition, Point, Rectangle},
+    messages::Message,
+    metrics::internal::wcss,
+    tasks::metric_dialogue,
     utils::RoundToDecimalPlaces,
     visual::Image,
 };
 
 const DEFAULT_POINT_AMOUNT: usize = 1000;
 
-pub fn execute() {
-    let point_amount = dialogue();
+pub fn execute() -> Result<()> {
+    let point_amount = dialogue()?;
 
     let boundary = Rectangle::default();
+    let points: Vec<Point> = (1..point_amount)
+        .map(|_| boundary.create_rand_point())
+        .collect();
+    let metric = metric_dialogue(&points)?;
 
-    let mut drawing = Image::new(
+    let mut drawing = Image::fitted(
         "/home/vlad0s/Изображения/Misc/labs/k_mean.png",
-        boundary.clone(),
+        &points,
+        None,
         true,
         None,
         None,
-    );
+    )?;
+    drawing.set_title(Message::KMeanTitle.to_string());
+    drawing.set_class_label(1, Message::KMeanPointsLabel.to_string());
+    drawing.set_legend(Some(FixedPosition::TopRight(-2.0, -2.0)));
 
     let center = boundary.center();
-    println!("Границы:{}\nЦентр - {}\nТочки:", boundary, center);
+    println!(
+        "{}",
+        Message::KMeanHeader {
+            rect: &boundary,
+            center: &center
+        }
+    );
 
     let mut class_center: Option<Point> = None;
     let mut min_distance: Option<f32> = None;
 
-    for i in 1..point_amount {
-        let point = boundary.create_rand_point();
-        drawing.draw_point_with_class(point, 1, false, false);
+    for (i, &point) in (1..).zip(&points) {
+        if let Err(error) = drawing.draw_point_with_class(point, 1, false) {
+            eprintln!("{}", Message::Warning { warning: &error });
+        }
 
-        let distance = point.distance_to(center);
+        let distance = metric.between(&point, &center);
         if min_distance.is_none() || min_distance.unwrap() > distance {
             class_center = Some(point);
             min_distance = Some(distance);
         }
         println!(
-            "{}: {} | Расстояние до центра: {}",
-            i,
-            point,
-            distance.round_to_dp(2)
+            "{}",
+            Message::KMeanPoint {
+                index: &i,
+                point: &point,
+                distance: &distance.round_to_dp(2)
+            }
         );
     }
 
-    if class_center.is_none() || min_distance.is_none() {
-        panic!("У нас буквально нету победителя!! КОШМАР!!")
+    let (Some(class_center), Some(min_distance)) = (class_center, min_distance) else {
+        return Err(Error::EmptyPointSet);
+    };
+
+    if let Err(error) = drawing.draw_point_with_class(class_center, 1, true) {
+        eprintln!("{}", Message::Warning { warning: &error });
     }
-    let class_center = class_center.unwrap();
-    let min_distance = min_distance.unwrap();
 
-    drawing.draw_point_with_class(class_center, 1, true, false);
+    println!(
+        "{}",
+        Message::KMeanResult {
+            center: &class_center,
+            distance: &min_distance
+        }
+    );
+
+    // The center above is the point closest to the middle of the field, the
+    // medoid is the one closest to all of the others
+    let medoid = clara(
+        points.len(),
+        1,
+        |first, second| metric.between(&points[first], &points[second]),
+        None,
+        None,
+    )?;
+    if let Some(index) = medoid.medoid(1) {
+        println!(
+            "{}",
+            Message::KMeanMedoid {
+                medoid: &points[index],
+                cost: &medoid.cost.round_to_dp(2)
+            }
+        );
+    }
 
+    // All the points are one cluster, the other indices compare several of them
+    let wcss = wcss(&points, &vec![1; points.len()], metric.as_ref());
     println!(
-        "\nЦЕНТР КЛАССА - {} с расстоянием до центра {}",
-        class_center, min_distance
+        "{}",
+        Message::Wcss {
+            value: &wcss.round_to_dp(2)
+        }
     );
 
-    drawing.save();
-    drawing.show("gimp");
+    drawing.save()?;
+    if let Err(error) = drawing.show("gimp") {
+        eprintln!("{}", Message::Error { error: &error });
+    }
+
+    Ok(())
 }
 
-fn dialogue() -> usize {
+fn dialogue() -> Result<usize> {
     let mut buf = String::new();
 
     println!(
-        "Введите количество точек (По умолчанию: {}).",
-        DEFAULT_POINT_AMOUNT
+        "{}",
+        Message::PointsCountPrompt {
+            default: &DEFAULT_POINT_AMOUNT
+        }
     );
-    stdin()
-        .read_line(&mut buf)
-        .expect("Не удалось прочитать из стандартного ввода.");
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
     let mut count = buf.trim().parse::<usize>().unwrap_or(DEFAULT_POINT_AMOUNT);
     if count == 0 {
         count = DEFAULT_POINT_AMOUNT;
     }
 
-    count
+    Ok(count)
 }
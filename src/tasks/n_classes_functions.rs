@@ -1,7 +1,13 @@
 use std::io::stdin;
 
 use crate::{
-    geometry::{Axis, Point, Rectangle},
+    error::{Error, Result},
+    geometry::{Axis, FixedPosition, Point, Rectangle},
+    messages::Message,
+    metrics::{ConfusionMatrix, match_labels},
+    perceptron::Perceptron,
+    recorder::FrameRecorder,
+    tasks::yes_no_dialogue,
     utils::rand_f32_in_range,
     visual::Image,
 };
@@ -15,40 +21,105 @@ const MAX_FREE_COEFF: f32 = 20.0;
 const MIN_COEFF: f32 = -3.0;
 const MAX_COEFF: f32 = 3.0;
 
-pub fn execute() {
-    let (points_count, classes_count) = dialogue();
+pub fn execute() -> Result<()> {
+    let (points_count, classes_count) = dialogue()?;
 
     let boundary = Rectangle::default();
-    let mut drawing = Image::new(
+    let points: Vec<Point> = (0..points_count)
+        .map(|_| boundary.create_rand_point())
+        .collect();
+
+    // The points are labeled by hidden functions of the same kind, the class
+    // of a point is the one whose function is the largest there
+    let true_functions: Vec<(f32, f32, f32)> =
+        (0..classes_count).map(|_| rand_coefficients()).collect();
+    let truth: Vec<usize> = points
+        .iter()
+        .map(|point| {
+            (1..)
+                .zip(&true_functions)
+                .map(|(class, (w_0, w_1, w_2))| (class, w_0 + w_1 * point.x + w_2 * point.y))
+                .max_by(|first, second| first.1.total_cmp(&second.1))
+                .map_or(1, |(class, _)| class)
+        })
+        .collect();
+
+    let mut drawing = Image::fitted(
         "/home/vlad0s/Изображения/Misc/labs/n_classes_functions.png",
-        boundary.clone(),
+        &points,
+        None,
         true,
         None,
         None,
-    );
+    )?;
+    drawing.set_title(Message::NClassesTitle.to_string());
+    drawing.set_legend(Some(FixedPosition::TopRight(-2.0, -2.0)));
 
     drawing.draw_axis(Axis::X, None, None);
     drawing.draw_axis(Axis::Y, None, None);
 
-    println!("Границы: {} \n\nРазделяющие функции:", boundary);
-
-    let mut classes: Vec<(f32, f32, f32, Vec<Point>)> = Vec::with_capacity(classes_count);
+    println!(
+        "{} \n\n{}",
+        Message::Boundaries { rect: &boundary },
+        Message::DividingFunctions
+    );
 
+    let mut functions = Vec::with_capacity(classes_count);
     for i in 1..=classes_count {
-        let w_0 = rand_f32_in_range(MIN_FREE_COEFF, MAX_FREE_COEFF, 2);
-        let w_1 = rand_f32_in_range(MIN_COEFF, MAX_COEFF, 2);
-        let w_2 = rand_f32_in_range(MIN_COEFF, MAX_COEFF, 2);
+        let (w_0, w_1, w_2) = rand_coefficients();
 
         println!("{}. f(x, y) = {} + {}x + {}y", i, w_0, w_1, w_2);
 
         //drawing.draw_graph(move |x: f32| Some((w_0 + w_1 * x) / -w_2), None);
-        classes.push((w_0, w_1, w_2, Vec::new()));
+        drawing.set_class_label(i - 1, Message::ClassLabel { class: &i }.to_string());
+        functions.push((w_0, w_1, w_2));
     }
-    println!("-------------------------");
 
-    for _ in 0..points_count {
-        let new_point = boundary.create_rand_point();
+    // The random functions may be trained towards the hidden ones, every epoch
+    // is a frame with the points in the classes of the functions at that time
+    let mut perceptron = Perceptron::new(functions);
+    if yes_no_dialogue(Message::PerceptronPrompt)? {
+        let mut recorder = FrameRecorder::new(
+            "/home/vlad0s/Изображения/Misc/labs/n_classes_functions.gif",
+            None,
+        );
+        let mut recording = Ok(());
+        let mut record = |perceptron: &Perceptron| {
+            let mut frame = drawing.clone();
+            for &point in &points {
+                let _ = frame.draw_point_with_class(point, perceptron.predict(point) - 1, false);
+            }
+            if recording.is_ok() {
+                recording = recorder.capture(&frame);
+            }
+        };
+        perceptron.train(&points, &truth, None, None, Some(&mut record));
+        recording?;
+        for (epoch, errors) in (1..).zip(&perceptron.errors) {
+            println!(
+                "{}",
+                Message::PerceptronEpoch {
+                    epoch: &epoch,
+                    errors
+                }
+            );
+        }
+        recorder.save()?;
+
+        println!("{}", Message::DividingFunctions);
+        for (i, (w_0, w_1, w_2)) in (1..).zip(&perceptron.weights) {
+            println!("{}. f(x, y) = {} + {}x + {}y", i, w_0, w_1, w_2);
+        }
+    }
+    let mut classes: Vec<(f32, f32, f32, Vec<Point>)> = perceptron
+        .weights
+        .iter()
+        .map(|&(w_0, w_1, w_2)| (w_0, w_1, w_2, Vec::new()))
+        .collect();
+    println!("-------------------------");
 
+    let mut predicted = Vec::with_capacity(points.len());
+    for &new_point in &points {
         let mut chosen_class: usize = 0;
         let mut highest_score = f32::MIN;
 
@@ -60,19 +131,31 @@ pub fn execute() {
             }
         }
         classes[chosen_class].3.push(new_point);
-        drawing.draw_point_with_class(new_point, chosen_class, false, true);
+        predicted.push(chosen_class + 1);
+        let _ = drawing.draw_point_with_class(new_point, chosen_class, false);
     }
 
     for (index, (w_0, w_1, w_2, points)) in classes.iter().enumerate() {
-        println!("\nКЛАСС {} -------------------------", index + 1);
+        println!(
+            "{}",
+            Message::NClassesClass {
+                class: &(index + 1)
+            }
+        );
         println!("f(x, y) = {} + {}x + {}y", w_0, w_1, w_2);
-        println!("\nТОЧКИ:");
+        println!("{}", Message::NClassesPoints);
         for (point_index, point) in points.iter().enumerate() {
-            println!("№{} {}", point_index + 1, point);
+            println!(
+                "{}",
+                Message::NClassesPoint {
+                    index: &(point_index + 1),
+                    point
+                }
+            );
             for (class_function_index, (w_0, w_1, w_2, _)) in classes.iter().enumerate() {
                 let dividing_function_result = w_0 + w_1 * point.x + w_2 * point.y;
                 let winner_string = if class_function_index == index {
-                    " (ВЫБРАН)".to_string()
+                    Message::Chosen.to_string()
                 } else {
                     String::new()
                 };
@@ -92,40 +175,93 @@ pub fn execute() {
         }
     }
 
-    drawing.save();
-    drawing.show("gimp");
+    println!("-------------------------");
+    println!("{}", Message::TrueDividingFunctions);
+    for (i, (w_0, w_1, w_2)) in (1..).zip(&true_functions) {
+        println!("{}. g(x, y) = {} + {}x + {}y", i, w_0, w_1, w_2);
+    }
+
+    // The numbers of the random functions have nothing to do with the numbers
+    // of the hidden ones, so the classes are matched before scoring
+    println!("-------------------------");
+    let matching = match_labels(&truth, &predicted);
+    for (predicted, truth) in &matching {
+        println!("{}", Message::MatchedClass { predicted, truth });
+    }
+    let predicted: Vec<usize> = predicted
+        .iter()
+        .map(|class| {
+            let position = matching
+                .binary_search_by_key(class, |&(predicted, _)| predicted)
+                .unwrap();
+            matching[position].1
+        })
+        .collect();
+
+    let confusion = ConfusionMatrix::new(&truth, &predicted);
+    println!("-------------------------");
+    println!("{}", confusion);
+    let heatmap =
+        confusion.heatmap("/home/vlad0s/Изображения/Misc/labs/n_classes_functions_confusion.png");
+
+    drawing.save()?;
+    heatmap.save()?;
+    if let Err(error) = drawing.show("gimp") {
+        eprintln!("{}", Message::Error { error: &error });
+    }
+
+    Ok(())
+}
+
+/// Free coefficient and the coefficients of x and y
+fn rand_coefficients() -> (f32, f32, f32) {
+    (
+        rand_f32_in_range(MIN_FREE_COEFF, MAX_FREE_COEFF, 2),
+        rand_f32_in_range(MIN_COEFF, MAX_COEFF, 2),
+        rand_f32_in_range(MIN_COEFF, MAX_COEFF, 2),
+    )
 }
 
-fn dialogue() -> (usize, usize) {
+fn dialogue() -> Result<(usize, usize)> {
     let mut buf = String::new();
 
     println!(
-        "Введите количество точек (По умолчанию: {}).",
-        DEFAULT_POINTS_COUNT
+        "{}",
+        Message::PointsCountPrompt {
+            default: &DEFAULT_POINTS_COUNT
+        }
     );
-    stdin()
-        .read_line(&mut buf)
-        .expect("Не удалось прочитать из стандартного ввода.");
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
     let mut points_count = buf.trim().parse::<usize>().unwrap_or(DEFAULT_POINTS_COUNT);
     if points_count == 0 {
         points_count = DEFAULT_POINTS_COUNT;
     }
-    println!("Выбранное количество точек: {}", points_count);
+    println!(
+        "{}",
+        Message::PointsCountChosen {
+            count: &points_count
+        }
+    );
     buf.clear();
 
     println!(
-        "Введите количество классов (По умолчанию: {}).",
-        DEFAULT_CLASSES_COUNT
+        "{}",
+        Message::ClassesCountPrompt {
+            default: &DEFAULT_CLASSES_COUNT
+        }
     );
-    stdin()
-        .read_line(&mut buf)
-        .expect("Не удалось прочитать из стандартного ввода.");
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
     let mut classes_count = buf.trim().parse::<usize>().unwrap_or(DEFAULT_CLASSES_COUNT);
     if classes_count == 0 {
         classes_count = DEFAULT_CLASSES_COUNT;
     }
-    println!("Выбранное количество классов: {}", classes_count);
+    println!(
+        "{}",
+        Message::ClassesCountChosen {
+            count: &classes_count
+        }
+    );
     buf.clear();
 
-    (points_count, classes_count)
+    Ok((points_count, classes_count))
 }
@@ -0,0 +1,126 @@
+use crate::{
+    clustering::MiniBatchKMeans,
+    error::Result,
+    geometry::{FixedPosition, Point, Rectangle},
+    messages::Message,
+    metrics::{Agreement, Validity},
+    recorder::FrameRecorder,
+    tasks::{metric_dialogue, points_and_classes_dialogue, rand_true_classes_count},
+    visual::Image,
+};
+
+const DEFAULT_POINTS_COUNT: usize = 100_000;
+const DEFAULT_CLUSTERS_COUNT: usize = 3;
+const BATCH_SIZE: usize = 1000;
+/// Points kept to choose the metric and to score the clusters, the silhouette
+/// takes `O(n²)` and can not be found over the whole stream
+const SAMPLE_SIZE: usize = 2000;
+/// Frames of the animation over the whole stream, however long it is
+const FRAMES_COUNT: usize = 50;
+
+pub fn execute() -> Result<()> {
+    let (points_count, clusters_count) =
+        points_and_classes_dialogue(DEFAULT_POINTS_COUNT, DEFAULT_CLUSTERS_COUNT)?;
+
+    let boundary = Rectangle::default();
+    // Points come in groups, the groups are the true classes
+    let true_classes_count = rand_true_classes_count();
+    let mut stream = boundary
+        .rand_clusters_stream(true_classes_count, None)
+        .take(points_count);
+
+    // Only the sample and one batch are ever kept in memory
+    let sample: Vec<(Point, usize)> = stream.by_ref().take(SAMPLE_SIZE).collect();
+    let sample_points: Vec<Point> = sample.iter().map(|(point, _)| *point).collect();
+    let sample_truth: Vec<usize> = sample.iter().map(|(_, class)| *class).collect();
+    let metric = metric_dialogue(&sample_points)?;
+
+    let mut drawing = Image::new(
+        "/home/vlad0s/Изображения/Misc/labs/streaming_k_mean.png",
+        boundary.clone(),
+        true,
+        None,
+        None,
+    );
+    drawing.set_title(Message::StreamingTitle.to_string());
+    for class in 1..=clusters_count {
+        drawing.set_class_label(class, Message::ClassLabel { class: &class }.to_string());
+    }
+    drawing.set_legend(Some(FixedPosition::TopRight(-2.0, -2.0)));
+
+    let mut recorder = FrameRecorder::new(
+        "/home/vlad0s/Изображения/Misc/labs/streaming_k_mean.gif",
+        None,
+    );
+    let batches_per_frame = points_count
+        .div_ceil(BATCH_SIZE)
+        .div_ceil(FRAMES_COUNT)
+        .max(1);
+
+    // Every batch is drawn in the clusters of the centroids right after it
+    let mut model = MiniBatchKMeans::new(clusters_count);
+    let mut points = sample_points
+        .iter()
+        .copied()
+        .chain(stream.map(|(point, _)| point));
+    let mut not_drawn = 0;
+    for batch_index in 0.. {
+        let batch: Vec<Point> = points.by_ref().take(BATCH_SIZE).collect();
+        if batch.is_empty() {
+            break;
+        }
+        model.partial_fit(&batch, metric.as_ref());
+        for point in batch {
+            let class = model.predict(&point, metric.as_ref());
+            if drawing.draw_point_with_class(point, class, false).is_err() {
+                not_drawn += 1;
+            }
+        }
+        if batch_index % batches_per_frame == 0 {
+            let mut frame = drawing.clone();
+            draw_centroids(&mut frame, &model);
+            recorder.capture(&frame)?;
+        }
+    }
+    draw_centroids(&mut drawing, &model);
+    recorder.capture(&drawing)?;
+
+    println!("{}", model);
+    println!(
+        "{}",
+        Message::PointsNotDrawn {
+            count: &not_drawn,
+            total: &model.points_seen()
+        }
+    );
+
+    let sample_labels: Vec<usize> = sample_points
+        .iter()
+        .map(|point| model.predict(point, metric.as_ref()))
+        .collect();
+    println!(
+        "\n{}",
+        Message::StreamSample {
+            count: &sample_points.len()
+        }
+    );
+    println!(
+        "{}",
+        Validity::new(&sample_points, &sample_labels, metric.as_ref())
+    );
+    println!("{}", Agreement::new(&sample_truth, &sample_labels));
+
+    drawing.save()?;
+    recorder.save()?;
+    if let Err(error) = drawing.show("gimp") {
+        eprintln!("{}", Message::Error { error: &error });
+    }
+
+    Ok(())
+}
+
+fn draw_centroids(drawing: &mut Image<&str>, model: &MiniBatchKMeans) {
+    for (class, centroid) in (1..).zip(model.centroids()) {
+        let _ = drawing.draw_point_with_class(*centroid, class, true);
+    }
+}
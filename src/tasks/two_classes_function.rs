@@ -1,9 +1,15 @@
 use std::io::stdin;
 
 use crate::{
-    geometry::{Axis, Rectangle},
+    error::{Error, Result},
+    geometry::{Axis, FixedPosition, Point, Rectangle},
+    messages::Message,
+    metrics::{ConfusionMatrix, match_labels},
+    perceptron::Perceptron,
+    recorder::FrameRecorder,
+    tasks::yes_no_dialogue,
     utils::{RoundToDecimalPlaces, rand_f32_in_range},
-    visual::Image,
+    visual::{Color, Image},
 };
 
 const DEFAULT_POINTS_COUNT: usize = 1000;
@@ -14,84 +20,218 @@ const MAX_FREE_COEFF: f32 = 20.0;
 const MIN_COEFF: f32 = -5.0;
 const MAX_COEFF: f32 = 5.0;
 
-pub fn execute() {
-    let points_count = dialogue();
+const TRUE_GRAPH_COLOR: Color = Color::rgba(0x00, 0x00, 0xb9, 153);
+
+pub fn execute() -> Result<()> {
+    let points_count = dialogue()?;
 
     let boundary = Rectangle::default();
-    let mut drawing = Image::new(
+    let points: Vec<Point> = (0..points_count)
+        .map(|_| boundary.create_rand_point())
+        .collect();
+
+    // The points are labeled by a hidden function of the same kind, so the
+    // random one is scored by how close it comes to it
+    let (v_0, v_1, v_2) = rand_coefficients();
+    let truth: Vec<usize> = points
+        .iter()
+        .map(|point| {
+            if v_0 + v_1 * point.x + v_2 * point.y >= 0.0 {
+                1
+            } else {
+                2
+            }
+        })
+        .collect();
+
+    let mut drawing = Image::fitted(
         "/home/vlad0s/Изображения/Misc/labs/two_classes_function.png",
-        boundary.clone(),
+        &points,
+        None,
         true,
         None,
         None,
-    );
+    )?;
+    drawing.set_title(Message::TwoClassesTitle.to_string());
+    drawing.set_class_label(1, Message::FirstClass.to_string());
+    drawing.set_class_label(2, Message::SecondClass.to_string());
+    drawing.set_legend(Some(FixedPosition::TopRight(-2.0, -2.0)));
 
     drawing.draw_axis(Axis::X, None, None);
     drawing.draw_axis(Axis::Y, None, None);
+    drawing.draw_graph(
+        &move |x: f32| Some((v_0 + v_1 * x) / -v_2),
+        Some(TRUE_GRAPH_COLOR),
+    );
 
-    let w_0 = rand_f32_in_range(MIN_FREE_COEFF, MAX_FREE_COEFF, 2);
-    let w_1 = rand_f32_in_range(MIN_COEFF, MAX_COEFF, 2);
-    let w_2 = rand_f32_in_range(MIN_COEFF, MAX_COEFF, 2);
+    let (w_0, w_1, w_2) = rand_coefficients();
+    println!(
+        "{} \n\n{}",
+        Message::Boundaries { rect: &boundary },
+        Message::DividingFunction {
+            w_0: &w_0,
+            w_1: &w_1,
+            w_2: &w_2
+        }
+    );
 
+    // The random function may be trained towards the hidden one, every epoch
+    // is a frame with the points in the classes of the function at that time
+    let mut perceptron = Perceptron::new(vec![(w_0, w_1, w_2)]);
+    if yes_no_dialogue(Message::PerceptronPrompt)? {
+        let mut recorder = FrameRecorder::new(
+            "/home/vlad0s/Изображения/Misc/labs/two_classes_function.gif",
+            None,
+        );
+        let mut recording = Ok(());
+        let mut record = |perceptron: &Perceptron| {
+            let mut frame = drawing.clone();
+            draw_perceptron(&mut frame, &points, perceptron);
+            if recording.is_ok() {
+                recording = recorder.capture(&frame);
+            }
+        };
+        perceptron.train(&points, &truth, None, None, Some(&mut record));
+        recording?;
+        for (epoch, errors) in (1..).zip(&perceptron.errors) {
+            println!(
+                "{}",
+                Message::PerceptronEpoch {
+                    epoch: &epoch,
+                    errors
+                }
+            );
+        }
+        recorder.save()?;
+    }
+    let (w_0, w_1, w_2) = perceptron.weights[0];
     let dividing_function = move |x: f32, y: f32| w_0 + w_1 * x + w_2 * y;
-
     drawing.draw_graph(&move |x: f32| Some((w_0 + w_1 * x) / -w_2), None);
-
-    println!(
-        "Границы: {} \n\nРазделяющая функция: f(x, y) = {} + {}x + {}y",
-        boundary, w_0, w_1, w_2
-    );
     println!("-------------------------");
 
-    for i in 1..=points_count {
-        let new_point = boundary.create_rand_point();
+    let mut predicted = Vec::with_capacity(points.len());
+    for (i, &new_point) in (1..).zip(&points) {
         let dividing_function_result = dividing_function(new_point.x, new_point.y);
 
         if dividing_function_result >= 0.0 {
-            drawing.draw_point_with_class(new_point, 1, false, true);
+            predicted.push(1);
+            let _ = drawing.draw_point_with_class(new_point, 1, false);
             println!(
-                "{} точка: {} | Значение разделяющей функции: {} | (I класс)",
-                i,
-                new_point,
-                dividing_function_result.round_to_dp(2)
+                "{}",
+                Message::TwoClassesPoint {
+                    index: &i,
+                    point: &new_point,
+                    value: &dividing_function_result.round_to_dp(2),
+                    class: &Message::FirstClass
+                }
             );
         } else {
-            drawing.draw_point_with_class(new_point, 2, false, true);
+            predicted.push(2);
+            let _ = drawing.draw_point_with_class(new_point, 2, false);
             println!(
-                "{} точка: {} | Значение разделяющей функции: {} | (II класс)",
-                i,
-                new_point,
-                dividing_function_result.round_to_dp(2)
+                "{}",
+                Message::TwoClassesPoint {
+                    index: &i,
+                    point: &new_point,
+                    value: &dividing_function_result.round_to_dp(2),
+                    class: &Message::SecondClass
+                }
             );
         }
     }
 
     println!("-------------------------");
     println!(
-        "Разделяющая функция: f(x, y) = {} + {}x + {}y",
-        w_0, w_1, w_2
+        "{}\n{}",
+        Message::DividingFunction {
+            w_0: &w_0,
+            w_1: &w_1,
+            w_2: &w_2
+        },
+        Message::TrueDividingFunction {
+            w_0: &v_0,
+            w_1: &v_1,
+            w_2: &v_2
+        }
     );
 
-    drawing.save();
-    drawing.show("gimp");
+    // The random function may be the hidden one with the opposite sign, then its
+    // first class is the second true one, so the classes are matched before scoring
+    println!("-------------------------");
+    let matching = match_labels(&truth, &predicted);
+    for (predicted, truth) in &matching {
+        println!("{}", Message::MatchedClass { predicted, truth });
+    }
+    let predicted: Vec<usize> = predicted
+        .iter()
+        .map(|class| {
+            let position = matching
+                .binary_search_by_key(class, |&(predicted, _)| predicted)
+                .unwrap();
+            matching[position].1
+        })
+        .collect();
+
+    let confusion = ConfusionMatrix::new(&truth, &predicted);
+    println!("-------------------------");
+    println!("{}", confusion);
+    let class_names = vec![
+        Message::FirstClass.to_string(),
+        Message::SecondClass.to_string(),
+    ];
+    let mut heatmap =
+        confusion.heatmap("/home/vlad0s/Изображения/Misc/labs/two_classes_function_confusion.png");
+    heatmap.set_row_labels(class_names.clone());
+    heatmap.set_column_labels(class_names);
+
+    drawing.save()?;
+    heatmap.save()?;
+    if let Err(error) = drawing.show("gimp") {
+        eprintln!("{}", Message::Error { error: &error });
+    }
+
+    Ok(())
 }
 
-fn dialogue() -> usize {
+/// Dividing line of the function and the points in its classes
+fn draw_perceptron(drawing: &mut Image<&str>, points: &[Point], perceptron: &Perceptron) {
+    let (w_0, w_1, w_2) = perceptron.weights[0];
+    drawing.draw_graph(&move |x: f32| Some((w_0 + w_1 * x) / -w_2), None);
+    for &point in points {
+        let _ = drawing.draw_point_with_class(point, perceptron.predict(point), false);
+    }
+}
+
+/// Free coefficient and the coefficients of x and y
+fn rand_coefficients() -> (f32, f32, f32) {
+    (
+        rand_f32_in_range(MIN_FREE_COEFF, MAX_FREE_COEFF, 2),
+        rand_f32_in_range(MIN_COEFF, MAX_COEFF, 2),
+        rand_f32_in_range(MIN_COEFF, MAX_COEFF, 2),
+    )
+}
+
+fn dialogue() -> Result<usize> {
     let mut buf = String::new();
 
     println!(
-        "Введите количество точек (По умолчанию: {}).",
-        DEFAULT_POINTS_COUNT
+        "{}",
+        Message::PointsCountPrompt {
+            default: &DEFAULT_POINTS_COUNT
+        }
     );
-    stdin()
-        .read_line(&mut buf)
-        .expect("Не удалось прочитать из стандартного ввода.");
+    stdin().read_line(&mut buf).map_err(Error::Input)?;
     let mut points_count = buf.trim().parse::<usize>().unwrap_or(DEFAULT_POINTS_COUNT);
     if points_count == 0 {
         points_count = DEFAULT_POINTS_COUNT;
     }
-    println!("Выбранное количество точек: {}", points_count);
+    println!(
+        "{}",
+        Message::PointsCountChosen {
+            count: &points_count
+        }
+    );
     buf.clear();
 
-    points_count
+    Ok(points_count)
 }
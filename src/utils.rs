@@ -30,6 +30,13 @@ pub fn rand_f32_in_range_with_distance(
     (random_num + min).round_to_dp(decimal_places)
 }
 
+/// Normally distributed number (Box–Muller transform)
+pub fn rand_normal(mean: f32, std_dev: f32) -> f32 {
+    let first: f32 = 1.0 - rand::random::<f32>();
+    let second: f32 = rand::random();
+    mean + std_dev * (-2.0 * first.ln()).sqrt() * (std::f32::consts::TAU * second).cos()
+}
+
 pub fn rand_isize_in_range(min: isize, max: isize) -> isize {
     assert!(
         max >= min,
@@ -42,24 +49,24 @@ pub fn rand_isize_in_range(min: isize, max: isize) -> isize {
 pub trait RandGet {
     type Element;
 
-    fn rand_get<'a>(&'a self) -> Option<&'a Self::Element>;
+    fn rand_get(&self) -> Option<&Self::Element>;
 
-    fn rand_get_mut<'a>(&'a mut self) -> Option<&'a mut Self::Element>;
+    fn rand_get_mut(&mut self) -> Option<&mut Self::Element>;
 
-    fn rand_index<'a>(&'a self) -> Option<usize>;
+    fn rand_index(&self) -> Option<usize>;
 }
 
 impl<T> RandGet for Vec<T> {
     type Element = T;
 
-    fn rand_get<'a>(&'a self) -> Option<&'a Self::Element> {
+    fn rand_get(&self) -> Option<&Self::Element> {
         if self.is_empty() {
             return None;
         }
         self.get(rand_isize_in_range(0, (self.len() - 1) as isize) as usize)
     }
 
-    fn rand_get_mut<'a>(&'a mut self) -> Option<&'a mut Self::Element> {
+    fn rand_get_mut(&mut self) -> Option<&mut Self::Element> {
         if self.is_empty() {
             return None;
         }
@@ -67,7 +74,7 @@ impl<T> RandGet for Vec<T> {
         self.get_mut(rand_isize_in_range(0, (self_len - 1) as isize) as usize)
     }
 
-    fn rand_index<'a>(&'a self) -> Option<usize> {
+    fn rand_index(&self) -> Option<usize> {
         if self.is_empty() {
             return None;
         }
@@ -89,3 +96,12 @@ impl RoundToDecimalPlaces for f32 {
         (self * 10.0_f32.powi(decimal_places as i32)).round() / 10.0_f32.powi(decimal_places as i32)
     }
 }
+
+impl RoundToDecimalPlaces for f64 {
+    fn round_to_dp(self, decimal_places: u16) -> Self
+    where
+        Self: Sized,
+    {
+        (self * 10.0_f64.powi(decimal_places as i32)).round() / 10.0_f64.powi(decimal_places as i32)
+    }
+}
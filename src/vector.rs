@@ -0,0 +1,243 @@
+pub mod matrix;
+
+use std::{fmt::Display, ops::Index};
+
+use crate::{
+    error::{Error, Result},
+    geometry::Point,
+};
+
+pub use matrix::Matrix;
+
+/// Point of a feature space of any dimension. The clustering algorithms and the
+/// validity indices work on any of them, [`Point`] is the two-dimensional one.
+pub trait Features: Clone + Send + Sync {
+    fn dimension(&self) -> usize;
+
+    /// As many coordinates as the dimension
+    fn coordinates(&self) -> impl AsRef<[f32]> + '_;
+
+    /// Missing coordinates are 0, types of a fixed dimension drop the extra ones
+    fn from_coordinates(coordinates: &[f32]) -> Self;
+}
+
+impl Features for Point {
+    fn dimension(&self) -> usize {
+        2
+    }
+
+    fn coordinates(&self) -> impl AsRef<[f32]> + '_ {
+        [self.x, self.y]
+    }
+
+    fn from_coordinates(coordinates: &[f32]) -> Self {
+        let coordinate = |axis: usize| coordinates.get(axis).copied().unwrap_or(0.0);
+        Point::new(coordinate(0), coordinate(1))
+    }
+}
+
+/// Point of a feature space with the dimension known only at run time,
+/// e.g. a row of a data set
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Vector {
+    coordinates: Vec<f32>,
+}
+
+impl Vector {
+    pub fn new(coordinates: Vec<f32>) -> Self {
+        Self { coordinates }
+    }
+
+    pub fn zeros(dimension: usize) -> Self {
+        Self::new(vec![0.0; dimension])
+    }
+
+    pub fn as_slice(&self) -> &[f32] {
+        &self.coordinates
+    }
+
+    /// Sum of the products of the coordinates, the shorter vector is padded with zeros
+    pub fn dot(&self, other: &Vector) -> f32 {
+        self.coordinates
+            .iter()
+            .zip(&other.coordinates)
+            .map(|(first, second)| first * second)
+            .sum()
+    }
+
+    pub fn norm(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn distance_to(&self, other: &Vector) -> f32 {
+        self.coordinates
+            .iter()
+            .zip(&other.coordinates)
+            .map(|(first, second)| (first - second).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
+}
+
+impl Features for Vector {
+    fn dimension(&self) -> usize {
+        self.coordinates.len()
+    }
+
+    fn coordinates(&self) -> impl AsRef<[f32]> + '_ {
+        self.as_slice()
+    }
+
+    fn from_coordinates(coordinates: &[f32]) -> Self {
+        Self::new(coordinates.to_vec())
+    }
+}
+
+impl From<Point> for Vector {
+    fn from(point: Point) -> Self {
+        Self::new(vec![point.x, point.y])
+    }
+}
+
+impl Index<usize> for Vector {
+    type Output = f32;
+
+    fn index(&self, axis: usize) -> &f32 {
+        &self.coordinates[axis]
+    }
+}
+
+impl Display for Vector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let coordinates: Vec<String> = self.coordinates.iter().map(f32::to_string).collect();
+        write!(f, "({})", coordinates.join("; "))
+    }
+}
+
+/// Mean of the points, `None` when there are none. The dimension is the one of
+/// the first point.
+pub fn mean<'a, P: Features + 'a>(points: impl IntoIterator<Item = &'a P>) -> Option<P> {
+    let mut points = points.into_iter();
+    let first = points.next()?;
+    let mut sums = first.coordinates().as_ref().to_vec();
+    let mut count = 1;
+    for point in points {
+        for (sum, coordinate) in sums.iter_mut().zip(point.coordinates().as_ref()) {
+            *sum += coordinate;
+        }
+        count += 1;
+    }
+    for sum in &mut sums {
+        *sum /= count as f32;
+    }
+    Some(P::from_coordinates(&sums))
+}
+
+/// Linear map of a feature space onto the plane, so that the results can be
+/// drawn with [`Image`](crate::visual::Image). A point is shifted by the origin
+/// and its X and Y are the dot products with the two axes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Projection {
+    origin: Vector,
+    axes: [Vector; 2],
+}
+
+impl Projection {
+    /// Axes must have the dimension of the origin
+    pub fn new(origin: Vector, axes: [Vector; 2]) -> Result<Self> {
+        for axis in &axes {
+            if axis.dimension() != origin.dimension() {
+                return Err(Error::DimensionMismatch {
+                    expected: origin.dimension(),
+                    found: axis.dimension(),
+                });
+            }
+        }
+        Ok(Self { origin, axes })
+    }
+
+    /// Two of the features as they are, counted from 0
+    pub fn features(dimension: usize, first: usize, second: usize) -> Result<Self> {
+        let axis = |feature: usize| {
+            if feature >= dimension {
+                return Err(Error::FeatureOutOfRange { feature, dimension });
+            }
+            let mut axis = Vector::zeros(dimension);
+            axis.coordinates[feature] = 1.0;
+            Ok(axis)
+        };
+        Self::new(Vector::zeros(dimension), [axis(first)?, axis(second)?])
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.origin.dimension()
+    }
+
+    pub fn apply<P: Features>(&self, point: &P) -> Point {
+        let shifted = Vector::new(
+            point
+                .coordinates()
+                .as_ref()
+                .iter()
+                .zip(self.origin.as_slice())
+                .map(|(coordinate, origin)| coordinate - origin)
+                .collect(),
+        );
+        Point::new(shifted.dot(&self.axes[0]), shifted.dot(&self.axes[1]))
+    }
+
+    pub fn apply_all<P: Features>(&self, points: &[P]) -> Vec<Point> {
+        points.iter().map(|point| self.apply(point)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::Error,
+        geometry::Point,
+        vector::{Features, Projection, Vector, mean},
+    };
+
+    #[test]
+    fn fixed_dimension_pads_and_drops() {
+        assert_eq!(Point::from_coordinates(&[1.0]), Point::new(1.0, 0.0));
+        assert_eq!(
+            Point::from_coordinates(&[1.0, 2.0, 3.0]),
+            Point::new(1.0, 2.0)
+        );
+        assert_eq!(Vector::from(Point::new(1.0, 2.0)).as_slice(), [1.0, 2.0]);
+    }
+
+    #[test]
+    fn mean_of_vectors() {
+        let points = [
+            Vector::new(vec![0.0, 2.0, 4.0]),
+            Vector::new(vec![2.0, 4.0, 8.0]),
+        ];
+        assert_eq!(mean(&points), Some(Vector::new(vec![1.0, 3.0, 6.0])));
+        assert_eq!(mean::<Vector>(&[]), None);
+    }
+
+    #[test]
+    fn projection_onto_features() {
+        let projection = Projection::features(3, 2, 0).unwrap();
+        let point = Vector::new(vec![1.0, 2.0, 3.0]);
+        assert_eq!(projection.apply(&point), Point::new(3.0, 1.0));
+
+        assert!(matches!(
+            Projection::features(3, 3, 0),
+            Err(Error::FeatureOutOfRange {
+                feature: 3,
+                dimension: 3
+            })
+        ));
+        assert!(matches!(
+            Projection::new(Vector::zeros(3), [Vector::zeros(3), Vector::zeros(2)]),
+            Err(Error::DimensionMismatch {
+                expected: 3,
+                found: 2
+            })
+        ));
+    }
+}
@@ -0,0 +1,390 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{
+    error::{Error, Result},
+    vector::{Features, Vector, mean},
+};
+
+/// Pivots closer to 0 than this make a matrix singular
+const MIN_PIVOT: f64 = 1e-9;
+/// Relative difference of the mirrored elements still taken as symmetric
+const SYMMETRY_TOLERANCE: f32 = 1e-4;
+/// Jacobi rotations stop after this many sweeps even if the matrix is not diagonal yet
+const MAX_JACOBI_SWEEPS: usize = 100;
+
+/// Square matrix, stored row by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    size: usize,
+    values: Vec<f32>,
+}
+
+impl Matrix {
+    pub fn zeros(size: usize) -> Self {
+        Self {
+            size,
+            values: vec![0.0; size * size],
+        }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Self::zeros(size);
+        for i in 0..size {
+            matrix[(i, i)] = 1.0;
+        }
+        matrix
+    }
+
+    /// Every row must be as long as there are rows
+    pub fn from_rows(rows: &[Vec<f32>]) -> Result<Self> {
+        let size = rows.len();
+        if let Some(row) = rows.iter().find(|row| row.len() != size) {
+            return Err(Error::DimensionMismatch {
+                expected: size,
+                found: row.len(),
+            });
+        }
+        Ok(Self {
+            size,
+            values: rows.concat(),
+        })
+    }
+
+    /// Sample covariance of the features (divided by `n - 1`)
+    pub fn covariance<P: Features>(points: &[P]) -> Result<Self> {
+        let mean = mean(points).ok_or(Error::EmptyPointSet)?;
+        let mean = mean.coordinates();
+        let mean = mean.as_ref();
+
+        let mut covariance = Self::zeros(mean.len());
+        for point in points {
+            let coordinates = point.coordinates();
+            let deviations: Vec<f32> = coordinates
+                .as_ref()
+                .iter()
+                .zip(mean)
+                .map(|(coordinate, mean)| coordinate - mean)
+                .collect();
+            for (row, first) in deviations.iter().enumerate() {
+                for (column, second) in deviations.iter().enumerate() {
+                    covariance[(row, column)] += first * second;
+                }
+            }
+        }
+        let scale = 1.0 / (points.len().max(2) - 1) as f32;
+        covariance
+            .values
+            .iter_mut()
+            .for_each(|value| *value *= scale);
+        Ok(covariance)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn row(&self, row: usize) -> &[f32] {
+        &self.values[row * self.size..(row + 1) * self.size]
+    }
+
+    pub fn transposed(&self) -> Self {
+        let mut transposed = Self::zeros(self.size);
+        for row in 0..self.size {
+            for column in 0..self.size {
+                transposed[(column, row)] = self[(row, column)];
+            }
+        }
+        transposed
+    }
+
+    pub fn is_symmetric(&self) -> bool {
+        let largest = self.values.iter().fold(0.0_f32, |max, v| max.max(v.abs()));
+        (0..self.size).all(|row| {
+            (row + 1..self.size).all(|column| {
+                (self[(row, column)] - self[(column, row)]).abs() <= SYMMETRY_TOLERANCE * largest
+            })
+        })
+    }
+
+    /// Lower triangular `L` with `L * Lᵀ` equal to the matrix, `None` if the
+    /// matrix is not positive definite
+    pub fn cholesky(&self) -> Option<Self> {
+        let mut lower = Self::zeros(self.size);
+        for row in 0..self.size {
+            for column in 0..=row {
+                let sum: f64 = (0..column)
+                    .map(|k| lower[(row, k)] as f64 * lower[(column, k)] as f64)
+                    .sum();
+                let value = self[(row, column)] as f64 - sum;
+                if row == column {
+                    if value <= MIN_PIVOT {
+                        return None;
+                    }
+                    lower[(row, column)] = value.sqrt() as f32;
+                } else {
+                    lower[(row, column)] = (value / lower[(column, column)] as f64) as f32;
+                }
+            }
+        }
+        Some(lower)
+    }
+
+    /// Gauss–Jordan elimination with partial pivoting, `None` for singular matrices
+    pub fn inverse(&self) -> Option<Self> {
+        let size = self.size;
+        let mut left: Vec<f64> = self.values.iter().map(|&value| value as f64).collect();
+        let mut right: Vec<f64> = Self::identity(size)
+            .values
+            .iter()
+            .map(|&value| value as f64)
+            .collect();
+
+        for column in 0..size {
+            let pivot_row = (column..size).max_by(|&first, &second| {
+                left[first * size + column]
+                    .abs()
+                    .total_cmp(&left[second * size + column].abs())
+            })?;
+            let pivot = left[pivot_row * size + column];
+            if pivot.abs() <= MIN_PIVOT {
+                return None;
+            }
+            for k in 0..size {
+                left.swap(column * size + k, pivot_row * size + k);
+                right.swap(column * size + k, pivot_row * size + k);
+            }
+            for k in 0..size {
+                left[column * size + k] /= pivot;
+                right[column * size + k] /= pivot;
+            }
+            for row in (0..size).filter(|&row| row != column) {
+                let factor = left[row * size + column];
+                if factor == 0.0 {
+                    continue;
+                }
+                for k in 0..size {
+                    left[row * size + k] -= factor * left[column * size + k];
+                    right[row * size + k] -= factor * right[column * size + k];
+                }
+            }
+        }
+
+        Some(Self {
+            size,
+            values: right.into_iter().map(|value| value as f32).collect(),
+        })
+    }
+
+    /// Eigenvalues and unit eigenvectors of a symmetric matrix (cyclic Jacobi
+    /// rotations), from the largest eigenvalue
+    pub fn symmetric_eigen(&self) -> Vec<(f32, Vector)> {
+        let size = self.size;
+        let mut values: Vec<f64> = self.values.iter().map(|&value| value as f64).collect();
+        let mut vectors: Vec<f64> = Self::identity(size)
+            .values
+            .iter()
+            .map(|&value| value as f64)
+            .collect();
+        let norm: f64 = values.iter().map(|value| value * value).sum();
+
+        for _ in 0..MAX_JACOBI_SWEEPS {
+            let off_diagonal: f64 = (0..size)
+                .flat_map(|row| {
+                    (0..size)
+                        .filter(move |&column| column != row)
+                        .map(move |column| (row, column))
+                })
+                .map(|(row, column)| values[row * size + column].powi(2))
+                .sum();
+            if off_diagonal <= norm * f64::EPSILON * f64::EPSILON {
+                break;
+            }
+
+            for p in 0..size {
+                for q in p + 1..size {
+                    let pq = values[p * size + q];
+                    if pq == 0.0 {
+                        continue;
+                    }
+                    // Rotation by the angle that zeroes the (p; q) element
+                    let theta = (values[q * size + q] - values[p * size + p]) / (2.0 * pq);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let cos = 1.0 / (t * t + 1.0).sqrt();
+                    let sin = t * cos;
+
+                    for k in 0..size {
+                        let (kp, kq) = (values[k * size + p], values[k * size + q]);
+                        values[k * size + p] = cos * kp - sin * kq;
+                        values[k * size + q] = sin * kp + cos * kq;
+                    }
+                    for k in 0..size {
+                        let (pk, qk) = (values[p * size + k], values[q * size + k]);
+                        values[p * size + k] = cos * pk - sin * qk;
+                        values[q * size + k] = sin * pk + cos * qk;
+                    }
+                    for k in 0..size {
+                        let (kp, kq) = (vectors[k * size + p], vectors[k * size + q]);
+                        vectors[k * size + p] = cos * kp - sin * kq;
+                        vectors[k * size + q] = sin * kp + cos * kq;
+                    }
+                }
+            }
+        }
+
+        // Eigenvectors are the columns of the accumulated rotations
+        let mut pairs: Vec<(f32, Vector)> = (0..size)
+            .map(|column| {
+                let vector = (0..size)
+                    .map(|row| vectors[row * size + column] as f32)
+                    .collect();
+                (values[column * size + column] as f32, Vector::new(vector))
+            })
+            .collect();
+        pairs.sort_by(|first, second| second.0.total_cmp(&first.0));
+        pairs
+    }
+
+    /// Solution of `L * x = b` for a lower triangular matrix (forward substitution)
+    pub fn solve_lower(&self, vector: &[f32]) -> Vec<f32> {
+        let mut solution = vec![0.0; self.size];
+        for row in 0..self.size {
+            let sum: f32 = (0..row)
+                .map(|column| self[(row, column)] * solution[column])
+                .sum();
+            let value = vector.get(row).copied().unwrap_or(0.0);
+            solution[row] = (value - sum) / self[(row, row)];
+        }
+        solution
+    }
+
+    pub fn multiply_vector(&self, vector: &[f32]) -> Vec<f32> {
+        (0..self.size)
+            .map(|row| {
+                self.row(row)
+                    .iter()
+                    .zip(vector)
+                    .map(|(value, coordinate)| value * coordinate)
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// `vᵀ * M * v`
+    pub fn quadratic_form(&self, vector: &[f32]) -> f32 {
+        self.multiply_vector(vector)
+            .iter()
+            .zip(vector)
+            .map(|(first, second)| first * second)
+            .sum()
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = f32;
+
+    fn index(&self, (row, column): (usize, usize)) -> &f32 {
+        &self.values[row * self.size + column]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut f32 {
+        &mut self.values[row * self.size + column]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vector::{Matrix, Vector};
+
+    fn assert_close(first: &Matrix, second: &Matrix) {
+        for row in 0..first.size() {
+            for column in 0..first.size() {
+                assert!(
+                    (first[(row, column)] - second[(row, column)]).abs() < 1e-4,
+                    "{:?} != {:?}",
+                    first,
+                    second
+                );
+            }
+        }
+    }
+
+    fn product(first: &Matrix, second: &Matrix) -> Matrix {
+        let mut product = Matrix::zeros(first.size());
+        for row in 0..first.size() {
+            for column in 0..first.size() {
+                product[(row, column)] = (0..first.size())
+                    .map(|k| first[(row, k)] * second[(k, column)])
+                    .sum();
+            }
+        }
+        product
+    }
+
+    #[test]
+    fn covariance_of_known_points() {
+        let points = [
+            Vector::new(vec![0.0, 0.0]),
+            Vector::new(vec![2.0, 2.0]),
+            Vector::new(vec![4.0, 1.0]),
+        ];
+        let expected = Matrix::from_rows(&[vec![4.0, 1.0], vec![1.0, 1.0]]).unwrap();
+        assert_close(&Matrix::covariance(&points).unwrap(), &expected);
+    }
+
+    #[test]
+    fn jacobi_eigen_of_known_matrix() {
+        // Eigenvalues 4, 2 and 1 with the eigenvectors (1; 1; 0), (1; -1; 0) and (0; 0; 1)
+        let matrix = Matrix::from_rows(&[
+            vec![3.0, 1.0, 0.0],
+            vec![1.0, 3.0, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ])
+        .unwrap();
+        let eigen = matrix.symmetric_eigen();
+        let expected = [
+            (4.0, [1.0, 1.0, 0.0]),
+            (2.0, [1.0, -1.0, 0.0]),
+            (1.0, [0.0, 0.0, 1.0]),
+        ];
+
+        for ((value, vector), (expected_value, expected_vector)) in eigen.iter().zip(expected) {
+            assert!((value - expected_value).abs() < 1e-5);
+            assert!((vector.norm() - 1.0).abs() < 1e-5);
+            let expected_vector = Vector::new(expected_vector.to_vec());
+            // Eigenvectors are known up to the sign
+            let cosine = vector.dot(&expected_vector) / expected_vector.norm();
+            assert!((cosine.abs() - 1.0).abs() < 1e-5);
+            // M * v = λ * v
+            for (product, coordinate) in matrix
+                .multiply_vector(vector.as_slice())
+                .iter()
+                .zip(vector.as_slice())
+            {
+                assert!((product - value * coordinate).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_and_cholesky() {
+        let matrix = Matrix::from_rows(&[
+            vec![4.0, 2.0, 0.0],
+            vec![2.0, 5.0, 1.0],
+            vec![0.0, 1.0, 3.0],
+        ])
+        .unwrap();
+        assert_close(
+            &product(&matrix, &matrix.inverse().unwrap()),
+            &Matrix::identity(3),
+        );
+
+        let lower = matrix.cholesky().unwrap();
+        assert_close(&product(&lower, &lower.transposed()), &matrix);
+
+        let singular = Matrix::from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+        assert!(singular.inverse().is_none());
+        assert!(singular.cholesky().is_none());
+    }
+}
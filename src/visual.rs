@@ -1,31 +1,42 @@
+pub mod color;
+pub mod heatmap;
+pub mod silhouette;
+
 use std::{
-    collections::HashMap,
-    ops::Sub,
+    collections::{BTreeMap, HashMap},
     path::Path,
-    process::{Command, exit},
+    process::Command,
 };
 
-use image::{
-    Pixel, Rgba, RgbaImage,
-    imageops::{FilterType, resize},
-};
+use image::RgbaImage;
 
 use crate::{
-    font::CharSymbol,
-    geometry::{Axis, Point, Rectangle},
-    utils::rand_isize_in_range,
+    backend::{
+        DrawingBackend, Label, LabelContent, RasterBackend, Resolution, SvgBackend, Viewport,
+    },
+    clustering::kmeans::nearest,
+    distance::Metric,
+    error::{Error, Result},
+    font::{BitmapFont, CharSymbol, Glyph, HorizontalAlign, TextStyle, VerticalAlign},
+    geometry::{Axis, FixedPosition, Point, Rectangle},
+    layout::{Layout, LegendEntry, text_scale},
+    messages::Message,
+    palette::{Palette, distinct_colors},
 };
 
-const MAX_IMAGE_DIMENSION: u32 = 5_000;
-
-const IMAGE_PADDING: u32 = 1;
+pub use color::{Color, Hsl, Hsv, Lab};
 
-const POINT_ALPHA: f32 = 1.0;
-
-const FILLING_COLOR: &str = "#FFFFFF";
-const FILLING_ALPHA: f32 = 1.0;
-
-const GRAPH_X_STEP: f32 = 0.1;
+const FILLING_COLOR: Color = Color::rgba(255, 255, 255, 255);
+const GRAPH_COLOR: Color = Color::rgba(0xb9, 0x00, 0x00, 153);
+const AXIS_COLOR: Color = Color::rgba(0x00, 0x00, 0x00, 76);
+const TEXT_COLOR: Color = Color::rgba(0x00, 0x00, 0x00, 255);
+/// Share of white in the colors of the regions, keeps the points on them visible
+const REGION_WHITENESS: f32 = 0.75;
+/// Side of the squares (in pixels) the regions are sampled on
+const REGION_CELL_SIZE: u32 = 2;
+/// Length of the sides of an arrow head in pixels and their angle to the shaft
+const ARROW_HEAD_SIZE: f32 = 8.0;
+const ARROW_HEAD_ANGLE: f32 = 0.45;
 
 /// Class ID - (Core Color, Point Color)
 type ClassColors = HashMap<usize, (Color, Color)>;
@@ -33,15 +44,20 @@ type ClassColors = HashMap<usize, (Color, Color)>;
 #[derive(Clone)]
 pub struct Image<T: AsRef<Path>> {
     path: T,
-    inner: RgbaImage,
-    rect: Rectangle,
+    backend: Box<dyn DrawingBackend>,
+    viewport: Viewport,
     class_colors: ClassColors,
-
-    final_width: u32,
-    final_height: u32,
+    palette: Palette,
+    class_labels: HashMap<usize, String>,
+    /// Class ID - Drawn points count
+    class_counts: BTreeMap<usize, usize>,
+    layout: Layout,
+    font: BitmapFont,
 }
 
 impl<T: AsRef<Path>> Image<T> {
+    /// Image of the field with a data area of `custom_width`x`custom_height` pixels,
+    /// see [`Resolution::Pixels`]. `0` is the same as `None`.
     pub fn new(
         path: T,
         rect: Rectangle,
@@ -49,233 +65,132 @@ impl<T: AsRef<Path>> Image<T> {
         custom_width: Option<u32>,
         custom_height: Option<u32>,
     ) -> Self {
-        let mut width: u32 = rect.width().ceil() as u32 + IMAGE_PADDING;
-        let mut height: u32 = rect.height().ceil() as u32 + IMAGE_PADDING;
-
-        let mut final_width = width;
-        let mut final_height = height;
-
-        if let Some(custom_width) = custom_width
-            && custom_width != 0
-        {
-            final_width = custom_width;
-        }
-
-        if let Some(custom_height) = custom_height
-            && custom_height != 0
-        {
-            final_height = custom_height;
-        }
-
-        if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
-            println!(
-                "ВНИМАНИЕ: максимальные высота и ширина изображения равны {}, однако требуется создать холст {}/{}. Изображение будет сжато, что может привести к потере некоторых точек.",
-                MAX_IMAGE_DIMENSION, height, width
-            );
-
-            let downscale_ratio: f32;
-            if width > height {
-                downscale_ratio = width as f32 / MAX_IMAGE_DIMENSION as f32
-            } else {
-                downscale_ratio = height as f32 / MAX_IMAGE_DIMENSION as f32
-            }
-            width = (width as f32 / downscale_ratio).round() as u32;
-            height = (height as f32 / downscale_ratio).round() as u32;
-
-            println!(
-                "Изображение было сжато до {}/{} (в {} раз)",
-                height, width, downscale_ratio
-            );
-        }
-
-        if final_width > MAX_IMAGE_DIMENSION || final_height > MAX_IMAGE_DIMENSION {
-            println!(
-                "ВНИМАНИЕ: максимальные КАСТОМНЫЕ высота и ширина изображения равны {}, однако требуется создать холст {}/{}. Изображение будет сжато.",
-                MAX_IMAGE_DIMENSION, final_height, final_width
-            );
-            let downscale_ratio: f32;
-
-            if final_width > final_height {
-                downscale_ratio = final_width as f32 / MAX_IMAGE_DIMENSION as f32
-            } else {
-                downscale_ratio = final_height as f32 / MAX_IMAGE_DIMENSION as f32
-            }
-            final_width = (final_width as f32 / downscale_ratio).round() as u32;
-            final_height = (final_height as f32 / downscale_ratio).round() as u32;
-
-            println!(
-                "Изображение (КАСТОМНОЕ) было сжато до {}/{} (в {} раз)",
-                final_height, final_width, downscale_ratio
-            );
-        }
-
-        let mut inner = RgbaImage::new(width, height);
-        if fill {
-            for pixel in inner.pixels_mut() {
-                *pixel = Color::hex(FILLING_COLOR, FILLING_ALPHA).inner();
-            }
-        }
+        let resolution = Resolution::Pixels {
+            width: custom_width.filter(|width| *width != 0),
+            height: custom_height.filter(|height| *height != 0),
+        };
+        Self::with_resolution(path, rect, fill, resolution)
+    }
 
-        let class_colors = Self::init_default_colors();
+    /// Image of the field drawn at the resolution. The field is mapped onto the
+    /// pixels by an affine transform, so it may be of any scale.
+    pub fn with_resolution(path: T, rect: Rectangle, fill: bool, resolution: Resolution) -> Self {
+        let viewport = Viewport::new(rect, &resolution);
+        let background = fill.then_some(FILLING_COLOR);
+        let is_svg = path
+            .as_ref()
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+        let backend: Box<dyn DrawingBackend> = if is_svg {
+            Box::new(SvgBackend::new(viewport.clone(), background))
+        } else {
+            Box::new(RasterBackend::new(viewport.clone(), background))
+        };
 
         Self {
             path,
-            inner,
-            rect,
-            class_colors,
-            final_width,
-            final_height,
+            backend,
+            viewport,
+            class_colors: HashMap::new(),
+            palette: Palette::default(),
+            class_labels: HashMap::new(),
+            class_counts: BTreeMap::new(),
+            layout: Layout::default(),
+            font: BitmapFont::builtin(),
         }
     }
 
-    fn init_default_colors() -> ClassColors {
-        let mut class_colors = HashMap::new();
-
-        class_colors.insert(
-            1,
-            (
-                Color::hex("#1e6b8a", POINT_ALPHA),
-                Color::hex("#4a9fc8", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            2,
-            (
-                Color::hex("#8a351e", POINT_ALPHA),
-                Color::hex("#c86a4a", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            3,
-            (
-                Color::hex("#2d8a1e", POINT_ALPHA),
-                Color::hex("#52c84a", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            4,
-            (
-                Color::hex("#7a1e8a", POINT_ALPHA),
-                Color::hex("#b84ac8", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            5,
-            (
-                Color::hex("#8a7a1e", POINT_ALPHA),
-                Color::hex("#c8b84a", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            6,
-            (
-                Color::hex("#1e8a7a", POINT_ALPHA),
-                Color::hex("#4ac8b8", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            7,
-            (
-                Color::hex("#8a2d1e", POINT_ALPHA),
-                Color::hex("#c85a4a", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            8,
-            (
-                Color::hex("#4a1e8a", POINT_ALPHA),
-                Color::hex("#7a4ac8", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            9,
-            (
-                Color::hex("#8a1e4a", POINT_ALPHA),
-                Color::hex("#c84a7a", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            10,
-            (
-                Color::hex("#5a8a1e", POINT_ALPHA),
-                Color::hex("#8ac84a", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            11,
-            (
-                Color::hex("#1e5a8a", POINT_ALPHA),
-                Color::hex("#4a8ac8", POINT_ALPHA),
-            ),
-        );
-
-        class_colors.insert(
-            12,
-            (
-                Color::hex("#8a5a1e", POINT_ALPHA),
-                Color::hex("#c88a4a", POINT_ALPHA),
-            ),
-        );
+    /// Image with the field fitted to the points, see [`Rectangle::bounding`].
+    /// When both custom sizes are set the field keeps their aspect ratio, so that
+    /// distances are not stretched.
+    pub fn fitted(
+        path: T,
+        points: &[Point],
+        padding: Option<f32>,
+        fill: bool,
+        custom_width: Option<u32>,
+        custom_height: Option<u32>,
+    ) -> Result<Self> {
+        let aspect_ratio = match (custom_width, custom_height) {
+            (Some(width), Some(height)) if width != 0 && height != 0 => {
+                Some(width as f32 / height as f32)
+            }
+            _ => None,
+        };
+        let rect = Rectangle::bounding(points, padding, aspect_ratio)?;
+        Ok(Self::new(path, rect, fill, custom_width, custom_height))
+    }
 
-        class_colors.insert(
-            13,
-            (
-                Color::hex("#6a1e8a", POINT_ALPHA),
-                Color::hex("#9a4ac8", POINT_ALPHA),
-            ),
-        );
+    /// Palette for classes that have not been drawn yet, drawn ones keep their colors
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
 
-        class_colors.insert(
-            14,
-            (
-                Color::hex("#1e8a2d", POINT_ALPHA),
-                Color::hex("#4ac85a", POINT_ALPHA),
-            ),
-        );
+    /// (Core Color, Point Color) of the class. Classes beyond the palette get the
+    /// color most distinct from the ones in use.
+    pub fn class_color(&mut self, class: usize) -> (Color, Color) {
+        if let Some(colors) = self.class_colors.get(&class) {
+            return *colors;
+        }
 
-        class_colors.insert(
-            15,
-            (
-                Color::hex("#8a6a1e", POINT_ALPHA),
-                Color::hex("#c89a4a", POINT_ALPHA),
-            ),
-        );
-        class_colors
+        let point_color = self
+            .palette
+            .class_color(class, FILLING_COLOR)
+            .unwrap_or_else(|| {
+                let taken: Vec<Color> = self
+                    .class_colors
+                    .values()
+                    .map(|colors| colors.1)
+                    .chain(self.palette.colors())
+                    .collect();
+                distinct_colors(1, FILLING_COLOR, &taken)
+                    .pop()
+                    .unwrap_or(TEXT_COLOR)
+            });
+        let mut core_color = point_color;
+        core_color.make_core();
+
+        self.class_colors.insert(class, (core_color, point_color));
+        (core_color, point_color)
     }
 
+    /// Fails if the point was not drawn, see [`DrawingBackend::draw_point`]
     pub fn draw_point_with_class(
         &mut self,
         point: Point,
         class: usize,
         is_core: bool,
-        silent: bool,
-    ) {
-        let color = self
-            .class_colors
-            .entry(class)
-            .or_insert_with(|| {
-                let rand_point_color = Color::rand();
-                let mut rand_core_color = rand_point_color.clone();
-                rand_core_color.make_core();
-                (rand_point_color, rand_core_color)
-            })
-            .clone();
+    ) -> Result<()> {
+        let color = self.class_color(class);
+
+        let count = self.class_counts.entry(class).or_insert(0);
+        if !is_core {
+            *count += 1;
+        }
 
         if is_core {
-            self.draw_point_with_color(point, color.0, false, silent);
+            self.draw_point_with_color(point, color.0, false)
         } else {
-            self.draw_point_with_color(point, color.1, true, silent);
+            self.draw_point_with_color(point, color.1, true)
+        }
+    }
+
+    /// Point in the blend of the colors of the classes (counted from 1) by its
+    /// memberships, so points shared by several clusters get the colors in between.
+    /// The legend counts it for the class with the largest membership.
+    pub fn draw_point_with_memberships(&mut self, point: Point, memberships: &[f32]) -> Result<()> {
+        let colors: Vec<(Color, f32)> = (1..)
+            .zip(memberships)
+            .map(|(class, membership)| (self.class_color(class).1, *membership))
+            .collect();
+        let color = Color::blend(colors).ok_or(Error::NoMemberships(point))?;
+        if let Some((class, _)) = (1..)
+            .zip(memberships)
+            .max_by(|first, second| first.1.total_cmp(second.1))
+        {
+            *self.class_counts.entry(class).or_insert(0) += 1;
         }
+        self.draw_point_with_color(point, color, true)
     }
 
     pub fn draw_point_with_color(
@@ -283,252 +198,375 @@ impl<T: AsRef<Path>> Image<T> {
         point: Point,
         color: Color,
         do_not_override: bool,
-        silent: bool,
-    ) {
-        let x = point.x.floor() - self.rect.bottom_left.x;
-        let y = self.rect.top_right.y - point.y.floor();
-
-        let width_ratio = (self.inner.width() - IMAGE_PADDING) as f32 / self.rect.width();
-        let height_ratio = (self.inner.height() - IMAGE_PADDING) as f32 / self.rect.height();
-
-        let pixel = self.inner.get_pixel_mut_checked(
-            (x * width_ratio).floor() as u32 + IMAGE_PADDING / 2,
-            (y * height_ratio).floor() as u32 + IMAGE_PADDING / 2,
-        );
-        if let None = pixel {
-            if !silent {
-                eprintln!(
-                    "ПРЕДУПРЕЖДЕНИЕ: не удалось отрисовать пиксель для точки {} по коориданатам ({}; {}); Поле - {}; Ширина изображения - {}, Высота изображения - {}",
-                    point,
-                    (x * width_ratio).floor() as u32 + IMAGE_PADDING / 2,
-                    (y * height_ratio).floor() as u32 + IMAGE_PADDING / 2,
-                    self.rect,
-                    self.inner.width(),
-                    self.inner.height()
-                );
-            }
-            return;
-        }
-        let pixel = pixel.unwrap();
-        if do_not_override && pixel.0 != Color::hex(FILLING_COLOR, FILLING_ALPHA).inner.0 {
-            if !silent {
-                println!(
-                    "ПРЕДУПРЕЖДЕНИЕ: пиксель {} по коориданатам ({}; {}) накладывается на другой и отрисован не будет.",
-                    point,
-                    (x * width_ratio).floor() as u32 + IMAGE_PADDING / 2,
-                    (x * width_ratio).floor() as u32 + IMAGE_PADDING / 2,
-                );
-            }
-            return;
-        }
-        *pixel = color.inner();
+    ) -> Result<()> {
+        self.backend.draw_point(point, color, do_not_override)
     }
 
     pub fn draw_graph<K>(&mut self, func: &K, color: Option<Color>)
     where
         K: Fn(f32) -> Option<f32>,
     {
-        let color = color.unwrap_or_else(|| Color::hex("#b90000", 0.6));
+        let color = color.unwrap_or(GRAPH_COLOR);
+
+        // One sample per pixel column. The graph is split into separate lines where
+        // the function is not defined
+        let (units_x, _) = self.viewport.units_per_pixel();
+        let mut segment = Vec::new();
+        for column in 0..=self.viewport.width {
+            let x = self.rect().bottom_left.x + column as f32 * units_x;
+            match func(x) {
+                Some(y) => segment.push(Point::new(x, y)),
+                None => {
+                    self.backend.draw_polyline(&segment, color);
+                    segment.clear();
+                }
+            }
+        }
+        self.backend.draw_polyline(&segment, color);
+    }
 
-        let mut x = self.rect.bottom_left.x;
+    /// Straight segments through the points, in the graph color by default
+    pub fn draw_polyline(&mut self, points: &[Point], color: Option<Color>) {
+        self.backend
+            .draw_polyline(points, color.unwrap_or(GRAPH_COLOR));
+    }
 
-        while x <= self.rect.top_right.x {
-            let y = func(x);
-            if let Some(y) = y {
-                self.draw_point_with_color(Point::new(x, y), color, false, true);
+    /// Paints every part of the field in the pale color of the class of its nearest
+    /// core by the metric. Cores are (position; class), the regions lie under the points.
+    pub fn draw_regions(&mut self, cores: &[(Point, usize)], metric: &dyn Metric) {
+        if cores.is_empty() {
+            return;
+        }
+        let centers: Vec<Point> = cores.iter().map(|core| core.0).collect();
+        let (units_x, units_y) = self.viewport.units_per_pixel();
+        let cell_width = units_x * REGION_CELL_SIZE as f32;
+        let cell_height = units_y * REGION_CELL_SIZE as f32;
+        let columns = self.viewport.width.div_ceil(REGION_CELL_SIZE);
+        let rows = self.viewport.height.div_ceil(REGION_CELL_SIZE);
+        let (left, top) = (self.rect().bottom_left.x, self.rect().top_right.y);
+
+        for row in 0..rows {
+            let row_top = top - row as f32 * cell_height;
+            let middle = row_top - cell_height / 2.0;
+            // Cells of the same class next to each other are filled at once
+            let mut run: Option<(usize, u32)> = None;
+            for column in 0..=columns {
+                let class = (column < columns).then(|| {
+                    let x = left + (column as f32 + 0.5) * cell_width;
+                    cores[nearest(&Point::new(x, middle), &centers, metric).0].1
+                });
+                match run {
+                    Some((run_class, _)) if Some(run_class) == class => continue,
+                    Some((run_class, start)) => {
+                        let color = self
+                            .class_color(run_class)
+                            .1
+                            .mix(FILLING_COLOR, REGION_WHITENESS);
+                        let rect = Rectangle {
+                            bottom_left: Point::new(
+                                left + start as f32 * cell_width,
+                                row_top - cell_height,
+                            ),
+                            top_right: Point::new(left + column as f32 * cell_width, row_top),
+                        };
+                        self.backend.fill_region(&rect, color);
+                    }
+                    None => {}
+                }
+                run = class.map(|class| (class, column));
             }
-            x += GRAPH_X_STEP;
         }
     }
 
+    /// Segment with a head at `to`, in the graph color by default
+    pub fn draw_arrow(&mut self, from: Point, to: Point, color: Option<Color>) {
+        let color = color.unwrap_or(GRAPH_COLOR);
+        self.backend.draw_polyline(&[from, to], color);
+
+        // The head is measured in pixels, so it looks the same on any field
+        let (units_x, units_y) = self.viewport.units_per_pixel();
+        let (dx, dy) = ((to.x - from.x) / units_x, (to.y - from.y) / units_y);
+        let length = dx.hypot(dy);
+        if length == 0.0 {
+            return;
+        }
+        let size = ARROW_HEAD_SIZE.min(length / 2.0);
+        let tip = |angle: f32| {
+            let (sin, cos) = angle.sin_cos();
+            let back_x = -(dx * cos - dy * sin) / length * size;
+            let back_y = -(dx * sin + dy * cos) / length * size;
+            Point::new(to.x + back_x * units_x, to.y + back_y * units_y)
+        };
+        self.backend
+            .draw_polyline(&[tip(ARROW_HEAD_ANGLE), to, tip(-ARROW_HEAD_ANGLE)], color);
+    }
+
     pub fn draw_axis(&mut self, axis: Axis, symbol: Option<CharSymbol>, color: Option<Color>) {
-        let color = color.unwrap_or_else(|| Color::hex("#000000", 0.3));
+        let color = color.unwrap_or(AXIS_COLOR);
 
         match axis {
             Axis::X => {
-                let mut x = self.rect.bottom_left.x;
-                while x <= self.rect.top_right.x {
-                    self.draw_point_with_color(Point::new(x, 0.0), color, false, true);
-                    x += 1.0;
-                }
+                self.backend.draw_polyline(
+                    &[
+                        Point::new(self.rect().bottom_left.x, 0.0),
+                        Point::new(self.rect().top_right.x, 0.0),
+                    ],
+                    color,
+                );
 
-                self.draw_symbol(
-                    Point::new(self.rect.top_right.x - 7.0, 3.0),
+                let style = TextStyle {
+                    horizontal_align: HorizontalAlign::Right,
+                    offset: (-1, 3),
+                    ..self.text_style()
+                };
+                self.draw_axis_symbol(
+                    Point::new(self.rect().top_right.x, 0.0),
+                    symbol,
+                    'X',
                     color,
-                    symbol.unwrap_or(CharSymbol::get('X')),
-                )
+                    &style,
+                );
             }
             Axis::Y => {
-                let mut y = self.rect.bottom_left.y;
-                while y <= self.rect.top_right.y {
-                    self.draw_point_with_color(Point::new(0.0, y), color, false, true);
-                    y += 1.0;
-                }
+                self.backend.draw_polyline(
+                    &[
+                        Point::new(0.0, self.rect().bottom_left.y),
+                        Point::new(0.0, self.rect().top_right.y),
+                    ],
+                    color,
+                );
 
-                self.draw_symbol(
-                    Point::new(3.0, self.rect.top_right.y - 7.0),
+                let style = TextStyle {
+                    vertical_align: VerticalAlign::Top,
+                    offset: (3, -2),
+                    ..self.text_style()
+                };
+                self.draw_axis_symbol(
+                    Point::new(0.0, self.rect().top_right.y),
+                    symbol,
+                    'Y',
                     color,
-                    symbol.unwrap_or(CharSymbol::get('Y')),
+                    &style,
                 );
             }
             Axis::Other(func) => {
-                let symbol_point_y = func(self.rect.top_right.x - 5.0)
-                    .unwrap_or(0.0)
-                    .clamp(self.rect.bottom_left.y, self.rect.top_right.y)
-                    .sub(9.0)
-                    .clamp(self.rect.bottom_left.y, self.rect.top_right.y);
-                let symbol_point = Point::new(self.rect.top_right.x - 9.0, symbol_point_y);
-
-                self.draw_symbol(symbol_point, color, symbol.unwrap_or(CharSymbol::get('Z')));
+                let symbol_point_y =
+                    func(self.rect().top_right.x - 5.0 * self.viewport.units_per_pixel().0)
+                        .unwrap_or(0.0)
+                        .clamp(self.rect().bottom_left.y, self.rect().top_right.y);
+                let style = TextStyle {
+                    horizontal_align: HorizontalAlign::Right,
+                    vertical_align: VerticalAlign::Top,
+                    offset: (-3, -4),
+                    ..self.text_style()
+                };
+                self.draw_axis_symbol(
+                    Point::new(self.rect().top_right.x, symbol_point_y),
+                    symbol,
+                    'Z',
+                    color,
+                    &style,
+                );
 
                 self.draw_graph(&func, Some(color));
             }
         }
     }
 
-    pub fn write<K: AsRef<str>>(&mut self, bottom_left: Point, text: K, color: Option<Color>) {
-        let mut point = bottom_left.clone();
-        let color = color.unwrap_or_else(|| Color::hex("#000000", 1.0));
-        for char in text.as_ref().chars() {
-            let symbol = CharSymbol::get(char);
-            self.draw_symbol(point, color, symbol);
-            point.x += (symbol.width() + 1) as f32;
-        }
+    fn draw_axis_symbol(
+        &mut self,
+        anchor: Point,
+        symbol: Option<CharSymbol>,
+        default: char,
+        color: Color,
+        style: &TextStyle,
+    ) {
+        let content = match symbol {
+            Some(symbol) => LabelContent::Bitmap(symbol.into()),
+            None => LabelContent::Text(default.to_string()),
+        };
+        self.push_label(anchor, content, color, style);
     }
 
-    pub fn draw_symbol(&mut self, bottom_left: Point, color: Color, symbol: CharSymbol) {
-        let mut point = bottom_left.clone();
-        for line in symbol.0.lines().rev() {
-            point.x = bottom_left.x;
-            let mut touched_line = false;
-            for char in line.chars() {
-                if char != '\n' {
-                    touched_line = true;
-                }
-                if char != ' ' && char != '\n' {
-                    self.draw_point_with_color(point, color, false, true);
-                }
-                point.x += 1.0;
-            }
-            if touched_line {
-                point.y += 1.0;
-            }
+    /// Default text style of the image: glyph pixels are scaled with the output size.
+    pub fn text_style(&self) -> TextStyle {
+        TextStyle {
+            scale: text_scale(self.viewport.width, self.viewport.height),
+            ..Default::default()
         }
     }
 
-    pub fn save(&mut self) {
-        let resized_image;
-        if self.final_height != self.inner.height() || self.final_width != self.inner.width() {
-            resized_image = resize(
-                &self.inner,
-                self.final_width,
-                self.final_height,
-                FilterType::Nearest,
-            );
-
-            let result = resized_image.save(self.path.as_ref());
-            if let Err(err) = result {
-                eprintln!(
-                    "ОШИБКА: Не удалось сохранить изображение по пути {}: {}",
-                    self.path.as_ref().to_string_lossy(),
-                    err
-                );
-            }
-        } else {
-            let result = self.inner.save(self.path.as_ref());
-            if let Err(err) = result {
-                eprintln!(
-                    "ОШИБКА: Не удалось сохранить изображение по пути {}: {}",
-                    self.path.as_ref().to_string_lossy(),
-                    err
-                );
-            }
-        }
+    /// Writes text with its bottom left corner at the point using [`Image::text_style`].
+    pub fn write<K: AsRef<str>>(&mut self, bottom_left: Point, text: K, color: Option<Color>) {
+        let style = self.text_style();
+        self.write_styled(bottom_left, text, color, &style);
+    }
 
-        println!(
-            "Изображение сохранено по пути {}",
-            self.path.as_ref().to_string_lossy()
-        );
+    /// Writes text aligned to the anchor point. The text is drawn on the final picture,
+    /// so its size is set in output pixels and does not depend on the field scale.
+    pub fn write_styled<K: AsRef<str>>(
+        &mut self,
+        anchor: Point,
+        text: K,
+        color: Option<Color>,
+        style: &TextStyle,
+    ) {
+        let color = color.unwrap_or(TEXT_COLOR);
+        let content = LabelContent::Text(text.as_ref().to_string());
+        self.push_label(anchor, content, color, style);
     }
 
-    pub fn show(&self, command: &str) {
-        let result = Command::new(command).arg(self.path.as_ref()).spawn();
+    /// Size of the text in output pixels (width; height)
+    pub fn measure_text<K: AsRef<str>>(&self, text: K, style: &TextStyle) -> (u32, u32) {
+        self.font.measure(text.as_ref(), style)
+    }
 
-        if let Err(err) = result {
-            eprintln!(
-                "ОШИБКА: Не удалось открыть изображение по пути {} с помощью команды {}: {}",
-                self.path.as_ref().to_string_lossy(),
-                command,
-                err
-            );
+    /// Part of the field that the text would cover if written at the anchor point.
+    pub fn text_bounds<K: AsRef<str>>(
+        &self,
+        anchor: Point,
+        text: K,
+        style: &TextStyle,
+    ) -> Rectangle {
+        let (width, height) = self.measure_text(text, style);
+        let (offset_x, offset_y) = style.top_left_offset(width, height);
+        let (units_x, units_y) = self.viewport.units_per_pixel();
+
+        let left = anchor.x + offset_x as f32 * units_x;
+        let top = anchor.y - offset_y as f32 * units_y;
+        Rectangle {
+            bottom_left: Point::new(left, top - height as f32 * units_y),
+            top_right: Point::new(left + width as f32 * units_x, top),
         }
     }
-}
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct Color {
-    inner: Rgba<u8>,
-}
+    pub fn draw_symbol(&mut self, bottom_left: Point, color: Color, symbol: CharSymbol) {
+        self.draw_glyph(bottom_left, color, &symbol.into());
+    }
 
-impl Color {
-    pub fn hex(hex: &str, alpha: f32) -> Self {
-        if !hex.starts_with("#") {
-            eprintln!("ОШИБКА: Цвет {} не начинается с #!", hex);
-            exit(1);
-        }
-        if alpha < 0.0 || alpha > 1.0 {
-            eprintln!("ОШИБКА: Альфа-канал {} не лежит в пределах (0; 1)!", alpha);
-            exit(1);
-        }
-        let hex = u32::from_str_radix(&hex[1..hex.len()], 16).unwrap_or_else(|err| {
-            eprintln!(
-                "ОШИБКА: Не удалось распарсить {} как шестнадцатеричный цвет: {}",
-                hex, err
-            );
-            exit(1);
+    pub fn draw_glyph(&mut self, bottom_left: Point, color: Color, glyph: &Glyph) {
+        let style = self.text_style();
+        self.push_label(
+            bottom_left,
+            LabelContent::Bitmap(glyph.clone()),
+            color,
+            &style,
+        );
+    }
+
+    fn push_label(
+        &mut self,
+        anchor: Point,
+        content: LabelContent,
+        color: Color,
+        style: &TextStyle,
+    ) {
+        self.backend.draw_label(Label {
+            anchor,
+            content,
+            color,
+            style: style.clone(),
         });
+    }
 
-        let r = ((hex & 0xff0000) >> 16) as u8;
-        let g = ((hex & 0xff00) >> 8) as u8;
-        let b = (hex & 0xff) as u8;
-        let a = (alpha * 255.0) as u8;
+    /// Field shown on the data area of the picture
+    pub fn rect(&self) -> &Rectangle {
+        &self.viewport.rect
+    }
 
-        Self::rgba(r, g, b, a)
+    /// Field and its mapping onto the pixels of the data area
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
     }
 
-    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
-        let inner: Rgba<u8> = Rgba::from_slice(&[r, g, b, a]).to_owned();
-        Self { inner }
+    /// Font used by [`Image::write`], axis labels and the layout around the plot
+    pub fn set_font(&mut self, font: BitmapFont) {
+        self.font = font;
     }
 
-    pub fn make_core(&mut self) {
-        if self.inner.0[0] >= 30 {
-            self.inner.0[0] -= 30;
-        } else {
-            self.inner.0[0] = rand_isize_in_range(0, self.inner.0[0] as isize) as u8;
-        }
+    pub fn set_title<K: AsRef<str>>(&mut self, title: K) {
+        self.layout.title = Some(title.as_ref().to_string());
+    }
 
-        if self.inner.0[1] >= 30 {
-            self.inner.0[1] -= 30;
-        } else {
-            self.inner.0[1] = rand_isize_in_range(0, self.inner.0[1] as isize) as u8;
-        }
+    pub fn set_x_axis_title<K: AsRef<str>>(&mut self, title: K) {
+        self.layout.x_axis_title = Some(title.as_ref().to_string());
+    }
 
-        if self.inner.0[2] >= 30 {
-            self.inner.0[2] -= 30;
-        } else {
-            self.inner.0[2] = rand_isize_in_range(0, self.inner.0[2] as isize) as u8;
-        }
+    pub fn set_y_axis_title<K: AsRef<str>>(&mut self, title: K) {
+        self.layout.y_axis_title = Some(title.as_ref().to_string());
+    }
+
+    /// Label shown for the class in the legend (class ID is used by default)
+    pub fn set_class_label<K: AsRef<str>>(&mut self, class: usize, label: K) {
+        self.class_labels.insert(class, label.as_ref().to_string());
+    }
+
+    /// Anchor of the legend inside the data area, offsets are in pixels. `None` hides the legend.
+    pub fn set_legend(&mut self, position: Option<FixedPosition>) {
+        self.layout.legend = position;
+    }
+
+    fn legend_entries(&self) -> Vec<LegendEntry> {
+        self.class_counts
+            .iter()
+            .map(|(class, count)| LegendEntry {
+                color: self
+                    .class_colors
+                    .get(class)
+                    .map(|colors| colors.1)
+                    .unwrap_or(TEXT_COLOR),
+                label: self
+                    .class_labels
+                    .get(class)
+                    .cloned()
+                    .unwrap_or_else(|| class.to_string()),
+                count: *count,
+            })
+            .collect()
+    }
+
+    pub fn path(&self) -> &Path {
+        self.path.as_ref()
+    }
+
+    /// Current picture with the layout, as [`Image::save`] would write it.
+    /// `None` for the vector backends.
+    pub fn snapshot(&self) -> Option<RgbaImage> {
+        self.backend
+            .snapshot(&self.layout, &self.legend_entries(), &self.font)
     }
 
-    pub fn rand() -> Self {
-        let r = rand_isize_in_range(0, 255) as u8;
-        let g = rand_isize_in_range(0, 255) as u8;
-        let b = rand_isize_in_range(0, 255) as u8;
-        Self::rgba(r, g, b, (POINT_ALPHA * 255.0) as u8)
+    pub fn save(&mut self) -> Result<()> {
+        self.backend
+            .save(
+                self.path.as_ref(),
+                &self.layout,
+                &self.legend_entries(),
+                &self.font,
+            )
+            .map_err(|source| Error::ImageIo {
+                path: self.path.as_ref().to_path_buf(),
+                source,
+            })?;
+
+        println!(
+            "{}",
+            Message::ImageSaved {
+                path: &self.path.as_ref().to_string_lossy()
+            }
+        );
+        Ok(())
     }
 
-    pub fn inner(self) -> Rgba<u8> {
-        self.inner
+    pub fn show(&self, command: &str) -> Result<()> {
+        Command::new(command)
+            .arg(self.path.as_ref())
+            .spawn()
+            .map_err(|source| Error::Viewer {
+                command: command.to_string(),
+                path: self.path.as_ref().to_path_buf(),
+                source,
+            })?;
+        Ok(())
     }
 }
@@ -0,0 +1,322 @@
+use image::Rgba;
+
+use crate::{
+    error::{Error, Result},
+    messages::Message,
+    utils::rand_isize_in_range,
+};
+
+const POINT_ALPHA: f32 = 1.0;
+/// Lab lightness that cores lose compared to the points of their class
+const CORE_DARKENING: f32 = 12.0;
+
+/// D65 white point of CIE XYZ
+const WHITE_POINT: [f32; 3] = [0.95047, 1.0, 1.08883];
+
+/// Hue in degrees (0; 360), saturation and lightness in (0; 1)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsl {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+}
+
+/// Hue in degrees (0; 360), saturation and value in (0; 1)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+/// CIELAB under D65, lightness in (0; 100). Equal distances look about equally
+/// different, which makes it the space for perceptual operations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl Lab {
+    /// CIE76 color difference, about 2.3 is the smallest noticeable one
+    pub fn delta_e(self, other: Lab) -> f32 {
+        ((self.l - other.l).powi(2) + (self.a - other.a).powi(2) + (self.b - other.b).powi(2))
+            .sqrt()
+    }
+
+    /// `share` of the way to the other color
+    pub fn lerp(self, other: Lab, share: f32) -> Lab {
+        Lab {
+            l: self.l + (other.l - self.l) * share,
+            a: self.a + (other.a - self.a) * share,
+            b: self.b + (other.b - self.b) * share,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    inner: Rgba<u8>,
+}
+
+impl Color {
+    pub fn hex(hex: &str, alpha: f32) -> Result<Self> {
+        let invalid = |reason: String| Error::InvalidHexColor {
+            hex: hex.to_string(),
+            reason,
+        };
+        let Some(digits) = hex.strip_prefix('#') else {
+            return Err(invalid(Message::HexWithoutHash.to_string()));
+        };
+        if !(0.0..=1.0).contains(&alpha) {
+            return Err(Error::AlphaOutOfRange(alpha));
+        }
+        let hex = u32::from_str_radix(digits, 16).map_err(|err| invalid(err.to_string()))?;
+
+        let a = (alpha * 255.0) as u8;
+        let [_, r, g, b] = hex.to_be_bytes();
+        Ok(Self::rgba(r, g, b, a))
+    }
+
+    /// Fully opaque color written as `0xRRGGBB`
+    pub const fn opaque(rgb: u32) -> Self {
+        let [_, r, g, b] = rgb.to_be_bytes();
+        Self::rgba(r, g, b, 255)
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self {
+            inner: Rgba([r, g, b, a]),
+        }
+    }
+
+    /// Darker shade used for cores of the class
+    pub fn make_core(&mut self) {
+        *self = self.darken(CORE_DARKENING);
+    }
+
+    pub fn rand() -> Self {
+        let r = rand_isize_in_range(0, 255) as u8;
+        let g = rand_isize_in_range(0, 255) as u8;
+        let b = rand_isize_in_range(0, 255) as u8;
+        Self::rgba(r, g, b, (POINT_ALPHA * 255.0) as u8)
+    }
+
+    pub fn inner(self) -> Rgba<u8> {
+        self.inner
+    }
+
+    /// `#rrggbb` notation without the alpha channel
+    pub fn to_hex(self) -> String {
+        let [r, g, b, _] = self.inner.0;
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    /// Opacity in (0; 1)
+    pub fn alpha(self) -> f32 {
+        self.inner.0[3] as f32 / 255.0
+    }
+
+    /// Channels in (0; 1) with the sRGB gamma removed, alpha is ignored
+    pub fn to_linear_rgb(self) -> [f32; 3] {
+        let [r, g, b, _] = self.inner.0;
+        [r, g, b].map(|channel| {
+            let channel = channel as f32 / 255.0;
+            if channel <= 0.04045 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        })
+    }
+
+    /// WCAG relative luminance, 0 for black and 1 for white
+    pub fn relative_luminance(self) -> f32 {
+        let [r, g, b] = self.to_linear_rgb();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// WCAG contrast ratio in (1; 21), the order of the colors does not matter
+    pub fn contrast_ratio(self, other: Color) -> f32 {
+        let first = self.relative_luminance();
+        let second = other.relative_luminance();
+        (first.max(second) + 0.05) / (first.min(second) + 0.05)
+    }
+
+    /// `share` of the way from this color to the other one, channel by channel
+    pub fn mix(self, other: Color, share: f32) -> Color {
+        let share = share.clamp(0.0, 1.0);
+        let channels = std::array::from_fn(|i| {
+            let from = self.inner.0[i] as f32;
+            let to = other.inner.0[i] as f32;
+            (from + (to - from) * share).round() as u8
+        });
+        Self {
+            inner: Rgba(channels),
+        }
+    }
+
+    /// Average of the colors by their weights, mixed as light (in linear RGB).
+    /// `None` if the weights do not sum up to a positive number.
+    pub fn blend(colors: impl IntoIterator<Item = (Color, f32)>) -> Option<Color> {
+        let mut rgb = [0.0; 3];
+        let mut alpha = 0.0;
+        let mut total = 0.0;
+        for (color, weight) in colors {
+            let weight = weight.max(0.0);
+            for (sum, channel) in rgb.iter_mut().zip(color.to_linear_rgb()) {
+                *sum += weight * channel;
+            }
+            alpha += weight * color.inner.0[3] as f32;
+            total += weight;
+        }
+        if total <= 0.0 || !total.is_finite() {
+            return None;
+        }
+        Some(Self::from_linear_rgb(
+            rgb.map(|channel| channel / total),
+            (alpha / total).round() as u8,
+        ))
+    }
+
+    /// Channels from (0; 1) with the sRGB gamma applied, out of gamut ones are clamped
+    fn from_linear_rgb(rgb: [f32; 3], alpha: u8) -> Self {
+        let [r, g, b] = rgb.map(|channel| {
+            let channel = channel.clamp(0.0, 1.0);
+            let channel = if channel <= 0.0031308 {
+                channel * 12.92
+            } else {
+                1.055 * channel.powf(1.0 / 2.4) - 0.055
+            };
+            (channel * 255.0).round() as u8
+        });
+        Self::rgba(r, g, b, alpha)
+    }
+
+    /// Hue and chroma shared by HSL and HSV, with the largest and smallest channels
+    fn hue(self) -> (f32, f32, f32) {
+        let [r, g, b] = [0, 1, 2].map(|i| self.inner.0[i] as f32 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+
+        let hue = if chroma == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / chroma + 2.0)
+        } else {
+            60.0 * ((r - g) / chroma + 4.0)
+        };
+        (hue, max, min)
+    }
+
+    /// Color of the hue with the given chroma, `offset` is added to every channel
+    fn from_hue(hue: f32, chroma: f32, offset: f32, alpha: f32) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let [r, g, b, a] = [r + offset, g + offset, b + offset, alpha]
+            .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+        Self::rgba(r, g, b, a)
+    }
+
+    pub fn to_hsl(self) -> Hsl {
+        let (h, max, min) = self.hue();
+        let l = (max + min) / 2.0;
+        let s = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        Hsl { h, s, l }
+    }
+
+    /// Opacity is in (0; 1)
+    pub fn from_hsl(hsl: Hsl, alpha: f32) -> Self {
+        let s = hsl.s.clamp(0.0, 1.0);
+        let l = hsl.l.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Self::from_hue(hsl.h, chroma, l - chroma / 2.0, alpha)
+    }
+
+    pub fn to_hsv(self) -> Hsv {
+        let (h, max, min) = self.hue();
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        Hsv { h, s, v: max }
+    }
+
+    /// Opacity is in (0; 1)
+    pub fn from_hsv(hsv: Hsv, alpha: f32) -> Self {
+        let s = hsv.s.clamp(0.0, 1.0);
+        let v = hsv.v.clamp(0.0, 1.0);
+        let chroma = v * s;
+        Self::from_hue(hsv.h, chroma, v - chroma, alpha)
+    }
+
+    pub fn to_lab(self) -> Lab {
+        let [r, g, b] = self.to_linear_rgb();
+        let xyz = [
+            0.4124 * r + 0.3576 * g + 0.1805 * b,
+            0.2126 * r + 0.7152 * g + 0.0722 * b,
+            0.0193 * r + 0.1192 * g + 0.9505 * b,
+        ];
+        let [fx, fy, fz] = [0, 1, 2].map(|i| {
+            let t = xyz[i] / WHITE_POINT[i];
+            if t > 216.0 / 24389.0 {
+                t.cbrt()
+            } else {
+                (24389.0 / 27.0 * t + 16.0) / 116.0
+            }
+        });
+        Lab {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+
+    /// Colors outside of sRGB are clamped to it. Opacity is in (0; 1)
+    pub fn from_lab(lab: Lab, alpha: f32) -> Self {
+        let fy = (lab.l + 16.0) / 116.0;
+        let f = [fy + lab.a / 500.0, fy, fy - lab.b / 200.0];
+        let [x, y, z] = [0, 1, 2].map(|i| {
+            let t = if f[i] > 6.0 / 29.0 {
+                f[i].powi(3)
+            } else {
+                (116.0 * f[i] - 16.0) * 27.0 / 24389.0
+            };
+            t * WHITE_POINT[i]
+        });
+        let rgb = [
+            3.2406 * x - 1.5372 * y - 0.4986 * z,
+            -0.9689 * x + 1.8758 * y + 0.0415 * z,
+            0.0557 * x - 0.2040 * y + 1.0570 * z,
+        ];
+        Self::from_linear_rgb(rgb, (alpha.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    /// Raises the Lab lightness by `amount` out of 100, so that colors of different
+    /// hues change by the same visible amount. The alpha channel is kept.
+    pub fn lighten(self, amount: f32) -> Self {
+        let mut lab = self.to_lab();
+        lab.l = (lab.l + amount).clamp(0.0, 100.0);
+        let mut color = Self::from_lab(lab, 1.0);
+        color.inner.0[3] = self.inner.0[3];
+        color
+    }
+
+    /// See [`Color::lighten`]
+    pub fn darken(self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+}
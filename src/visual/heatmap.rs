@@ -0,0 +1,307 @@
+use std::{path::Path, process::Command};
+
+use image::RgbaImage;
+
+use crate::{
+    backend::raster::{draw_bitmap, draw_frame, fill_rect},
+    error::{Error, Result},
+    font::{BitmapFont, HorizontalAlign, Rotation, TextStyle, VerticalAlign},
+    messages::Message,
+    palette::Colormap,
+    visual::Color,
+};
+
+const BACKGROUND_COLOR: Color = Color::rgba(255, 255, 255, 255);
+const TEXT_COLOR: Color = Color::rgba(0, 0, 0, 255);
+const LIGHT_TEXT_COLOR: Color = Color::rgba(255, 255, 255, 255);
+const FRAME_COLOR: Color = Color::rgba(0, 0, 0, 255);
+
+/// Output pixels per glyph pixel
+const TEXT_SCALE: u32 = 2;
+const MIN_CELL_SIZE: u32 = 48;
+const CELL_PADDING: u32 = 8;
+const MARGIN: u32 = 8;
+
+/// Grid of values drawn as colored cells with the text of every cell on top,
+/// e.g. a confusion matrix. Rows go from top to bottom.
+#[derive(Clone)]
+pub struct Heatmap<T: AsRef<Path>> {
+    path: T,
+    values: Vec<Vec<f32>>,
+    cell_texts: Vec<Vec<String>>,
+    row_labels: Vec<String>,
+    column_labels: Vec<String>,
+    title: Option<String>,
+    row_title: Option<String>,
+    column_title: Option<String>,
+    colormap: Colormap,
+    font: BitmapFont,
+}
+
+impl<T: AsRef<Path>> Heatmap<T> {
+    /// Cells show the values themselves until [`Heatmap::set_cell_texts`] is called.
+    /// Colors span from the smallest to the largest value.
+    pub fn new(path: T, values: Vec<Vec<f32>>) -> Self {
+        let cell_texts = values
+            .iter()
+            .map(|row| row.iter().map(|value| value.to_string()).collect())
+            .collect();
+        let rows = values.len();
+        let columns = values.iter().map(Vec::len).max().unwrap_or(0);
+
+        Self {
+            path,
+            values,
+            cell_texts,
+            row_labels: (1..=rows).map(|row| row.to_string()).collect(),
+            column_labels: (1..=columns).map(|column| column.to_string()).collect(),
+            title: None,
+            row_title: None,
+            column_title: None,
+            colormap: Colormap::Viridis,
+            font: BitmapFont::builtin(),
+        }
+    }
+
+    pub fn set_cell_texts(&mut self, texts: Vec<Vec<String>>) {
+        self.cell_texts = texts;
+    }
+
+    pub fn set_row_labels(&mut self, labels: Vec<String>) {
+        self.row_labels = labels;
+    }
+
+    pub fn set_column_labels(&mut self, labels: Vec<String>) {
+        self.column_labels = labels;
+    }
+
+    pub fn set_title<K: AsRef<str>>(&mut self, title: K) {
+        self.title = Some(title.as_ref().to_string());
+    }
+
+    /// Title written vertically to the left of the row labels
+    pub fn set_row_title<K: AsRef<str>>(&mut self, title: K) {
+        self.row_title = Some(title.as_ref().to_string());
+    }
+
+    /// Title written above the column labels
+    pub fn set_column_title<K: AsRef<str>>(&mut self, title: K) {
+        self.column_title = Some(title.as_ref().to_string());
+    }
+
+    pub fn set_colormap(&mut self, colormap: Colormap) {
+        self.colormap = colormap;
+    }
+
+    pub fn set_font(&mut self, font: BitmapFont) {
+        self.font = font;
+    }
+
+    pub fn path(&self) -> &Path {
+        self.path.as_ref()
+    }
+
+    pub fn render(&self) -> RgbaImage {
+        let style = TextStyle {
+            scale: TEXT_SCALE,
+            ..TextStyle::default()
+        };
+        let measure = |text: &str| self.font.measure(text, &style);
+        let line_height = measure("0").1;
+
+        let rows = self.values.len() as u32;
+        let columns = self.values.iter().map(Vec::len).max().unwrap_or(0) as u32;
+        let cell_size = self
+            .cell_texts
+            .iter()
+            .flatten()
+            .chain(&self.column_labels)
+            .map(|text| measure(text).0 + 2 * CELL_PADDING)
+            .fold(MIN_CELL_SIZE, u32::max);
+        // The grid is at least as long as the titles along it
+        let title_width =
+            |title: &Option<String>| title.as_deref().map_or(0, |title| measure(title).0);
+        let cell_size = cell_size
+            .max(title_width(&self.column_title).div_ceil(columns.max(1)))
+            .max(title_width(&self.row_title).div_ceil(rows.max(1)));
+        let row_labels_width = self
+            .row_labels
+            .iter()
+            .map(|label| measure(label).0)
+            .max()
+            .unwrap_or(0);
+
+        let title_height =
+            |title: &Option<String>| title.as_ref().map_or(0, |_| line_height + MARGIN);
+        let grid_x = MARGIN + title_height(&self.row_title) + row_labels_width + MARGIN;
+        let grid_y = MARGIN
+            + title_height(&self.title)
+            + title_height(&self.column_title)
+            + line_height
+            + MARGIN;
+        let title_style = TextStyle {
+            bold: true,
+            ..style.clone()
+        };
+        let main_title_width = self
+            .title
+            .as_deref()
+            .map_or(0, |title| self.font.measure(title, &title_style).0);
+        let width = (grid_x + columns * cell_size + MARGIN).max(main_title_width + 2 * MARGIN);
+        let height = grid_y + rows * cell_size + MARGIN;
+
+        let mut canvas = RgbaImage::from_pixel(width, height, BACKGROUND_COLOR.inner());
+        let write = |canvas: &mut RgbaImage,
+                     anchor: (i32, i32),
+                     text: &str,
+                     style: &TextStyle,
+                     color: Color| {
+            let bitmap = self.font.render(text, style);
+            let (width, height) = self.font.measure(text, style);
+            let (dx, dy) = style.top_left_offset(width, height);
+            draw_bitmap(canvas, (anchor.0 + dx, anchor.1 + dy), &bitmap, color);
+        };
+        let centered = TextStyle {
+            horizontal_align: HorizontalAlign::Center,
+            vertical_align: VerticalAlign::Middle,
+            ..style.clone()
+        };
+        let grid_center_x = (grid_x + columns * cell_size / 2) as i32;
+
+        let mut y = MARGIN;
+        if let Some(title) = &self.title {
+            let style = TextStyle {
+                bold: true,
+                vertical_align: VerticalAlign::Top,
+                ..centered.clone()
+            };
+            write(
+                &mut canvas,
+                ((width / 2) as i32, y as i32),
+                title,
+                &style,
+                TEXT_COLOR,
+            );
+            y += line_height + MARGIN;
+        }
+        if let Some(title) = &self.column_title {
+            let style = TextStyle {
+                vertical_align: VerticalAlign::Top,
+                ..centered.clone()
+            };
+            write(
+                &mut canvas,
+                (grid_center_x, y as i32),
+                title,
+                &style,
+                TEXT_COLOR,
+            );
+        }
+        if let Some(title) = &self.row_title {
+            let style = TextStyle {
+                rotation: Rotation::CounterClockwise,
+                ..centered.clone()
+            };
+            let anchor = (
+                (MARGIN + line_height / 2) as i32,
+                (grid_y + rows * cell_size / 2) as i32,
+            );
+            write(&mut canvas, anchor, title, &style, TEXT_COLOR);
+        }
+
+        for (column, label) in self.column_labels.iter().enumerate().take(columns as usize) {
+            let anchor = (
+                (grid_x + column as u32 * cell_size + cell_size / 2) as i32,
+                (grid_y - MARGIN - line_height / 2) as i32,
+            );
+            write(&mut canvas, anchor, label, &centered, TEXT_COLOR);
+        }
+        for (row, label) in self.row_labels.iter().enumerate().take(rows as usize) {
+            let style = TextStyle {
+                horizontal_align: HorizontalAlign::Right,
+                ..centered.clone()
+            };
+            let anchor = (
+                (grid_x - MARGIN) as i32,
+                (grid_y + row as u32 * cell_size + cell_size / 2) as i32,
+            );
+            write(&mut canvas, anchor, label, &style, TEXT_COLOR);
+        }
+
+        let finite = || {
+            self.values
+                .iter()
+                .flatten()
+                .filter(|value| value.is_finite())
+        };
+        let min = finite().copied().fold(f32::INFINITY, f32::min);
+        let max = finite().copied().fold(f32::NEG_INFINITY, f32::max);
+        for (row, values) in self.values.iter().enumerate() {
+            for (column, &value) in values.iter().enumerate() {
+                let x = grid_x + column as u32 * cell_size;
+                let y = grid_y + row as u32 * cell_size;
+                let color = if value.is_finite() {
+                    self.colormap.map(value, min, max)
+                } else {
+                    BACKGROUND_COLOR
+                };
+                fill_rect(&mut canvas, x, y, cell_size, cell_size, color);
+
+                let Some(text) = self.cell_texts.get(row).and_then(|texts| texts.get(column))
+                else {
+                    continue;
+                };
+                // Whichever of black and white is easier to read on the cell
+                let text_color =
+                    if color.contrast_ratio(TEXT_COLOR) >= color.contrast_ratio(LIGHT_TEXT_COLOR) {
+                        TEXT_COLOR
+                    } else {
+                        LIGHT_TEXT_COLOR
+                    };
+                let anchor = ((x + cell_size / 2) as i32, (y + cell_size / 2) as i32);
+                write(&mut canvas, anchor, text, &centered, text_color);
+            }
+        }
+        if rows > 0 && columns > 0 {
+            draw_frame(
+                &mut canvas,
+                grid_x,
+                grid_y,
+                columns * cell_size,
+                rows * cell_size,
+                FRAME_COLOR,
+            );
+        }
+
+        canvas
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.render()
+            .save(self.path.as_ref())
+            .map_err(|err| Error::ImageIo {
+                path: self.path.as_ref().to_path_buf(),
+                source: std::io::Error::other(err),
+            })?;
+
+        println!(
+            "{}",
+            Message::ImageSaved {
+                path: &self.path.as_ref().to_string_lossy()
+            }
+        );
+        Ok(())
+    }
+
+    pub fn show(&self, command: &str) -> Result<()> {
+        Command::new(command)
+            .arg(self.path.as_ref())
+            .spawn()
+            .map_err(|source| Error::Viewer {
+                command: command.to_string(),
+                path: self.path.as_ref().to_path_buf(),
+                source,
+            })?;
+        Ok(())
+    }
+}
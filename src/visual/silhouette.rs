@@ -0,0 +1,112 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{
+    distance::Metric,
+    error::Result,
+    font::{HorizontalAlign, TextStyle, VerticalAlign},
+    geometry::{Point, Rectangle},
+    messages::Message,
+    metrics::internal::silhouette_samples,
+    utils::RoundToDecimalPlaces,
+    vector::Features,
+    visual::{Color, Image},
+};
+
+const PLOT_WIDTH: u32 = 800;
+const PLOT_HEIGHT: u32 = 600;
+/// Empty rows between the clusters
+const CLUSTER_GAP: f32 = 0.02;
+/// Free space on the sides of the bars, in units of the silhouette
+const SILHOUETTE_PADDING: f32 = 0.1;
+/// Silhouettes below this are drawn as negative bars, the axis always shows some of them
+const MIN_SHOWN_SILHOUETTE: f32 = -0.2;
+
+const ZERO_LINE_COLOR: Color = Color::rgba(0x00, 0x00, 0x00, 76);
+const MEAN_LINE_COLOR: Color = Color::rgba(0xb9, 0x00, 0x00, 200);
+
+/// Silhouette plot of a clustering: a horizontal bar with the silhouette of every
+/// point, grouped by cluster (from the top) and sorted from the largest value.
+/// Bars have the colors of the classes and the mean silhouette is a vertical line.
+pub fn silhouette_plot<T: AsRef<Path>, P: Features>(
+    path: T,
+    points: &[P],
+    labels: &[usize],
+    metric: &dyn Metric,
+) -> Result<Image<T>> {
+    let samples = silhouette_samples(points, labels, metric);
+    let mut clusters: BTreeMap<usize, Vec<f32>> = BTreeMap::new();
+    for (&label, &value) in labels.iter().zip(&samples) {
+        clusters.entry(label).or_default().push(value);
+    }
+    for values in clusters.values_mut() {
+        values.sort_by(|first, second| second.total_cmp(first));
+    }
+
+    let gap = (samples.len() as f32 * CLUSTER_GAP).ceil().max(1.0);
+    let rows = samples.len() as f32 + gap * (clusters.len() + 1) as f32;
+    let lowest = samples.iter().copied().fold(MIN_SHOWN_SILHOUETTE, f32::min);
+    let rect = Rectangle::new(
+        Point::new(lowest - SILHOUETTE_PADDING, 0.0),
+        Point::new(1.0 + SILHOUETTE_PADDING, rows),
+    )?;
+
+    let left = rect.bottom_left.x;
+    let mut drawing = Image::new(path, rect, true, Some(PLOT_WIDTH), Some(PLOT_HEIGHT));
+    drawing.set_title(Message::SilhouettePlotTitle.to_string());
+    drawing.set_x_axis_title(Message::SilhouetteAxis.to_string());
+    drawing.set_y_axis_title(Message::ClusterAxis.to_string());
+
+    let label_style = TextStyle {
+        horizontal_align: HorizontalAlign::Left,
+        vertical_align: VerticalAlign::Middle,
+        offset: (2, 0),
+        ..drawing.text_style()
+    };
+    let mut top = rows - gap;
+    for (&label, values) in &clusters {
+        let color = drawing.class_color(label).1;
+        for (row, &value) in values.iter().enumerate() {
+            let y = top - row as f32 - 0.5;
+            if value != 0.0 {
+                drawing.draw_polyline(&[Point::new(0.0, y), Point::new(value, y)], Some(color));
+            }
+        }
+
+        let middle = top - values.len() as f32 / 2.0;
+        drawing.write_styled(
+            Point::new(left, middle),
+            Message::ClassLabel { class: &label }.to_string(),
+            Some(color),
+            &label_style,
+        );
+        top -= values.len() as f32 + gap;
+    }
+
+    drawing.draw_polyline(
+        &[Point::new(0.0, 0.0), Point::new(0.0, rows)],
+        Some(ZERO_LINE_COLOR),
+    );
+    if !samples.is_empty() {
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        drawing.draw_polyline(
+            &[Point::new(mean, 0.0), Point::new(mean, rows)],
+            Some(MEAN_LINE_COLOR),
+        );
+        let style = TextStyle {
+            vertical_align: VerticalAlign::Top,
+            offset: (3, -2),
+            ..drawing.text_style()
+        };
+        drawing.write_styled(
+            Point::new(mean, rows),
+            Message::MeanSilhouette {
+                value: &mean.round_to_dp(3),
+            }
+            .to_string(),
+            Some(MEAN_LINE_COLOR),
+            &style,
+        );
+    }
+
+    Ok(drawing)
+}